@@ -0,0 +1,156 @@
+//! Random settings, block sizes and signals through the limiter. Nothing may
+//! panic, no NaN may come out, and with the output gain at or below 0 dB
+//! nothing may come out over the ceiling.
+
+#![no_main]
+
+use arbitrary::{Arbitrary, Unstructured};
+use libfuzzer_sys::fuzz_target;
+use limit2zero::engine::{db_to_gain, Algorithm, EnvelopeShape, Processor, Settings, Weighting};
+
+const SAMPLE_RATES: [f32; 4] = [22050.0, 44100.0, 48000.0, 96000.0];
+// the float maths of the gain stages, not a real overshoot
+const CEILING: f32 = 1.0 + 1e-5;
+
+// 0 to 1 from the fuzzer, mapped onto the params' ranges
+fn unit(u: &mut Unstructured) -> arbitrary::Result<f32> {
+    Ok(u16::arbitrary(u)? as f32 / u16::MAX as f32)
+}
+
+fn range(u: &mut Unstructured, min: f32, max: f32) -> arbitrary::Result<f32> {
+    Ok(min + (max - min) * unit(u)?)
+}
+
+fn shape(u: &mut Unstructured) -> arbitrary::Result<EnvelopeShape> {
+    let power = |u: &mut Unstructured| Ok(2f32.powf(range(u, -4.0, 4.0)?));
+    Ok(EnvelopeShape {
+        linearity: unit(u)?,
+        center: unit(u)?,
+        smooth_amount: unit(u)?,
+        polarity_in: unit(u)?,
+        polarity_out: unit(u)?,
+        power_in: power(u)?,
+        power_out: power(u)?,
+        sm_polarity_in: unit(u)?,
+        sm_polarity_out: unit(u)?,
+        sm_power_in: power(u)?,
+        sm_power_out: power(u)?,
+    })
+}
+
+fn settings(u: &mut Unstructured) -> arbitrary::Result<Settings> {
+    Ok(Settings {
+        drive: db_to_gain(range(u, 0.0, 60.0)?),
+        trim: range(u, -1.0, 0.0)?,
+        output_gain: db_to_gain(range(u, -24.0, 24.0)?),
+        lookahead_ms: range(u, 0.0, 50.0)?,
+        lookahead_accuracy: u.int_in_range(1..=16)?,
+        lookahead_glide: bool::arbitrary(u)?,
+        lookahead_offset_ms: range(u, -5.0, 5.0)?,
+        live: bool::arbitrary(u)?,
+        dc_block: bool::arbitrary(u)?,
+        detector_tilt_db: range(u, -6.0, 6.0)?,
+        detector_weighting: *u.choose(&[Weighting::Flat, Weighting::A, Weighting::K])?,
+        detector_low_db: range(u, -12.0, 12.0)?,
+        detector_mid_db: range(u, -12.0, 12.0)?,
+        detector_mid_hz: range(u, 200.0, 8000.0)?,
+        detector_high_db: range(u, -12.0, 12.0)?,
+        detector_listen: bool::arbitrary(u)?,
+        algorithm: *u.choose(&[Algorithm::Envelope, Algorithm::MovingMin])?,
+        leveling: bool::arbitrary(u)?,
+        leveling_target_db: range(u, -36.0, 0.0)?,
+        leveling_range_db: range(u, 0.0, 12.0)?,
+        attack_amt: range(u, 0.0, 5.0)?,
+        attack_overshoot: range(u, 0.0, 0.5)?,
+        hold_ms: range(u, 0.0, 1000.0)?,
+        release_ms: range(u, 0.0, 3000.0)?,
+        hold_level: unit(u)?,
+        true_hold: bool::arbitrary(u)?,
+        release_start: unit(u)?,
+        release_scaling: bool::arbitrary(u)?,
+        gr_slew_db_per_ms: if bool::arbitrary(u)? {
+            Some(range(u, 0.05, 20.0)?)
+        } else {
+            None
+        },
+        freeze: bool::arbitrary(u)?,
+        true_peak: bool::arbitrary(u)?,
+        true_peak_catcher: bool::arbitrary(u)?,
+        inverse: bool::arbitrary(u)?,
+        stereo_link: unit(u)?,
+        width: range(u, 0.0, 2.0)?,
+        balance_guard_db: if bool::arbitrary(u)? {
+            Some(range(u, 0.0, 12.0)?)
+        } else {
+            None
+        },
+        compensate: bool::arbitrary(u)?,
+        measured_compensation_db: None,
+        bypass: bool::arbitrary(u)?,
+        attack: shape(u)?,
+        release: shape(u)?,
+    })
+}
+
+// raw floats, NaN and infinity included, with the signals that tend to
+// break DSP mixed in
+fn sample(u: &mut Unstructured, dc: f32) -> arbitrary::Result<f32> {
+    Ok(match u.int_in_range(0..=7)? {
+        0 => f32::NAN,
+        1 => f32::INFINITY * if bool::arbitrary(u)? { 1.0 } else { -1.0 },
+        2 => f32::MIN_POSITIVE * unit(u)?,
+        3 => dc,
+        4 => f32::arbitrary(u)?,
+        _ => range(u, -4.0, 4.0)?,
+    })
+}
+
+fn run(u: &mut Unstructured) -> arbitrary::Result<()> {
+    let channels = u.int_in_range(1..=2)?;
+    let sample_rate = *u.choose(&SAMPLE_RATES)?;
+    let mut settings = settings(u)?;
+    // bypassing fades over to the dry signal, which can be over the ceiling,
+    // so it's either on or off for a whole run
+    let bypass = settings.bypass;
+    let mut processor = Processor::new(channels, sample_rate, &settings);
+    // the longest lookahead with the longest offset
+    processor.reserve_lookahead(55.0);
+
+    while !u.is_empty() {
+        // settings change between blocks, like automation does
+        if bool::arbitrary(u)? {
+            settings = Settings {
+                bypass,
+                ..self::settings(u)?
+            };
+        }
+        processor.update_lookahead(&settings);
+
+        let block_len = u.int_in_range(1..=512)?;
+        let dc = range(u, -2.0, 2.0)?;
+        let mut block = Vec::with_capacity(block_len * channels);
+        for _ in 0..block_len * channels {
+            block.push(sample(u, dc)?);
+        }
+
+        processor.process(&mut block, &settings);
+
+        // listening to the detector skips the limiting altogether
+        let ceiling_holds =
+            settings.output_gain <= 1.0 && !settings.bypass && !settings.detector_listen;
+        for output in &block {
+            assert!(!output.is_nan(), "NaN out with {settings:?}");
+            if ceiling_holds {
+                assert!(
+                    output.abs() <= CEILING,
+                    "{output} is over the ceiling with {settings:?}"
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+fuzz_target!(|data: &[u8]| {
+    let _ = run(&mut Unstructured::new(data));
+});
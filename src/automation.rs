@@ -0,0 +1,125 @@
+use crate::engine::{EnvelopeShape, Settings};
+
+// drive, trim, output gain, stereo link, width and attack amount, then both
+// curves
+const FIELDS: usize = 6 + 2 * 11;
+
+/// Ramps the continuous settings the limiter follows per sample towards the
+/// params, over the same time for all of them. Short times follow
+/// automation tightly, long ones trade that for no zipper noise on heavy
+/// automation. Overrides from MIDI and OSC ramp the same way.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SettingsSmoother {
+    ramps: [Ramp; FIELDS],
+    // the first settings after a reset are taken as they are
+    primed: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Ramp {
+    current: f32,
+    target: f32,
+    step: f32,
+    steps_left: u32,
+}
+
+impl Ramp {
+    // restarts from wherever it is whenever the target moves
+    fn next(&mut self, target: f32, steps: u32) -> f32 {
+        if target != self.target {
+            self.target = target;
+            self.steps_left = steps;
+            self.step = (target - self.current) / steps.max(1) as f32;
+        }
+        match self.steps_left {
+            0 => self.current = self.target,
+            1 => {
+                self.steps_left = 0;
+                self.current = self.target;
+            }
+            _ => {
+                self.steps_left -= 1;
+                self.current += self.step;
+            }
+        }
+        self.current
+    }
+
+    fn jump(&mut self, value: f32) {
+        *self = Ramp {
+            current: value,
+            target: value,
+            ..Ramp::default()
+        };
+    }
+}
+
+impl SettingsSmoother {
+    /// Jumps straight to the next settings instead of ramping to them.
+    pub fn reset(&mut self) {
+        self.primed = false;
+    }
+
+    /// Moves `settings`, the params as they are, to where the ramps are for
+    /// this sample, `steps` samples being a full ramp. True while anything
+    /// is still moving, the envelopes have to be built again then.
+    pub fn apply(&mut self, settings: &mut Settings, steps: u32) -> bool {
+        let primed = std::mem::replace(&mut self.primed, true);
+        let mut ramps = self.ramps.iter_mut();
+        let mut moving = false;
+        for_each_field(settings, |value| {
+            let Some(ramp) = ramps.next() else {
+                return;
+            };
+            if !primed {
+                ramp.jump(*value);
+                return;
+            }
+            let before = ramp.current;
+            *value = ramp.next(*value, steps);
+            moving |= *value != before;
+        });
+        moving
+    }
+}
+
+fn for_each_field(settings: &mut Settings, mut visit: impl FnMut(&mut f32)) {
+    visit(&mut settings.drive);
+    visit(&mut settings.trim);
+    visit(&mut settings.output_gain);
+    visit(&mut settings.stereo_link);
+    visit(&mut settings.width);
+    visit(&mut settings.attack_amt);
+    for shape in [&mut settings.attack, &mut settings.release] {
+        shape_fields(shape).into_iter().for_each(&mut visit);
+    }
+}
+
+fn shape_fields(shape: &mut EnvelopeShape) -> [&mut f32; 11] {
+    let EnvelopeShape {
+        linearity,
+        center,
+        smooth_amount,
+        polarity_in,
+        polarity_out,
+        power_in,
+        power_out,
+        sm_polarity_in,
+        sm_polarity_out,
+        sm_power_in,
+        sm_power_out,
+    } = shape;
+    [
+        linearity,
+        center,
+        smooth_amount,
+        polarity_in,
+        polarity_out,
+        power_in,
+        power_out,
+        sm_polarity_in,
+        sm_polarity_out,
+        sm_power_in,
+        sm_power_out,
+    ]
+}
@@ -0,0 +1,196 @@
+//! Runs limit2zero over a WAV file without a host.
+//!
+//! ```shell
+//! cargo run --release --features cli --bin limit2zero-cli -- in.wav out.wav --drive 6 --release 80
+//! ```
+
+use std::env;
+use std::process::ExitCode;
+
+use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+use limit2zero::engine::{db_to_gain, Algorithm, Processor, Settings, Weighting};
+
+const BLOCK_SIZE: usize = 512;
+
+const USAGE: &str = "\
+usage: limit2zero-cli <input.wav> <output.wav> [options]
+
+options (defaults are the plugin's):
+  --drive <dB>          gain into the limiter
+  --trim <dB>           gain after the limiter
+  --output <dB>         output gain
+  --lookahead <ms>
+  --accuracy <1-16>     lookahead accuracy
+  --la-offset <ms>      how much further ahead the right channel looks
+  --live <on|off>       no lookahead, true peak or catcher, no delay
+  --dc-block <on|off>   5 Hz high pass on the input
+  --algorithm <name>    envelope or moving-min
+  --weighting <curve>   detector weighting, flat, a or k
+  --tilt <dB>           detector tilt, positive reacts more to highs
+  --eq-low <dB>         detector EQ low shelf
+  --eq-mid <dB>         detector EQ peak
+  --eq-mid-freq <Hz>    where the detector EQ peak sits
+  --eq-high <dB>        detector EQ high shelf
+  --listen <on|off>     put out what the detector sees
+  --attack <amount>     attack amount
+  --bite <0-0.5>        attack overshoot
+  --hold <ms>
+  --release <ms>
+  --hold-level <0-1>    share of the peak reduction held
+  --true-hold <on|off>  hold at the floor the attack reached
+  --release-start <0-1> share of the peak reduction released from
+  --rel-scale <on|off>  release slower after heavy limiting
+  --slew <dB/ms>        fastest the reduction may change
+  --true-peak <on|off>  keep the peaks between samples under the ceiling
+  --catcher <on|off>    a fast true peak limiter after the main one
+  --inverse <on|off>    boost where the limiter would reduce
+  --link <0-1>          stereo link
+  --width <0-2>         stereo width after limiting
+  --guard <dB>          most the channels' reductions may differ by
+  --compensate <on|off> drive compensation
+";
+
+fn main() -> ExitCode {
+    match run(env::args().skip(1).collect()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}\n\n{USAGE}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: Vec<String>) -> Result<(), String> {
+    let mut paths = Vec::new();
+    let mut settings = Settings::default();
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let Some(flag) = arg.strip_prefix("--") else {
+            paths.push(arg);
+            continue;
+        };
+        if flag == "help" {
+            print!("{USAGE}");
+            return Ok(());
+        }
+
+        let value = args
+            .next()
+            .ok_or_else(|| format!("--{flag} needs a value"))?;
+        let number = || {
+            value
+                .parse::<f32>()
+                .map_err(|_| format!("--{flag}: '{value}' isn't a number"))
+        };
+        let switch = || match value.as_str() {
+            "on" => Ok(true),
+            "off" => Ok(false),
+            _ => Err(format!("--{flag} takes on or off, not '{value}'")),
+        };
+
+        match flag {
+            "drive" => settings.drive = db_to_gain(number()?),
+            "trim" => settings.trim = number()?,
+            "output" => settings.output_gain = db_to_gain(number()?),
+            "lookahead" => settings.lookahead_ms = number()?.max(0.0),
+            "live" => settings.live = switch()?,
+            "dc-block" => settings.dc_block = switch()?,
+            "la-offset" => settings.lookahead_offset_ms = number()?.clamp(-5.0, 5.0),
+            "accuracy" => settings.lookahead_accuracy = (number()? as i32).clamp(1, 16),
+            "algorithm" => {
+                settings.algorithm = match value.as_str() {
+                    "envelope" => Algorithm::Envelope,
+                    "moving-min" => Algorithm::MovingMin,
+                    _ => {
+                        return Err(format!(
+                            "--{flag} takes envelope or moving-min, not '{value}'"
+                        ))
+                    }
+                }
+            }
+            "weighting" => {
+                settings.detector_weighting = match value.as_str() {
+                    "flat" => Weighting::Flat,
+                    "a" => Weighting::A,
+                    "k" => Weighting::K,
+                    _ => return Err(format!("--{flag} takes flat, a or k, not '{value}'")),
+                }
+            }
+            "tilt" => settings.detector_tilt_db = number()?.clamp(-6.0, 6.0),
+            "eq-low" => settings.detector_low_db = number()?.clamp(-12.0, 12.0),
+            "eq-mid" => settings.detector_mid_db = number()?.clamp(-12.0, 12.0),
+            "eq-mid-freq" => settings.detector_mid_hz = number()?.clamp(200.0, 8000.0),
+            "eq-high" => settings.detector_high_db = number()?.clamp(-12.0, 12.0),
+            "listen" => settings.detector_listen = switch()?,
+            "attack" => settings.attack_amt = number()?,
+            "bite" => settings.attack_overshoot = number()?.clamp(0.0, 0.5),
+            "hold" => settings.hold_ms = number()?.max(0.0),
+            "release" => settings.release_ms = number()?.max(0.0),
+            "hold-level" => settings.hold_level = number()?.clamp(0.0, 1.0),
+            "true-hold" => settings.true_hold = switch()?,
+            "release-start" => settings.release_start = number()?.clamp(0.0, 1.0),
+            "rel-scale" => settings.release_scaling = switch()?,
+            "slew" => settings.gr_slew_db_per_ms = Some(number()?.max(0.01)),
+            "true-peak" => settings.true_peak = switch()?,
+            "catcher" => settings.true_peak_catcher = switch()?,
+            "inverse" => settings.inverse = switch()?,
+            "link" => settings.stereo_link = number()?.clamp(0.0, 1.0),
+            "width" => settings.width = number()?.clamp(0.0, 2.0),
+            "guard" => settings.balance_guard_db = Some(number()?.max(0.0)),
+            "compensate" => settings.compensate = switch()?,
+            _ => return Err(format!("unknown option --{flag}")),
+        }
+    }
+
+    let [input, output] = paths.as_slice() else {
+        return Err("expected an input and an output file".to_string());
+    };
+
+    let mut reader = WavReader::open(input).map_err(|err| format!("{input}: {err}"))?;
+    let spec = reader.spec();
+    let channels = spec.channels as usize;
+
+    // every format is read as float
+    let mut audio: Vec<f32> = match spec.sample_format {
+        SampleFormat::Float => reader.samples::<f32>().collect::<Result<_, _>>(),
+        SampleFormat::Int => {
+            let scale = (1u64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|sample| sample.map(|sample| sample as f32 / scale))
+                .collect()
+        }
+    }
+    .map_err(|err| format!("{input}: {err}"))?;
+
+    let mut processor = Processor::new(channels, spec.sample_rate as f32, &settings);
+    processor.update_lookahead(&settings);
+    let catcher_settings = settings.catcher();
+    let mut catcher = Processor::new(channels, spec.sample_rate as f32, &catcher_settings);
+    catcher.update_lookahead(&catcher_settings);
+
+    // the lookahead delays everything, so push the tail out with silence and
+    // drop as much from the front
+    let delay = (processor.delay_samples() + catcher.delay_samples()) * channels;
+    let len = audio.len();
+    audio.resize(len + delay, 0.0);
+    for block in audio.chunks_mut(BLOCK_SIZE * channels) {
+        processor.process(block, &settings);
+        catcher.process(block, &catcher_settings);
+    }
+
+    let spec = WavSpec {
+        channels: spec.channels,
+        sample_rate: spec.sample_rate,
+        bits_per_sample: 32,
+        sample_format: SampleFormat::Float,
+    };
+    let mut writer = WavWriter::create(output, spec).map_err(|err| format!("{output}: {err}"))?;
+    for sample in &audio[delay..] {
+        writer
+            .write_sample(*sample)
+            .map_err(|err| format!("{output}: {err}"))?;
+    }
+    writer.finalize().map_err(|err| format!("{output}: {err}"))
+}
@@ -0,0 +1,112 @@
+use nih_plug::prelude::*;
+use nih_plug_vizia::ViziaState;
+use std::sync::Arc;
+
+use crate::engine::ClipSettings;
+use crate::{drive_param, metadata, output_gain_param, trim_param};
+
+mod editor;
+
+/// The limiter's drive and ceiling on their own: a hard clipper at 0 dB
+/// without lookahead, so it adds no latency.
+pub struct Clip2zero {
+    params: Arc<Clip2zeroParams>,
+}
+
+#[derive(Params)]
+struct Clip2zeroParams {
+    #[persist = "editor-state"]
+    editor_state: Arc<ViziaState>,
+
+    #[id = "drive"]
+    pub drive: FloatParam,
+
+    #[id = "trim"]
+    pub trim: FloatParam,
+
+    #[id = "output_gain"]
+    pub output_gain: FloatParam,
+
+    #[id = "compensate"]
+    pub compensate: BoolParam,
+}
+
+impl Default for Clip2zero {
+    fn default() -> Self {
+        Self {
+            params: Arc::new(Clip2zeroParams::default()),
+        }
+    }
+}
+
+impl Default for Clip2zeroParams {
+    fn default() -> Self {
+        Self {
+            editor_state: editor::default_state(),
+            drive: drive_param(util::db_to_gain(0.0)),
+            trim: trim_param(0.0),
+            output_gain: output_gain_param(util::db_to_gain(0.0)),
+            compensate: BoolParam::new("Gain Compensation", false),
+        }
+    }
+}
+
+impl Plugin for Clip2zero {
+    const NAME: &'static str = metadata::CLIP2ZERO.name;
+    const VENDOR: &'static str = metadata::VENDOR;
+    const URL: &'static str = metadata::URL;
+    const EMAIL: &'static str = metadata::EMAIL;
+
+    const VERSION: &'static str = metadata::VERSION;
+
+    const AUDIO_IO_LAYOUTS: &'static [AudioIOLayout] = &[AudioIOLayout {
+        main_input_channels: NonZeroU32::new(2),
+        main_output_channels: NonZeroU32::new(2),
+
+        aux_input_ports: &[],
+        aux_output_ports: &[],
+
+        names: PortNames::const_default(),
+    }];
+
+    const MIDI_INPUT: MidiConfig = MidiConfig::None;
+    const MIDI_OUTPUT: MidiConfig = MidiConfig::None;
+
+    const SAMPLE_ACCURATE_AUTOMATION: bool = true;
+
+    type SysExMessage = ();
+    type BackgroundTask = ();
+
+    fn params(&self) -> Arc<dyn Params> {
+        self.params.clone()
+    }
+
+    fn editor(&mut self, _async_executor: AsyncExecutor<Self>) -> Option<Box<dyn Editor>> {
+        editor::create(self.params.clone(), self.params.editor_state.clone())
+    }
+
+    fn process(
+        &mut self,
+        buffer: &mut Buffer,
+        _aux: &mut AuxiliaryBuffers,
+        _context: &mut impl ProcessContext<Self>,
+    ) -> ProcessStatus {
+        let mut settings = ClipSettings {
+            drive: self.params.drive.value(),
+            trim: self.params.trim.value(),
+            output_gain: self.params.output_gain.value(),
+            compensate: self.params.compensate.value(),
+        };
+
+        for channel_samples in buffer.iter_samples() {
+            settings.output_gain = self.params.output_gain.smoothed.next();
+            for sample in channel_samples {
+                *sample = settings.process(*sample);
+            }
+        }
+
+        ProcessStatus::Normal
+    }
+}
+
+metadata::impl_formats!(Clip2zero, metadata::CLIP2ZERO);
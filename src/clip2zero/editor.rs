@@ -0,0 +1,58 @@
+use nih_plug::prelude::Editor;
+use nih_plug_vizia::vizia::prelude::*;
+use nih_plug_vizia::widgets::*;
+use nih_plug_vizia::{assets, create_vizia_editor, ViziaState, ViziaTheming};
+use std::sync::Arc;
+
+use super::Clip2zeroParams;
+
+#[derive(Lens)]
+struct Data {
+    params: Arc<Clip2zeroParams>,
+}
+
+impl Model for Data {}
+
+pub(super) fn default_state() -> Arc<ViziaState> {
+    ViziaState::new(|| (300, 320))
+}
+
+// just the four params, the limiter's editor is the one with the meters
+pub(super) fn create(
+    params: Arc<Clip2zeroParams>,
+    editor_state: Arc<ViziaState>,
+) -> Option<Box<dyn Editor>> {
+    create_vizia_editor(editor_state, ViziaTheming::Custom, move |cx, _| {
+        assets::register_noto_sans_light(cx);
+        assets::register_noto_sans_thin(cx);
+
+        Data {
+            params: params.clone(),
+        }
+        .build(cx);
+
+        VStack::new(cx, |cx| {
+            Label::new(cx, "Clip2Zero")
+                .font_family(vec![FamilyOwned::Name(String::from(assets::NOTO_SANS))])
+                .font_weight(FontWeightKeyword::Thin)
+                .font_size(30.0)
+                .height(Pixels(50.0))
+                .child_top(Stretch(1.0))
+                .child_bottom(Pixels(0.0));
+
+            Label::new(cx, "Drive");
+            ParamSlider::new(cx, Data::params, |params| &params.drive);
+            Label::new(cx, "Trim");
+            ParamSlider::new(cx, Data::params, |params| &params.trim);
+            Label::new(cx, "Output Gain");
+            ParamSlider::new(cx, Data::params, |params| &params.output_gain);
+            Label::new(cx, "Gain Compensation");
+            ParamButton::new(cx, Data::params, |params| &params.compensate);
+        })
+        .row_between(Pixels(0.0))
+        .child_left(Stretch(1.0))
+        .child_right(Stretch(1.0));
+
+        ResizeHandle::new(cx);
+    })
+}
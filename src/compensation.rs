@@ -0,0 +1,136 @@
+use atomic_float::AtomicF32;
+use std::sync::atomic::Ordering;
+
+use crate::engine::{db_to_gain, SampleDB};
+use crate::loudness::{LoudnessAnalyzer, LoudnessCapture, LoudnessSnapshot};
+
+// how far the compensation moves towards the measured difference with every
+// 100 ms block, slow enough that it doesn't pump along with the music
+const FOLLOW: f32 = 0.05;
+const MIN_DB: f32 = -60.0;
+// gain match may turn up as well, but not without bound on a limiter that's
+// barely letting anything through
+const MAX_MATCH_DB: f32 = 12.0;
+
+/// Gain compensation from the measured loudness of the delayed input and the
+/// limited output, so switching compensation on A/Bs at the same loudness.
+/// The output is measured before trim and output gain, those stay the
+/// user's to set.
+pub(crate) struct CompensationSnapshot {
+    input: LoudnessSnapshot,
+    output: LoudnessSnapshot,
+    /// What to add to the output, in dB.
+    pub db: AtomicF32,
+    /// What brings the output to the input's loudness, up or down, in dB.
+    /// Gain match uses it in place of trim and output gain.
+    pub matched_db: AtomicF32,
+}
+
+impl Default for CompensationSnapshot {
+    fn default() -> Self {
+        Self {
+            input: LoudnessSnapshot::default(),
+            output: LoudnessSnapshot::default(),
+            db: AtomicF32::new(0.0),
+            matched_db: AtomicF32::new(0.0),
+        }
+    }
+}
+
+/// Collects both sides on the audio thread, see [`LoudnessCapture`].
+pub(crate) struct CompensationCapture {
+    input: LoudnessCapture,
+    output: LoudnessCapture,
+    frame: [Vec<f32>; 2],
+}
+
+impl CompensationCapture {
+    pub fn new(channels: usize, sample_rate: f32, snapshot: &CompensationSnapshot) -> Self {
+        Self {
+            input: LoudnessCapture::new(channels, sample_rate, &snapshot.input),
+            output: LoudnessCapture::new(channels, sample_rate, &snapshot.output),
+            frame: [vec![0.0; channels], vec![0.0; channels]],
+        }
+    }
+
+    /// Adds a frame coming out of the lookahead along with the reduction
+    /// applied to it, true when there's a block to analyze.
+    pub fn push(
+        &mut self,
+        delayed: &[SampleDB],
+        reduction: &[f32],
+        snapshot: &CompensationSnapshot,
+    ) -> bool {
+        let [input, output] = &mut self.frame;
+        for (i, sample) in delayed.iter().enumerate() {
+            input[i] = sample.dry;
+            output[i] = sample.sample * db_to_gain(reduction[i]);
+        }
+        let input_ready = self.input.push(input, &snapshot.input);
+        let output_ready = self.output.push(output, &snapshot.output);
+        input_ready || output_ready
+    }
+
+    pub fn reset(&mut self) {
+        self.input.reset();
+        self.output.reset();
+    }
+}
+
+/// Short-term loudness of both sides, run as a background task.
+pub(crate) struct CompensationAnalyzer {
+    input: LoudnessAnalyzer,
+    output: LoudnessAnalyzer,
+}
+
+impl Default for CompensationAnalyzer {
+    fn default() -> Self {
+        Self {
+            input: LoudnessAnalyzer::short_term(),
+            output: LoudnessAnalyzer::short_term(),
+        }
+    }
+}
+
+impl CompensationAnalyzer {
+    /// Starts over from `db`, where the compensation stays until there's
+    /// something to measure.
+    pub fn reset(
+        &mut self,
+        channels: usize,
+        sample_rate: f32,
+        db: f32,
+        snapshot: &CompensationSnapshot,
+    ) {
+        self.input.reset(channels, sample_rate, &snapshot.input);
+        self.output.reset(channels, sample_rate, &snapshot.output);
+        snapshot.db.store(db, Ordering::Relaxed);
+        snapshot.matched_db.store(db, Ordering::Relaxed);
+    }
+
+    pub fn analyze(&mut self, snapshot: &CompensationSnapshot) {
+        self.input.analyze(&snapshot.input);
+        self.output.analyze(&snapshot.output);
+
+        // silence on either side says nothing about the difference
+        let input = snapshot.input.lufs.load(Ordering::Relaxed);
+        let output = snapshot.output.lufs.load(Ordering::Relaxed);
+        if !input.is_finite() || !output.is_finite() {
+            return;
+        }
+
+        // only ever turning down, like drive's compensation, so the ceiling
+        // still holds. Output the limiter made quieter stays quieter.
+        let target = (input - output).clamp(MIN_DB, 0.0);
+        let db = snapshot.db.load(Ordering::Relaxed);
+        snapshot
+            .db
+            .store(db + (target - db) * FOLLOW, Ordering::Relaxed);
+
+        let target = (input - output).clamp(MIN_DB, MAX_MATCH_DB);
+        let db = snapshot.matched_db.load(Ordering::Relaxed);
+        snapshot
+            .matched_db
+            .store(db + (target - db) * FOLLOW, Ordering::Relaxed);
+    }
+}
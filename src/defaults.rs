@@ -0,0 +1,60 @@
+use nih_plug::nih_error;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use crate::editor::{GRViewPrefs, Language, ThemeKind};
+
+/// Defaults for new instances, from `limit2zero/defaults.json` in the user's
+/// config directory. Everything is optional, and a saved session or preset
+/// still wins over it. Params are plain values by param ID, like in presets:
+///
+/// ```json
+/// { "scale": 1.5, "theme": "Light", "params": { "release": 80.0 } }
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct UserDefaults {
+    pub scale: Option<f64>,
+    pub theme: Option<ThemeKind>,
+    pub language: Option<Language>,
+    pub gr_view: Option<GRViewPrefs>,
+    pub low_power: Option<bool>,
+    // only read with the `osc` feature
+    #[allow(dead_code)]
+    pub osc_address: Option<IpAddr>,
+    #[allow(dead_code)]
+    pub osc_port: Option<u16>,
+    pub params: BTreeMap<String, f32>,
+}
+
+impl UserDefaults {
+    /// Read once per process, the first instance loads it.
+    pub fn get() -> &'static UserDefaults {
+        static DEFAULTS: OnceLock<UserDefaults> = OnceLock::new();
+        DEFAULTS.get_or_init(|| {
+            let Some(path) = path() else {
+                return UserDefaults::default();
+            };
+            // not having one is the normal case
+            let Ok(file) = std::fs::File::open(&path) else {
+                return UserDefaults::default();
+            };
+            serde_json::from_reader(std::io::BufReader::new(file)).unwrap_or_else(|err| {
+                nih_error!("couldn't read {}: {err}", path.display());
+                UserDefaults::default()
+            })
+        })
+    }
+}
+
+fn path() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join("limit2zero").join("defaults.json"))
+}
+
+/// The user's default for a param, as a plain value.
+pub(crate) fn user_default(id: &str) -> Option<f32> {
+    UserDefaults::get().params.get(id).copied()
+}
@@ -0,0 +1,113 @@
+use atomic_float::AtomicF32;
+use nih_plug::prelude::*;
+use std::mem::discriminant;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::engine::{EnvState, Processor};
+
+// how much audio goes by between two log lines
+const LOG_INTERVAL_SECONDS: f32 = 5.0;
+
+/// Counters for chasing down reports from hosts we can't test in, logged
+/// every few seconds through nih-plug's logger, so `NIH_LOG` can send them to
+/// a file. Built with the `diagnostics` feature.
+pub(crate) struct Diagnostics {
+    blocks: AtomicU64,
+    // times the clip stage had to catch a sample the envelope missed
+    clips: AtomicU64,
+    // deepest reduction in any block since the last log line
+    max_block_gr: AtomicF32,
+    // meter frames the editor wasn't ready for and got merged into the next
+    meter_overruns: AtomicU64,
+    // envelope moves between off, hold and release, summed over the channels
+    transitions: AtomicU64,
+}
+
+impl Default for Diagnostics {
+    fn default() -> Self {
+        Self {
+            blocks: AtomicU64::new(0),
+            clips: AtomicU64::new(0),
+            max_block_gr: AtomicF32::new(0.0),
+            meter_overruns: AtomicU64::new(0),
+            transitions: AtomicU64::new(0),
+        }
+    }
+}
+
+impl Diagnostics {
+    /// Writes out and restarts the counters, from the background thread.
+    pub fn log(&self) {
+        let blocks = self.blocks.swap(0, Ordering::Relaxed);
+        let clips = self.clips.swap(0, Ordering::Relaxed);
+        let max_block_gr = self.max_block_gr.swap(0.0, Ordering::Relaxed);
+        let meter_overruns = self.meter_overruns.swap(0, Ordering::Relaxed);
+        let transitions = self.transitions.swap(0, Ordering::Relaxed);
+        nih_log!(
+            "diagnostics: {blocks} blocks, {clips} clips, max GR {max_block_gr:.1} dB, \
+             {meter_overruns} meter overruns, {transitions} envelope transitions"
+        );
+    }
+
+    pub fn meter_overrun(&self) {
+        self.meter_overruns.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// The audio thread side, counts a block at a time so the shared counters
+/// are only touched once per block.
+pub(crate) struct DiagnosticsCapture {
+    states: Vec<EnvState>,
+    clips: u64,
+    transitions: u64,
+    block_gr: f32,
+    since_log: f32,
+}
+
+impl DiagnosticsCapture {
+    pub fn new(channels: usize) -> Self {
+        Self {
+            states: vec![EnvState::Off; channels],
+            clips: 0,
+            transitions: 0,
+            block_gr: 0.0,
+            since_log: 0.0,
+        }
+    }
+
+    /// After every frame, with what it clipped and the deepest linked
+    /// reduction of its channels.
+    pub fn frame(&mut self, processor: &mut Processor, clips: usize, reduction: f32) {
+        self.clips += clips as u64;
+        self.block_gr = self.block_gr.min(reduction);
+        for (channel, last) in self.states.iter_mut().enumerate() {
+            let state = *processor.limiter(channel).state;
+            if discriminant(&state) != discriminant(last) {
+                self.transitions += 1;
+            }
+            *last = state;
+        }
+    }
+
+    /// Hands the block's counts over, true when it's time for a log line.
+    pub fn block(&mut self, samples: usize, sample_rate: f32, shared: &Diagnostics) -> bool {
+        shared.blocks.fetch_add(1, Ordering::Relaxed);
+        shared.clips.fetch_add(self.clips, Ordering::Relaxed);
+        shared
+            .transitions
+            .fetch_add(self.transitions, Ordering::Relaxed);
+        shared
+            .max_block_gr
+            .fetch_min(self.block_gr, Ordering::Relaxed);
+        self.clips = 0;
+        self.transitions = 0;
+        self.block_gr = 0.0;
+
+        self.since_log += samples as f32 / sample_rate;
+        if self.since_log < LOG_INTERVAL_SECONDS {
+            return false;
+        }
+        self.since_log = 0.0;
+        true
+    }
+}
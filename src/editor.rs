@@ -2,28 +2,264 @@
 use atomic_float::AtomicF32;
 use nih_plug::prelude::Editor;
 use nih_plug_vizia::vizia::prelude::*;
-use nih_plug_vizia::vizia::vg::{Color, LineCap, LineJoin, Paint, Path};
+use nih_plug_vizia::vizia::vg::{LineCap, LineJoin, Paint, Path};
 use nih_plug_vizia::widgets::*;
 use nih_plug_vizia::{assets, create_vizia_editor, ViziaState, ViziaTheming};
+use serde::{Deserialize, Serialize};
 use std::array;
 use std::collections::VecDeque;
-use std::sync::{atomic::Ordering, Arc};
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+use std::sync::Arc;
 
-use crate::easing::Ease;
-use crate::{build_envelope, Limit2zeroParams};
+use crate::defaults::UserDefaults;
+use crate::engine::easing::Ease;
+use crate::loudness::LoudnessSnapshot;
+use crate::macro_knobs::MacroMap;
+use crate::telemetry::{MeterFrame, MeterQueue};
+use crate::{
+    DeltaSnapshot, EnvelopeProgress, Limit2zeroParams, LookaheadSnapshot, Settings, TriggerSnapshot,
+};
+
+mod ceiling;
+mod clip;
+mod compare;
+mod export;
+mod handle;
+mod i18n;
+mod knob;
+mod lock;
+mod lookahead;
+mod loudness;
+mod macros;
+mod menu;
+mod midi;
+mod morph;
+mod needle;
+mod pages;
+mod preset;
+mod randomize;
+mod scope;
+mod shared;
+mod spectrum;
+mod theme;
+mod transfer;
+mod trigger;
+mod undo;
+
+use ceiling::CeilingEvent;
+use clip::ClipIndicator;
+use compare::ABCompare;
+use handle::CurveHandle;
+use i18n::tr;
+pub(crate) use i18n::Language;
+use lock::{LockEvent, ParamLocks};
+use lookahead::{LookaheadAtomics, LookaheadFrame};
+use loudness::{LoudnessAtomics, LoudnessFrame};
+use macros::MacroEvent;
+use midi::{MidiEvent, MidiLearn};
+use morph::MorphEvent;
+use needle::{Needle, NeedleMeter};
+use pages::Tab;
+use randomize::{RandomizeEvent, Randomizer};
+use scope::{ScopeAtomics, ScopeBuffer, WaveformScope, SCOPE_LEN};
+use shared::Shared;
+use spectrum::{DeltaAtomics, DeltaSpectrum, SpectrumBuffer};
+pub(crate) use theme::ThemeKind;
+use theme::THEME_STYLE;
+use trigger::{ScopeMode, TriggerAtomics, TriggerBuffer, TriggeredScope};
+use undo::{UndoEvent, UndoStack};
 
 const FUNC_STYLE: &str = r#"
     function-graph {
-        background-color: #2e2e2e;
         border-radius: 3px;
         border-width: 1px;
-        border-color: #4e4e4e;
+    }
+    curve-handle {
+        position-type: self-directed;
+    }
+    function-graph > label {
+        position-type: self-directed;
+        width: auto;
+        height: auto;
+        font-size: 10;
+    }
+    clip-indicator {
+        layout-type: row;
+        width: auto;
+        child-space: 1s;
+        col-between: 6px;
+    }
+    .clip-light {
+        width: 12px;
+        height: 12px;
+        border-radius: 6px;
+    }
+    .scale-controls {
+        height: 24px;
+        col-between: 4px;
+        child-left: 1s;
+        child-right: 30px;
+    }
+    .scale-controls > button {
+        width: 24px;
+    }
+    .scale-controls > .size-preset {
+        width: 28px;
+    }
+    .scale-controls > .theme-toggle, .scale-controls > .fps-toggle,
+    .scale-controls > .language-toggle {
+        width: 56px;
+    }
+    .ab-controls, .undo-controls, .preset-controls, .loudness-controls {
+        width: auto;
+        height: 24px;
+        top: 1s;
+        bottom: 1s;
+        col-between: 4px;
+    }
+    .ab-controls > .ab-slot {
+        width: 28px;
+    }
+    .ab-controls > .ab-copy {
+        width: 64px;
+    }
+    .loudness-controls > .loudness-lufs {
+        width: 72px;
+        child-top: 1s;
+        child-bottom: 1s;
+    }
+    .loudness-controls > .loudness-drive {
+        width: 96px;
+    }
+    .undo-controls > button, .preset-controls > button {
+        width: 48px;
+    }
+    .param-label-row {
+        height: auto;
+        col-between: 4px;
+    }
+    .param-search {
+        height: 24px;
+        col-between: 4px;
+        child-left: 8px;
+        child-right: 8px;
+    }
+    .param-search > label {
+        width: auto;
+        child-top: 1s;
+        child-bottom: 1s;
+    }
+    .param-search > textbox {
+        width: 1s;
+    }
+    .lock-toggle {
+        width: 48px;
+        height: 18px;
+        font-size: 11;
+    }
+    .lock-toggle.locked {
+        background-color: #8c3c46;
+    }
+    param-menu-host {
+        height: auto;
+    }
+    ceiling-menu {
+        width: 140px;
+        height: auto;
+    }
+    ceiling-menu > button {
+        width: 1s;
+        height: 24px;
+    }
+    .param-menu {
+        position-type: self-directed;
+        top: 100%;
+        width: 140px;
+        height: auto;
+        z-index: 10;
+        child-space: 2px;
+        row-between: 1px;
+        border-width: 1px;
+    }
+    .param-menu > .param-menu-entry {
+        width: 1s;
+        height: 22px;
+        child-left: 6px;
+    }
+    .randomize-controls {
+        height: 24px;
+        col-between: 4px;
+    }
+    .randomize-controls > button {
+        width: auto;
+        child-left: 6px;
+        child-right: 6px;
+    }
+    .randomize-controls > button.locked {
+        background-color: #8c3c46;
+    }
+    .gr-controls {
+        height: 24px;
+        col-between: 4px;
+        child-left: 1s;
+    }
+    .gr-controls > button {
+        width: 56px;
+    }
+    limit2zero-meter > .gr-peak {
+        position-type: self-directed;
+        width: auto;
+        height: auto;
+        font-size: 10;
+    }
+    transfer-curve {
+        height: 160px;
+        border-radius: 3px;
+        border-width: 1px;
+    }
+    lookahead-view {
+        height: 120px;
+        border-radius: 3px;
+        border-width: 1px;
+    }
+    .tab-bar {
+        height: 28px;
+        col-between: 2px;
+    }
+    .tab-bar > .tab {
+        width: 1s;
+    }
+    .tab-page {
+        child-space: 8px;
+        row-between: 2px;
+    }
+    .knob-row {
+        height: auto;
+        col-between: 12px;
+    }
+    .knob-cell {
+        width: 96px;
+        height: auto;
+        row-between: 4px;
+    }
+    .knob-label {
+        width: 1s;
+        child-left: 1s;
+        child-right: 1s;
+    }
+    param-knob {
+        width: 96px;
+        height: 96px;
+    }
+    param-knob .value {
+        font-size: 12;
     }
     .scrollbar {
         display: none;
     }
-    .gain-reduction-todo {
-        alignment: center;
+    waveform-scope, needle-meter {
+        border-radius: 3px;
+        border-width: 1px;
     }
 "#;
 
@@ -32,37 +268,654 @@ struct GUIData {
     params: Arc<Limit2zeroParams>,
     attack: Vec<(f32, f32)>,
     release: Vec<(f32, f32)>,
-    gr_atomics: GRAtomics,
-    gr_buffer: GRBuffer,
+    #[lens(ignore)]
+    #[data(ignore)]
+    envelope_inputs: [f32; ENVELOPE_INPUTS],
+    meters: MeterAtomics,
+    gr_buffer: Shared<GRBuffer>,
+    scope_atomics: ScopeAtomics,
+    scope_buffer: Shared<ScopeBuffer>,
+    scope_mode: ScopeMode,
+    trigger_atomics: TriggerAtomics,
+    trigger_buffer: Shared<TriggerBuffer>,
+    delta_atomics: DeltaAtomics,
+    spectrum_buffer: Shared<SpectrumBuffer>,
+    clip_count: Arc<AtomicUsize>,
+    clips: usize,
+    scale: f64,
+    tab: Tab,
+    // whether the detailed params are in use, the tabs with them hide otherwise
+    advanced: bool,
+    time_scale: TimeScale,
+    db_range: DbRange,
+    paused: bool,
+    history_view: HistoryView,
+    gr_linked: bool,
+    gr_needle: bool,
+    peak_depth: PeakDepth,
+    ballistics: Ballistics,
+    peaks: Vec<PeakLabel>,
+    needle: Needle,
+    graph_readout: Option<GraphReadout>,
+    theme: ThemeKind,
+    language: Language,
+    latency_atomics: LatencyAtomics,
+    latency: Latency,
+    lookahead_atomics: LookaheadAtomics,
+    lookahead: LookaheadFrame,
+    progress_atomics: ProgressAtomics,
+    progress: ProgressFrame,
+    loudness_atomics: LoudnessAtomics,
+    loudness: LoudnessFrame,
+    low_power: bool,
+    ab: ABCompare,
+    undo: UndoStack,
+    randomizer: Randomizer,
+    locks: ParamLocks,
+    midi: MidiLearn,
+    macros: MacroMap,
+    param_clipboard: Option<String>,
+    /// What the search box above the pages holds, see `pages::param_search`.
+    param_filter: String,
+    #[lens(ignore)]
+    #[data(ignore)]
+    idle_frames: usize,
+    // the last point pushed into the history, after the ballistics
+    #[lens(ignore)]
+    #[data(ignore)]
+    last_point: GRPoint,
+    #[lens(ignore)]
+    #[data(ignore)]
+    gr_timer: Option<Timer>,
+    #[lens(ignore)]
+    #[data(ignore)]
+    low_power_timer: Option<Timer>,
 }
 
 #[derive(Debug, Clone)]
-struct GRAtomics {
-    pre: [Arc<AtomicF32>; 2],
-    post: [Arc<AtomicF32>; 2],
-    env: [Arc<AtomicF32>; 2],
+struct LatencyAtomics {
+    samples: Arc<AtomicU32>,
+    sample_rate: Arc<AtomicF32>,
 }
 
-#[derive(Debug, Clone)]
+impl Data for LatencyAtomics {
+    fn same(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+// the delay reported to the host, which is what PDC will compensate for
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct Latency {
+    samples: u32,
+    sample_rate: f32,
+}
+
+impl Latency {
+    fn load(atomics: &LatencyAtomics) -> Self {
+        Self {
+            samples: atomics.samples.load(Ordering::Relaxed),
+            sample_rate: atomics.sample_rate.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Latency {
+    fn text(&self, language: Language) -> String {
+        let ms = self.samples as f32 / self.sample_rate.max(1.0) * 1000.0;
+        format!(
+            "{}: {} {} ({:.2} ms)",
+            language.text("header.latency"),
+            self.samples,
+            language.text("header.samples"),
+            ms
+        )
+    }
+}
+
+impl Data for Latency {
+    fn same(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+#[derive(Clone)]
+struct ProgressAtomics(Arc<EnvelopeProgress>);
+
+impl Data for ProgressAtomics {
+    fn same(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+// where each channel's envelope sits on the attack and release curves,
+// negative when that stage isn't active
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct ProgressFrame {
+    attack: [f32; 2],
+    release: [f32; 2],
+}
+
+impl Default for ProgressFrame {
+    fn default() -> Self {
+        Self {
+            attack: [-1.0; 2],
+            release: [-1.0; 2],
+        }
+    }
+}
+
+impl ProgressFrame {
+    fn load(atomics: &ProgressAtomics) -> Self {
+        let progress = &atomics.0;
+        Self {
+            attack: array::from_fn(|i| progress.attack[i].load(Ordering::Relaxed)),
+            release: array::from_fn(|i| progress.release[i].load(Ordering::Relaxed)),
+        }
+    }
+}
+
+impl Data for ProgressFrame {
+    fn same(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+#[derive(Clone)]
+struct MeterAtomics(Arc<MeterQueue>);
+
+impl Data for MeterAtomics {
+    fn same(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+const GR_FPS: f32 = 60.0;
+const GR_POINTS: usize = 300;
+// refresh rate of the low power mode, each tick stands in for several frames
+const LOW_POWER_FPS: f32 = 15.0;
+
+/// How the GR history was last set up. Stored in the plugin state so reopening
+/// a session shows it the same way, the theme is stored next to it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct GRViewPrefs {
+    time_scale: TimeScale,
+    db_range: DbRange,
+    paused: bool,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum TimeScale {
+    OneSecond,
+    #[default]
+    FiveSeconds,
+    ThirtySeconds,
+    TwoMinutes,
+}
+
+impl TimeScale {
+    fn name(&self) -> &'static str {
+        match self {
+            TimeScale::OneSecond => "1 s",
+            TimeScale::FiveSeconds => "5 s",
+            TimeScale::ThirtySeconds => "30 s",
+            TimeScale::TwoMinutes => "2 min",
+        }
+    }
+
+    fn seconds(&self) -> f32 {
+        match self {
+            TimeScale::OneSecond => 1.0,
+            TimeScale::FiveSeconds => 5.0,
+            TimeScale::ThirtySeconds => 30.0,
+            TimeScale::TwoMinutes => 120.0,
+        }
+    }
+
+    fn next(&self) -> Self {
+        match self {
+            TimeScale::OneSecond => TimeScale::FiveSeconds,
+            TimeScale::FiveSeconds => TimeScale::ThirtySeconds,
+            TimeScale::ThirtySeconds => TimeScale::TwoMinutes,
+            TimeScale::TwoMinutes => TimeScale::OneSecond,
+        }
+    }
+
+    // how many timer ticks get folded into a single point of the history
+    fn frames_per_point(&self) -> usize {
+        (self.seconds() * GR_FPS / GR_POINTS as f32).ceil().max(1.0) as usize
+    }
+
+    fn points(&self) -> usize {
+        ((self.seconds() * GR_FPS) as usize / self.frames_per_point()).min(GR_POINTS)
+    }
+}
+
+impl Data for TimeScale {
+    fn same(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum DbRange {
+    Six,
+    #[default]
+    Twelve,
+    TwentyFour,
+    FortyEight,
+}
+
+impl DbRange {
+    fn name(&self) -> &'static str {
+        match self {
+            DbRange::Six => "6 dB",
+            DbRange::Twelve => "12 dB",
+            DbRange::TwentyFour => "24 dB",
+            DbRange::FortyEight => "48 dB",
+        }
+    }
+
+    fn db(&self) -> f32 {
+        match self {
+            DbRange::Six => 6.0,
+            DbRange::Twelve => 12.0,
+            DbRange::TwentyFour => 24.0,
+            DbRange::FortyEight => 48.0,
+        }
+    }
+
+    // spacing of the horizontal gridlines, six lines regardless of the range
+    fn grid_step(&self) -> f32 {
+        self.db() / 6.0
+    }
+
+    fn next(&self) -> Self {
+        match self {
+            DbRange::Six => DbRange::Twelve,
+            DbRange::Twelve => DbRange::TwentyFour,
+            DbRange::TwentyFour => DbRange::FortyEight,
+            DbRange::FortyEight => DbRange::Six,
+        }
+    }
+}
+
+impl Data for DbRange {
+    fn same(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+const METER_ATTACK_MS: [f32; 4] = [0.0, 10.0, 50.0, 250.0];
+const METER_RELEASE_MS: [f32; 4] = [0.0, 100.0, 500.0, 2000.0];
+
+// smoothing of the history's traces, purely cosmetic. attack is how fast a
+// trace moves towards more level or more reduction, release how fast it
+// falls back. both are indices into the tables above
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct Ballistics {
+    attack: usize,
+    release: usize,
+}
+
+impl Ballistics {
+    fn attack_ms(&self) -> f32 {
+        METER_ATTACK_MS[self.attack]
+    }
+
+    fn release_ms(&self) -> f32 {
+        METER_RELEASE_MS[self.release]
+    }
+
+    fn cycle_attack(&mut self) {
+        self.attack = (self.attack + 1) % METER_ATTACK_MS.len();
+    }
+
+    fn cycle_release(&mut self) {
+        self.release = (self.release + 1) % METER_RELEASE_MS.len();
+    }
+
+    // one pole per frame, zero means the trace follows the input exactly
+    fn coefficient(ms: f32) -> f32 {
+        if ms <= 0.0 {
+            0.0
+        } else {
+            (-1000.0 / (ms * GR_FPS)).exp()
+        }
+    }
+
+    fn step(&self, current: f32, target: f32, attacking: bool) -> f32 {
+        let ms = if attacking {
+            self.attack_ms()
+        } else {
+            self.release_ms()
+        };
+        target + (current - target) * Self::coefficient(ms)
+    }
+
+    // levels attack upwards, the envelopes downwards into more reduction
+    fn apply(&self, current: GRPoint, target: GRPoint) -> GRPoint {
+        let level = |current: [f32; 2], target: [f32; 2]| {
+            array::from_fn(|i| self.step(current[i], target[i], target[i] > current[i]))
+        };
+        let reduction = |current: [f32; 2], target: [f32; 2]| {
+            array::from_fn(|i| self.step(current[i], target[i], target[i] < current[i]))
+        };
+
+        GRPoint {
+            pre: level(current.pre, target.pre),
+            post: level(current.post, target.post),
+            env: reduction(current.env, target.env),
+            channel_env: reduction(current.channel_env, target.channel_env),
+        }
+    }
+}
+
+impl Data for Ballistics {
+    fn same(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+// how deep the reduction has to go before the history calls it out
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+enum PeakDepth {
+    Off,
+    Three,
+    #[default]
+    Six,
+    Twelve,
+}
+
+impl PeakDepth {
+    fn db(&self) -> Option<f32> {
+        match self {
+            PeakDepth::Off => None,
+            PeakDepth::Three => Some(3.0),
+            PeakDepth::Six => Some(6.0),
+            PeakDepth::Twelve => Some(12.0),
+        }
+    }
+
+    fn next(&self) -> Self {
+        match self {
+            PeakDepth::Off => PeakDepth::Three,
+            PeakDepth::Three => PeakDepth::Six,
+            PeakDepth::Six => PeakDepth::Twelve,
+            PeakDepth::Twelve => PeakDepth::Off,
+        }
+    }
+}
+
+impl Data for PeakDepth {
+    fn same(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+// at most this many peaks get a label, the deepest ones win
+const MAX_PEAK_LABELS: usize = 6;
+
+// a labelled peak in the visible part of the history, `x` and `y` are
+// relative to the size of the view
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct PeakLabel {
+    x: f32,
+    y: f32,
+    db: f32,
+}
+
+impl Data for PeakLabel {
+    fn same(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+const MAX_HISTORY_ZOOM: f32 = 32.0;
+// the curve graphs get a line every 25%
+const GRAPH_GRID_LINES: usize = 4;
+// length of the dashes and spacing of the markers in patterned themes, in points
+const DASH_POINTS: usize = 4;
+const MARKER_POINTS: usize = 12;
+
+// the part of the history that's on screen, position is where the visible
+// window sits between the oldest (0.0) and the newest (1.0) point
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct HistoryView {
+    zoom: f32,
+    position: f32,
+}
+
+impl Default for HistoryView {
+    fn default() -> Self {
+        Self {
+            zoom: 1.0,
+            position: 1.0,
+        }
+    }
+}
+
+impl HistoryView {
+    fn range(&self, len: usize) -> (usize, usize) {
+        let visible = ((len as f32 / self.zoom).round() as usize).clamp(len.min(2), len);
+        let start = ((len - visible) as f32 * self.position).round() as usize;
+        (start, start + visible)
+    }
+
+    fn zoom_by(&mut self, lines: f32) {
+        self.zoom = (self.zoom * 1.25_f32.powf(lines)).clamp(1.0, MAX_HISTORY_ZOOM);
+    }
+
+    // `fraction` is relative to the width of the view, dragging to the right
+    // pulls older points into view
+    fn pan_by(&mut self, fraction: f32) {
+        let hidden = 1.0 - self.zoom.recip();
+        if hidden > 0.0 {
+            self.position = (self.position - fraction * self.zoom.recip() / hidden).clamp(0.0, 1.0);
+        }
+    }
+}
+
+impl Data for HistoryView {
+    fn same(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+#[derive(Debug)]
 struct GRBuffer {
     pre: VecDeque<[f32; 2]>,
     post: VecDeque<[f32; 2]>,
     env: VecDeque<[f32; 2]>,
+    channel_env: VecDeque<[f32; 2]>,
+    frames_per_point: usize,
+    pending_frames: usize,
+    pending: GRPoint,
 }
 
-impl Default for GRBuffer {
+// a point in the history, pre and post keep the loudest frame and the
+// envelopes keep the deepest reduction of all frames folded into it. `env` is
+// what's applied after stereo linking, `channel_env` is each channel's own
+// envelope before linking
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct GRPoint {
+    pre: [f32; 2],
+    post: [f32; 2],
+    env: [f32; 2],
+    channel_env: [f32; 2],
+}
+
+impl Default for GRPoint {
     fn default() -> Self {
         Self {
-            pre: VecDeque::from_iter((0..300).map(|_| [-100.0; 2])),
-            post: VecDeque::from_iter((0..300).map(|_| [-100.0; 2])),
-            env: VecDeque::from_iter((0..300).map(|_| [0.0; 2])),
+            pre: [-100.0; 2],
+            post: [-100.0; 2],
+            env: [0.0; 2],
+            channel_env: [0.0; 2],
+        }
+    }
+}
+
+impl GRBuffer {
+    fn new(scale: TimeScale) -> Self {
+        let points = scale.points();
+        Self {
+            pre: VecDeque::from_iter((0..points).map(|_| [-100.0; 2])),
+            post: VecDeque::from_iter((0..points).map(|_| [-100.0; 2])),
+            env: VecDeque::from_iter((0..points).map(|_| [0.0; 2])),
+            channel_env: VecDeque::from_iter((0..points).map(|_| [0.0; 2])),
+            frames_per_point: scale.frames_per_point(),
+            pending_frames: 0,
+            pending: GRPoint::default(),
+        }
+    }
+
+    // how many frames the whole history spans
+    fn frames(&self) -> usize {
+        self.pre.len() * self.frames_per_point
+    }
+
+    fn push(&mut self, point: GRPoint) {
+        let GRPoint {
+            pre,
+            post,
+            env,
+            channel_env,
+        } = point;
+        for channel in 0..2 {
+            self.pending.pre[channel] = self.pending.pre[channel].max(pre[channel]);
+            self.pending.post[channel] = self.pending.post[channel].max(post[channel]);
+            self.pending.env[channel] = self.pending.env[channel].min(env[channel]);
+            self.pending.channel_env[channel] =
+                self.pending.channel_env[channel].min(channel_env[channel]);
+        }
+
+        self.pending_frames += 1;
+        if self.pending_frames < self.frames_per_point {
+            return;
+        }
+
+        self.pre.pop_front();
+        self.post.pop_front();
+        self.env.pop_front();
+        self.channel_env.pop_front();
+
+        self.pre.push_back(self.pending.pre);
+        self.post.push_back(self.pending.post);
+        self.env.push_back(self.pending.env);
+        self.channel_env.push_back(self.pending.channel_env);
+
+        self.pending_frames = 0;
+        self.pending = GRPoint::default();
+    }
+}
+
+// the deepest point of every stretch of the visible history that goes below
+// `depth`, so a peak that scrolls out of view loses its label with it
+fn find_peaks(
+    env: &VecDeque<[f32; 2]>,
+    (start, end): (usize, usize),
+    depth: f32,
+    db_range: DbRange,
+) -> Vec<PeakLabel> {
+    let mut peaks = Vec::new();
+    let mut deepest: Option<(usize, f32)> = None;
+    for i in start..end {
+        let db = env[i][0].min(env[i][1]);
+        if db <= -depth {
+            if deepest.map_or(true, |(_, deepest_db)| db < deepest_db) {
+                deepest = Some((i, db));
+            }
+        } else if let Some(peak) = deepest.take() {
+            peaks.push(peak);
         }
     }
+    peaks.extend(deepest);
+
+    peaks.sort_by(|a, b| a.1.total_cmp(&b.1));
+    peaks.truncate(MAX_PEAK_LABELS);
+
+    let visible = (end - start).max(1) as f32;
+    peaks
+        .into_iter()
+        .map(|(i, db)| PeakLabel {
+            x: (i - start) as f32 / visible,
+            y: ((-db) / db_range.db()).min(1.0),
+            db,
+        })
+        .collect()
+}
+
+impl Default for GRBuffer {
+    fn default() -> Self {
+        Self::new(TimeScale::default())
+    }
 }
 
 enum GUIEvent {
-    UpdateEnvelopes,
     UpdateGRVizulization,
+    ClearClips,
+    ScaleBy(f64),
+    ResetScale,
+    SetSize(SizePreset),
+    SetTab(Tab),
+    CycleTimeScale,
+    CycleDbRange,
+    TogglePause,
+    ZoomHistory(f32),
+    PanHistory(f32),
+    ResetHistoryView,
+    ExportHistory,
+    ImportPreset,
+    ExportPreset,
+    ToggleLinkedGR,
+    ToggleNeedle,
+    CyclePeakDepth,
+    CycleMeterAttack,
+    CycleScopeMode,
+    CycleMeterRelease,
+    GraphHover(Option<GraphReadout>),
+    CycleTheme,
+    CycleLanguage,
+    ToggleLowPower,
+    ToggleAB,
+    CopyParamValue(String),
+    SetParamFilter(String),
+    CopyAB,
+    ApplySuggestedDrive,
+}
+
+const MIN_SCALE: f64 = 0.5;
+const MAX_SCALE: f64 = 3.0;
+
+// fixed window sizes on top of the free scaling, medium is the 800x800 default
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SizePreset {
+    Small,
+    Medium,
+    Large,
+}
+
+impl SizePreset {
+    const ALL: [SizePreset; 3] = [SizePreset::Small, SizePreset::Medium, SizePreset::Large];
+
+    // key of the preset's label in the string table
+    fn name(&self) -> &'static str {
+        match self {
+            SizePreset::Small => "size.small",
+            SizePreset::Medium => "size.medium",
+            SizePreset::Large => "size.large",
+        }
+    }
+
+    fn scale(&self) -> f64 {
+        match self {
+            SizePreset::Small => 0.75,
+            SizePreset::Medium => 1.0,
+            SizePreset::Large => 1.5,
+        }
+    }
 }
 
 impl GUIData {
@@ -71,26 +924,290 @@ impl GUIData {
         self.release = generate_release_graph(&self.params, 100);
     }
 
-    pub fn update_buffers(&mut self) {
-        let pre = array::from_fn(|i| self.gr_atomics.pre[i].swap(0.0, Ordering::Relaxed));
-        let post = array::from_fn(|i| self.gr_atomics.post[i].swap(0.0, Ordering::Relaxed));
-        let env = array::from_fn(|i| self.gr_atomics.env[i].swap(0.0, Ordering::Relaxed));
+    // automation, preset loads and text entry don't go through our widgets,
+    // so the timer checks whether any curve param actually moved
+    fn update_functions_if_changed(&mut self) {
+        let inputs = envelope_inputs(&self.params);
+        if inputs != self.envelope_inputs {
+            self.envelope_inputs = inputs;
+            self.update_functions();
+        }
+    }
+
+    // every tick covers this many frames of the history
+    fn frames_per_tick(&self) -> usize {
+        if self.low_power {
+            (GR_FPS / LOW_POWER_FPS).round() as usize
+        } else {
+            1
+        }
+    }
+
+    fn active_timer(&self) -> Option<Timer> {
+        if self.low_power {
+            self.low_power_timer
+        } else {
+            self.gr_timer
+        }
+    }
+
+    fn store_gr_view(&self) {
+        *self.params.gr_view.write().unwrap() = GRViewPrefs {
+            time_scale: self.time_scale,
+            db_range: self.db_range,
+            paused: self.paused,
+        };
+    }
+
+    /// Drains the atomics into the buffers and returns whether the editor
+    /// needs to be redrawn.
+    pub fn update_buffers(&mut self) -> bool {
+        let frames = self.frames_per_tick();
+
+        let MeterFrame {
+            pre,
+            post,
+            env,
+            channel_env,
+        } = self.meters.0.drain();
+
+        let point = GRPoint {
+            pre,
+            post,
+            env,
+            channel_env,
+        };
+        let mut smoothed = self.last_point;
+        let mut gr_buffer = self.gr_buffer.write();
+        for _ in 0..frames {
+            smoothed = self.ballistics.apply(smoothed, point);
+            gr_buffer.push(smoothed);
+        }
+        let history_frames = gr_buffer.frames();
+        drop(gr_buffer);
+
+        let scope_changed = self
+            .scope_buffer
+            .write()
+            .update(&self.scope_atomics, frames);
+
+        let triggered = self.trigger_buffer.read().is_stale(&self.trigger_atomics);
+        if triggered {
+            self.trigger_buffer.write().load(&self.trigger_atomics);
+        }
+
+        // only worth the FFT while it's on screen
+        let spectrum_changed = self.scope_mode == ScopeMode::Spectrum
+            && self
+                .spectrum_buffer
+                .write()
+                .update(&self.delta_atomics, self.latency.sample_rate);
+
+        let clips = self.clip_count.load(Ordering::Relaxed);
+        let latency = Latency::load(&self.latency_atomics);
+        let lookahead = LookaheadFrame::load(&self.lookahead_atomics);
+        let progress = ProgressFrame::load(&self.progress_atomics);
+        let loudness = LoudnessFrame::load(&self.loudness_atomics);
+
+        let changed = smoothed != self.last_point
+            || scope_changed
+            || triggered
+            || spectrum_changed
+            || clips != self.clips
+            || latency != self.latency
+            || lookahead != self.lookahead
+            || progress != self.progress
+            || loudness != self.loudness;
+
+        // the needle keeps swinging for a moment after the meters settle
+        let needle = self.needle;
+        self.needle.update(point.env[0].min(point.env[1]), frames);
+        let changed = changed || needle != self.needle;
+
+        self.last_point = smoothed;
+        self.clips = clips;
+        self.latency = latency;
+        self.lookahead = lookahead;
+        self.progress = progress;
+        self.loudness = loudness;
+
+        if changed {
+            self.idle_frames = 0;
+        } else {
+            self.idle_frames = self.idle_frames.saturating_add(frames);
+        }
+
+        // once the history and the scope are filled with the same frame,
+        // scrolling them any further doesn't change a pixel
+        self.idle_frames <= history_frames.max(SCOPE_LEN)
+    }
+
+    fn update_peaks(&mut self) {
+        let Some(depth) = self.peak_depth.db() else {
+            self.peaks.clear();
+            return;
+        };
 
-        self.gr_buffer.pre.pop_front();
-        self.gr_buffer.post.pop_front();
-        self.gr_buffer.env.pop_front();
+        let gr_buffer = self.gr_buffer.read();
+        let env = if self.gr_linked {
+            &gr_buffer.env
+        } else {
+            &gr_buffer.channel_env
+        };
+        let range = self.history_view.range(env.len());
+        self.peaks = find_peaks(env, range, depth, self.db_range);
+    }
 
-        self.gr_buffer.pre.push_back(pre);
-        self.gr_buffer.post.push_back(post);
-        self.gr_buffer.env.push_back(env);
+    pub fn clear_clips(&mut self) {
+        self.clip_count.store(0, Ordering::Relaxed);
+        self.clips = 0;
     }
 }
 
 impl Model for GUIData {
-    fn event(&mut self, _cx: &mut EventContext, event: &mut Event) {
+    fn event(&mut self, cx: &mut EventContext, event: &mut Event) {
         event.map(|app_event, _| match app_event {
-            GUIEvent::UpdateEnvelopes => self.update_functions(),
-            GUIEvent::UpdateGRVizulization => self.update_buffers(),
+            GUIEvent::UpdateGRVizulization => {
+                if self.update_buffers() {
+                    cx.needs_redraw();
+                }
+                self.update_functions_if_changed();
+                self.midi.refresh(&self.params);
+                self.advanced = self.params.simple.advanced.value();
+                if !self.advanced && self.tab.detailed() {
+                    self.tab = Tab::Main;
+                }
+                // the resize handle changes the scale behind our back
+                self.scale = cx.user_scale_factor();
+            }
+            GUIEvent::ClearClips => self.clear_clips(),
+            // nih_plug_vizia stores the user scale factor in the `ViziaState`
+            // once the window has been resized, so it survives reopening
+            GUIEvent::ScaleBy(step) => {
+                self.scale = (cx.user_scale_factor() + step).clamp(MIN_SCALE, MAX_SCALE);
+                cx.set_user_scale_factor(self.scale);
+            }
+            GUIEvent::ResetScale => {
+                self.scale = 1.0;
+                cx.set_user_scale_factor(self.scale);
+            }
+            GUIEvent::SetSize(preset) => {
+                self.scale = preset.scale();
+                cx.set_user_scale_factor(self.scale);
+            }
+            GUIEvent::SetTab(tab) => self.tab = *tab,
+            GUIEvent::CycleTimeScale => {
+                self.time_scale = self.time_scale.next();
+                self.gr_buffer = Shared::new(GRBuffer::new(self.time_scale));
+                self.history_view = HistoryView::default();
+                self.store_gr_view();
+            }
+            GUIEvent::CycleDbRange => {
+                self.db_range = self.db_range.next();
+                self.store_gr_view();
+            }
+            // stopping the timer also stops draining the atomics, so the
+            // history stays exactly as it was when paused
+            GUIEvent::TogglePause => {
+                self.paused = !self.paused;
+                self.history_view = HistoryView::default();
+                self.store_gr_view();
+                if let Some(timer) = self.active_timer() {
+                    if self.paused {
+                        cx.stop_timer(timer);
+                    } else {
+                        cx.start_timer(timer);
+                    }
+                }
+            }
+            // zooming into a history that's still scrolling isn't useful, so
+            // this only works while paused
+            GUIEvent::ZoomHistory(lines) if self.paused => self.history_view.zoom_by(*lines),
+            GUIEvent::PanHistory(fraction) if self.paused => self.history_view.pan_by(*fraction),
+            GUIEvent::ResetHistoryView => self.history_view = HistoryView::default(),
+            GUIEvent::ExportHistory => export::export_history(&self.gr_buffer.read()),
+            GUIEvent::ImportPreset => preset::import_preset(cx, &self.params),
+            GUIEvent::ExportPreset => preset::export_preset(&self.params),
+            GUIEvent::ToggleLinkedGR => self.gr_linked = !self.gr_linked,
+            GUIEvent::ToggleNeedle => self.gr_needle = !self.gr_needle,
+            GUIEvent::CyclePeakDepth => self.peak_depth = self.peak_depth.next(),
+            GUIEvent::CycleMeterAttack => self.ballistics.cycle_attack(),
+            GUIEvent::CycleScopeMode => self.scope_mode = self.scope_mode.next(),
+            GUIEvent::CycleMeterRelease => self.ballistics.cycle_release(),
+            GUIEvent::GraphHover(readout) => self.graph_readout = *readout,
+            GUIEvent::ToggleLowPower => {
+                if let (Some(timer), false) = (self.active_timer(), self.paused) {
+                    cx.stop_timer(timer);
+                }
+                self.low_power = !self.low_power;
+                *self.params.low_power.write().unwrap() = self.low_power;
+                if let (Some(timer), false) = (self.active_timer(), self.paused) {
+                    cx.start_timer(timer);
+                }
+            }
+            GUIEvent::ToggleAB => self.ab.toggle(cx, self.params.as_ref()),
+            GUIEvent::CopyAB => self.ab.copy_to_other(self.params.as_ref()),
+            GUIEvent::ApplySuggestedDrive => self.loudness.apply(cx, &self.params),
+            GUIEvent::CopyParamValue(value) => self.param_clipboard = Some(value.clone()),
+            GUIEvent::SetParamFilter(filter) => self.param_filter = filter.clone(),
+            GUIEvent::CycleTheme => {
+                self.theme = self.theme.next();
+                *self.params.theme.write().unwrap() = self.theme;
+            }
+            GUIEvent::CycleLanguage => {
+                self.language = self.language.next();
+                *self.params.language.write().unwrap() = self.language;
+            }
+            GUIEvent::ZoomHistory(_) | GUIEvent::PanHistory(_) => (),
+        });
+
+        // new points, zooming, panning and the display settings all move the
+        // peaks around, so they're found again after any of them
+        event.map(|_: &GUIEvent, _| self.update_peaks());
+
+        // only listening in, the params still need to reach the host
+        event.map(|param_event: &RawParamEvent, _| self.undo.observe(param_event));
+
+        event.map(|undo_event, _| match undo_event {
+            UndoEvent::Undo => self.undo.undo(cx),
+            UndoEvent::Redo => self.undo.redo(cx),
+            UndoEvent::BeginGroup { record } => self.undo.begin_group(*record),
+            UndoEvent::EndGroup => self.undo.end_group(),
+        });
+
+        event.map(|randomize_event, _| match randomize_event {
+            RandomizeEvent::Randomize => self.randomizer.randomize(cx, &self.params, &self.locks),
+            RandomizeEvent::Mutate => self.randomizer.mutate(cx, &self.params, &self.locks),
+            RandomizeEvent::ToggleLock(group) => self.randomizer.toggle_lock(*group),
+            RandomizeEvent::CycleMutateAmount => self.randomizer.cycle_mutate_amount(),
+        });
+
+        event.map(|morph_event, _| match morph_event {
+            MorphEvent::Detail(morph) => morph.detail(cx, &self.params),
+        });
+
+        event.map(|ceiling_event, _| match ceiling_event {
+            CeilingEvent::Apply(preset) => preset.apply(cx, &self.params),
+        });
+
+        event.map(|lock_event, _| match lock_event {
+            LockEvent::Toggle(id) => self.locks.toggle(&self.params, id),
+        });
+
+        event.map(|midi_event, _| self.midi.event(&self.params, midi_event));
+
+        event.map(|macro_event: &MacroEvent, _| self.macros = macro_event.apply(&self.params));
+
+        event.map(|window_event, meta| match window_event {
+            WindowEvent::KeyDown(Code::KeyZ, _) if cx.modifiers().command() => {
+                if cx.modifiers().shift() {
+                    self.undo.redo(cx);
+                } else {
+                    self.undo.undo(cx);
+                }
+                meta.consume();
+            }
+            _ => {}
         });
     }
 }
@@ -101,27 +1218,88 @@ impl Data for GRAtomics {
     }
 }
 
-impl Data for GRBuffer {
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FunctionGraph {
+    Attack,
+    Release,
+}
+
+// the point of the curve under the cursor, x is the time and y the gain
+// left, both from 0 to 1
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct GraphReadout {
+    graph: FunctionGraph,
+    x: f32,
+    y: f32,
+}
+
+impl Data for GraphReadout {
     fn same(&self, other: &Self) -> bool {
-        if self.pre.len() != other.pre.len() {
-            return false;
-        }
-        for i in 0..self.pre.len() {
-            let pre = self.pre[i] != other.pre[i];
-            let post = self.post[i] != other.post[i];
-            let reduction = self.env[i] != other.env[i];
+        self == other
+    }
+}
 
-            if pre || post || reduction {
-                return false;
-            }
+impl FunctionGraph {
+    fn new(cx: &mut Context, graph: FunctionGraph) -> Handle<'_, Self> {
+        let language = GUIData::language.get(cx);
+        graph.build(cx, move |cx| {
+            Label::new(cx, "0%")
+                .class("graph-axis")
+                .hoverable(false)
+                .left(Pixels(4.0))
+                .top(Stretch(1.0))
+                .bottom(Pixels(2.0));
+            Label::new(cx, "100%")
+                .class("graph-axis")
+                .hoverable(false)
+                .left(Stretch(1.0))
+                .right(Pixels(4.0))
+                .top(Stretch(1.0))
+                .bottom(Pixels(2.0));
+            Label::new(cx, "100%")
+                .class("graph-axis")
+                .hoverable(false)
+                .left(Pixels(4.0))
+                .top(Pixels(2.0));
+            Label::new(
+                cx,
+                GUIData::graph_readout.map(move |readout| match readout {
+                    Some(readout) if readout.graph == graph => format!(
+                        "{:.0}% {}, {:.0}% {}",
+                        readout.x * 100.0,
+                        language.text("graph.time"),
+                        (1.0 - readout.y) * 100.0,
+                        language.text("graph.reduction")
+                    ),
+                    _ => String::new(),
+                }),
+            )
+            .class("graph-readout")
+            .hoverable(false)
+            .left(Stretch(1.0))
+            .right(Pixels(4.0))
+            .top(Pixels(2.0));
+            CurveHandle::new(cx, graph);
+        })
+    }
+
+    fn points(&self, cx: &mut impl DataContext) -> Vec<(f32, f32)> {
+        match self {
+            FunctionGraph::Attack => GUIData::attack.get(cx),
+            FunctionGraph::Release => GUIData::release.get(cx),
         }
-        true
     }
 }
 
-enum FunctionGraph {
-    Attack,
-    Release,
+// the curves are drawn in the largest square that fits, centered in the view.
+// returns its left and top edge and its size
+fn graph_area(bounds: BoundingBox) -> (f32, f32, f32) {
+    let wh = f32::min(bounds.h, bounds.w);
+    (
+        bounds.x + (bounds.w - wh) / 2.0,
+        bounds.y + (bounds.h - wh) / 2.0,
+        wh,
+    )
 }
 
 impl View for FunctionGraph {
@@ -129,6 +1307,27 @@ impl View for FunctionGraph {
         Some("function-graph")
     }
 
+    fn event(&mut self, cx: &mut EventContext, event: &mut Event) {
+        event.map(|window_event, _| match window_event {
+            WindowEvent::MouseMove(x, _) => {
+                let points = self.points(cx);
+                if points.len() < 2 {
+                    return;
+                }
+
+                let (left, _, wh) = graph_area(cx.bounds());
+                let x = ((x - left) / wh).clamp(0.0, 1.0);
+                cx.emit(GUIEvent::GraphHover(Some(GraphReadout {
+                    graph: *self,
+                    x,
+                    y: sample_curve(&points, x),
+                })));
+            }
+            WindowEvent::MouseLeave => cx.emit(GUIEvent::GraphHover(None)),
+            _ => {}
+        });
+    }
+
     fn draw(&self, cx: &mut DrawContext, canvas: &mut Canvas) {
         let points = match self {
             FunctionGraph::Attack => GUIData::attack.0.get(cx),
@@ -139,25 +1338,26 @@ impl View for FunctionGraph {
             return;
         }
 
-        let bounds = cx.bounds();
-        let wh = f32::min(bounds.h, bounds.w);
-
-        let (x_offset, y_offset) = if wh < bounds.h {
-            let crest = bounds.h - wh;
-            (0.0, crest / 2.0)
-        } else if wh < bounds.w {
-            let crest = bounds.w - wh;
-            (crest / 2.0, 0.0)
-        } else {
-            (0.0, 0.0)
-        };
+        let (left, top, wh) = graph_area(cx.bounds());
+        let palette = GUIData::theme.0.get(cx).palette();
+
+        let mut grid = Path::new();
+        for i in 0..=GRAPH_GRID_LINES {
+            let offset = i as f32 / GRAPH_GRID_LINES as f32 * wh;
+            grid.move_to(left + offset, top);
+            grid.line_to(left + offset, top + wh);
+            grid.move_to(left, top + offset);
+            grid.line_to(left + wh, top + offset);
+        }
+
+        let mut paint_grid = Paint::color(palette.grid);
+        paint_grid.set_line_width(1.0);
+        canvas.stroke_path(&grid, &paint_grid);
 
         let mut path = Path::new();
         for (i, (x, y)) in points.iter().enumerate() {
-            let mut px = x * wh;
-            let mut py = wh - (y * wh);
-            px += bounds.x + x_offset;
-            py += bounds.y + y_offset;
+            let px = left + x * wh;
+            let py = top + wh - (y * wh);
             if i == 0 {
                 path.move_to(px, py);
             } else {
@@ -165,32 +1365,107 @@ impl View for FunctionGraph {
             }
         }
 
-        let mut paint = Paint::color(Color::rgb(77, 205, 102));
-        paint.set_line_width(2.0);
+        let mut paint = Paint::color(palette.accent);
+        paint.set_line_width(palette.stroke_width);
         paint.set_line_cap(LineCap::Round);
         paint.set_line_join(LineJoin::Round);
 
         canvas.stroke_path(&path, &paint);
+
+        // mark where the realtime envelope currently is on the curve
+        let progress = GUIData::progress.0.get(cx);
+        let progress = match self {
+            FunctionGraph::Attack => progress.attack,
+            FunctionGraph::Release => progress.release,
+        };
+
+        let mut dots = Path::new();
+        for x in progress.into_iter().filter(|x| *x >= 0.0) {
+            let y = sample_curve(&points, x);
+            dots.circle(left + x * wh, top + wh - (y * wh), 4.0);
+        }
+        canvas.fill_path(&dots, &Paint::color(palette.highlight));
+
+        if let Some(readout) = GUIData::graph_readout
+            .0
+            .get(cx)
+            .filter(|r| r.graph == *self)
+        {
+            let px = left + readout.x * wh;
+            let py = top + wh - (readout.y * wh);
+
+            let mut cursor = Path::new();
+            cursor.move_to(px, top);
+            cursor.line_to(px, top + wh);
+
+            let mut paint_cursor = Paint::color(palette.zero);
+            paint_cursor.set_line_width(1.0);
+            canvas.stroke_path(&cursor, &paint_cursor);
+
+            let mut dot = Path::new();
+            dot.circle(px, py, 3.0);
+            canvas.fill_path(&dot, &Paint::color(palette.zero));
+        }
     }
 }
 
+// linearly interpolates the evenly spaced graph points at `x`
+fn sample_curve(points: &[(f32, f32)], x: f32) -> f32 {
+    let position = x.clamp(0.0, 1.0) * (points.len() - 1) as f32;
+    let i = (position.floor() as usize).min(points.len() - 2);
+    lerp(points[i].1, points[i + 1].1, position - i as f32)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
 // Makes sense to also define this here, makes it a bit easier to keep track of
 pub(crate) fn default_state() -> Arc<ViziaState> {
-    ViziaState::new(|| (800, 800))
+    let scale = UserDefaults::get()
+        .scale
+        .map_or(1.0, |scale| scale.clamp(MIN_SCALE, MAX_SCALE));
+    ViziaState::new_with_default_scale_factor(|| (800, 800), scale)
 }
 
-struct GRVizualization;
+fn add_gr_timer(cx: &mut Context, fps: f32) -> Timer {
+    cx.add_timer(
+        Duration::from_secs_f32(1.0 / (fps - f32::EPSILON)),
+        None,
+        |cx, reason| match reason {
+            TimerAction::Tick(_) => cx.emit(GUIEvent::UpdateGRVizulization),
+            _ => (),
+        },
+    )
+}
+
+struct GRVizualization {
+    drag_x: Option<f32>,
+}
 impl GRVizualization {
     pub fn new(cx: &'_ mut Context) -> Handle<'_, Self> {
-        cx.add_timer(
-            Duration::from_secs_f32(1.0 / (60.0 - f32::EPSILON)),
-            None,
-            |cx, reason| match reason {
-                TimerAction::Tick(_) => cx.emit(GUIEvent::UpdateGRVizulization),
-                _ => (),
-            },
-        );
-        GRVizualization.build(cx, |_| {})
+        GRVizualization { drag_x: None }.build(cx, |cx| {
+            // a fixed set of labels that get moved onto the current peaks
+            for slot in 0..MAX_PEAK_LABELS {
+                let peak = move |peaks: &Vec<PeakLabel>| peaks.get(slot).copied();
+                Label::new(
+                    cx,
+                    GUIData::peaks.map(move |peaks| {
+                        peak(peaks).map_or(String::new(), |peak| format!("{:.1} dB", peak.db))
+                    }),
+                )
+                .class("gr-peak")
+                .hoverable(false)
+                .display(GUIData::peaks.map(move |peaks| peak(peaks).is_some()))
+                .left(GUIData::peaks.map(move |peaks| {
+                    // keep labels near the right edge from running off the view
+                    Percentage(peak(peaks).map_or(0.0, |peak| peak.x.min(0.9) * 100.0))
+                }))
+                .top(GUIData::peaks.map(move |peaks| {
+                    Percentage(peak(peaks).map_or(0.0, |peak| peak.y.min(0.9) * 100.0))
+                }));
+            }
+        })
     }
 }
 impl View for GRVizualization {
@@ -198,60 +1473,154 @@ impl View for GRVizualization {
         Some("limit2zero-meter")
     }
 
+    fn event(&mut self, cx: &mut EventContext, event: &mut Event) {
+        event.map(|window_event, meta| match window_event {
+            WindowEvent::MouseScroll(_, scroll_y) => {
+                cx.emit(GUIEvent::ZoomHistory(*scroll_y));
+                meta.consume();
+            }
+            WindowEvent::MouseDown(MouseButton::Left) => {
+                self.drag_x = Some(cx.mouse().cursorx);
+                cx.capture();
+                meta.consume();
+            }
+            WindowEvent::MouseMove(x, _) => {
+                if let Some(last_x) = self.drag_x {
+                    let width = cx.bounds().w;
+                    if width > 0.0 {
+                        cx.emit(GUIEvent::PanHistory((x - last_x) / width));
+                    }
+                    self.drag_x = Some(*x);
+                }
+            }
+            WindowEvent::MouseUp(MouseButton::Left) => {
+                if self.drag_x.take().is_some() {
+                    cx.release();
+                    meta.consume();
+                }
+            }
+            WindowEvent::MouseDoubleClick(MouseButton::Left) => {
+                cx.emit(GUIEvent::ResetHistoryView);
+                meta.consume();
+            }
+            _ => {}
+        });
+    }
+
     fn draw(&self, cx: &mut DrawContext, canvas: &mut Canvas) {
-        let points = GUIData::gr_buffer.0.get(cx);
+        let shared = GUIData::gr_buffer.0.get(cx);
+        let points = shared.read();
 
         let bounds = cx.bounds();
-        let db_resolution = 100.0;
+        let db_range = GUIData::db_range.0.get(cx);
+        let db_resolution = db_range.db();
+
+        // everything is drawn as dB below zero, so 0 dB sits on the top edge
+        // and the bottom edge is the end of the selected range
+        let db_to_y =
+            |db: f32| (-db).clamp(0.0, db_resolution) / db_resolution * bounds.h + bounds.y;
+
+        let mut grid = Path::new();
+        let mut grid_db = db_range.grid_step();
+        while grid_db < db_resolution {
+            let y = db_to_y(-grid_db);
+            grid.move_to(bounds.x, y);
+            grid.line_to(bounds.x + bounds.w, y);
+            grid_db += db_range.grid_step();
+        }
+
+        let palette = GUIData::theme.0.get(cx).palette();
+
+        let mut paint_grid = Paint::color(palette.grid);
+        paint_grid.set_line_width(1.0);
+        canvas.stroke_path(&grid, &paint_grid);
 
         let mut path_pre = [Path::new(), Path::new()];
         let mut path_post = [Path::new(), Path::new()];
         let mut path_env = [Path::new(), Path::new()];
 
-        for i in 0..points.pre.len() {
-            let x = (i as f32 / points.pre.len() as f32) * bounds.w + bounds.x;
+        // either what's actually applied after stereo linking, or what each
+        // channel's envelope would have done on its own
+        let env = if GUIData::gr_linked.0.get(cx) {
+            &points.env
+        } else {
+            &points.channel_env
+        };
+
+        let (start, end) = GUIData::history_view.0.get(cx).range(points.pre.len());
+
+        // patterned themes break the post trace into dashes
+        let dash_on = |i: usize| !palette.patterned || ((i - start) / DASH_POINTS) % 2 == 0;
+        let mut markers = Path::new();
+
+        for i in start..end {
+            let x = ((i - start) as f32 / (end - start) as f32) * bounds.w + bounds.x;
 
             for (channel, y) in points.pre[i].iter().enumerate() {
-                let y = y.clamp(0.0, -1.0 * db_resolution) / db_resolution;
-                let y = y * bounds.h + bounds.y;
-                if i == 0 {
+                let y = db_to_y(*y);
+                if i == start {
                     path_pre[channel].move_to(x, y);
                 } else {
                     path_pre[channel].line_to(x, y);
                 }
             }
             for (channel, y) in points.post[i].iter().enumerate() {
-                let y = y.clamp(0.0, -1.0 * db_resolution) / db_resolution;
-                let y = y * bounds.h + bounds.y;
-                if i == 0 {
+                let y = db_to_y(*y);
+                if !dash_on(i) {
+                    continue;
+                }
+                if i == start || !dash_on(i - 1) {
                     path_post[channel].move_to(x, y);
                 } else {
                     path_post[channel].line_to(x, y);
                 }
             }
-            for (channel, y) in points.env[i].iter().enumerate() {
-                let y = y.clamp(0.0, -1.0 * db_resolution) / db_resolution;
-                let y = y * bounds.h + bounds.y;
-                if i == 0 {
+            for (channel, y) in env[i].iter().enumerate() {
+                let y = db_to_y(*y);
+                if i == start {
                     path_env[channel].move_to(x, y);
                 } else {
                     path_env[channel].line_to(x, y);
                 }
+                if palette.patterned && (i - start) % MARKER_POINTS == 0 {
+                    let half = palette.stroke_width * 1.25;
+                    markers.rect(x - half, y - half, half * 2.0, half * 2.0);
+                }
             }
         }
 
-        let mut paint_pre = Paint::color(Color::rgb(77, 205, 102));
-        paint_pre.set_line_width(2.0);
+        // bridge the two channels' envelopes, the more they're linked the more
+        // solid it gets
+        let stereo_link = GUIData::params.0.get(cx).input.stereo_link.value();
+        if stereo_link > 0.0 && end - start > 1 {
+            let x_at = |i: usize| ((i - start) as f32 / (end - start) as f32) * bounds.w + bounds.x;
+            let mut bridge = Path::new();
+            bridge.move_to(x_at(start), db_to_y(env[start][0]));
+            for i in start + 1..end {
+                bridge.line_to(x_at(i), db_to_y(env[i][0]));
+            }
+            for i in (start..end).rev() {
+                bridge.line_to(x_at(i), db_to_y(env[i][1]));
+            }
+            bridge.close();
+
+            let mut bridge_color = palette.env;
+            bridge_color.set_alphaf(0.1 + 0.4 * stereo_link);
+            canvas.fill_path(&bridge, &Paint::color(bridge_color));
+        }
+
+        let mut paint_pre = Paint::color(palette.accent);
+        paint_pre.set_line_width(palette.stroke_width);
         paint_pre.set_line_cap(LineCap::Round);
         paint_pre.set_line_join(LineJoin::Round);
 
-        let mut paint_post = Paint::color(Color::rgb(102, 77, 205));
-        paint_post.set_line_width(2.0);
+        let mut paint_post = Paint::color(palette.post);
+        paint_post.set_line_width(palette.stroke_width);
         paint_post.set_line_cap(LineCap::Round);
         paint_post.set_line_join(LineJoin::Round);
 
-        let mut paint_env = Paint::color(Color::rgb(205, 77, 102));
-        paint_env.set_line_width(2.0);
+        let mut paint_env = Paint::color(palette.env);
+        paint_env.set_line_width(palette.stroke_width);
         paint_env.set_line_cap(LineCap::Round);
         paint_env.set_line_join(LineJoin::Round);
 
@@ -260,238 +1629,422 @@ impl View for GRVizualization {
             canvas.stroke_path(&path_post[i], &paint_post);
             canvas.stroke_path(&path_env[i], &paint_env);
         }
+        canvas.fill_path(&markers, &Paint::color(palette.env));
     }
 }
 
 pub(crate) fn create(
     params: Arc<Limit2zeroParams>,
-    pre: [Arc<AtomicF32>; 2],
-    post: [Arc<AtomicF32>; 2],
-    reduction: [Arc<AtomicF32>; 2],
+    meters: Arc<MeterQueue>,
+    scope_peak: [Arc<AtomicF32>; 2],
+    scope_gr: [Arc<AtomicF32>; 2],
+    clip_count: Arc<AtomicUsize>,
+    latency_samples: Arc<AtomicU32>,
+    sample_rate: Arc<AtomicF32>,
+    lookahead: Arc<LookaheadSnapshot>,
+    progress: Arc<EnvelopeProgress>,
+    trigger: Arc<TriggerSnapshot>,
+    delta: Arc<DeltaSnapshot>,
+    loudness: Arc<LoudnessSnapshot>,
     editor_state: Arc<ViziaState>,
 ) -> Option<Box<dyn Editor>> {
+    let scale = editor_state.user_scale_factor();
     create_vizia_editor(editor_state, ViziaTheming::Custom, move |cx, _| {
         assets::register_noto_sans_light(cx);
         assets::register_noto_sans_thin(cx);
 
+        let gr_timer = add_gr_timer(cx, GR_FPS);
+        let low_power_timer = add_gr_timer(cx, LOW_POWER_FPS);
+        let low_power = *params.low_power.read().unwrap();
+        let gr_view = *params.gr_view.read().unwrap();
+
         GUIData {
             params: params.clone(),
             attack: generate_attack_graph(&params.clone(), 100),
             release: generate_release_graph(&params.clone(), 100),
-            gr_atomics: GRAtomics {
-                pre: pre.clone(),
-                post: post.clone(),
-                env: reduction.clone(),
+            envelope_inputs: envelope_inputs(&params),
+            meters: MeterAtomics(meters.clone()),
+            gr_buffer: Shared::new(GRBuffer::new(gr_view.time_scale)),
+            scope_atomics: ScopeAtomics {
+                peak: scope_peak.clone(),
+                gr: scope_gr.clone(),
             },
-            gr_buffer: GRBuffer::default(),
+            scope_buffer: Shared::default(),
+            scope_mode: ScopeMode::default(),
+            trigger_atomics: TriggerAtomics(trigger.clone()),
+            trigger_buffer: Shared::default(),
+            delta_atomics: DeltaAtomics(delta.clone()),
+            spectrum_buffer: Shared::default(),
+            clip_count: clip_count.clone(),
+            clips: clip_count.load(Ordering::Relaxed),
+            scale,
+            tab: Tab::default(),
+            advanced: params.simple.advanced.value(),
+            time_scale: gr_view.time_scale,
+            db_range: gr_view.db_range,
+            paused: gr_view.paused,
+            history_view: HistoryView::default(),
+            gr_linked: true,
+            peak_depth: PeakDepth::default(),
+            ballistics: Ballistics::default(),
+            peaks: Vec::new(),
+            gr_needle: false,
+            needle: Needle::default(),
+            graph_readout: None,
+            theme: *params.theme.read().unwrap(),
+            language: *params.language.read().unwrap(),
+            latency_atomics: LatencyAtomics {
+                samples: latency_samples.clone(),
+                sample_rate: sample_rate.clone(),
+            },
+            latency: Latency {
+                samples: latency_samples.load(Ordering::Relaxed),
+                sample_rate: sample_rate.load(Ordering::Relaxed),
+            },
+            lookahead_atomics: LookaheadAtomics(lookahead.clone()),
+            lookahead: LookaheadFrame::default(),
+            progress_atomics: ProgressAtomics(progress.clone()),
+            progress: ProgressFrame::default(),
+            loudness_atomics: LoudnessAtomics(loudness.clone()),
+            loudness: LoudnessFrame::default(),
+            low_power,
+            ab: ABCompare::default(),
+            undo: UndoStack::default(),
+            randomizer: Randomizer::default(),
+            locks: ParamLocks::load(&params),
+            midi: MidiLearn::load(&params),
+            macros: params.macro_map.read().unwrap().clone(),
+            param_clipboard: None,
+            param_filter: String::new(),
+            idle_frames: 0,
+            last_point: GRPoint::default(),
+            gr_timer: Some(gr_timer),
+            low_power_timer: Some(low_power_timer),
         }
         .build(cx);
 
+        if !gr_view.paused {
+            cx.start_timer(if low_power { low_power_timer } else { gr_timer });
+        }
+
         cx.add_stylesheet(FUNC_STYLE).unwrap();
+        cx.add_stylesheet(THEME_STYLE).unwrap();
 
-        VStack::new(cx, |cx| {
-            GRVizualization::new(cx);
-            Label::new(cx, "Clip2Zero")
-                .font_family(vec![FamilyOwned::Name(String::from(assets::NOTO_SANS))])
-                .font_weight(FontWeightKeyword::Thin)
-                .font_size(30.0)
-                .height(Pixels(50.0))
-                .child_top(Stretch(1.0))
-                .child_bottom(Pixels(0.0));
-
-            HStack::new(cx, |cx| {
-                VStack::new(cx, |cx| {
-                    Label::new(cx, "Drive");
-                    ParamSlider::new(cx, GUIData::params, |params| &params.drive);
-                    ParamButton::new(cx, GUIData::params, |params| &params.compensate);
-                    Label::new(cx, "stereo_link");
-                    ParamSlider::new(cx, GUIData::params, |params| &params.stereo_link);
-                    Label::new(cx, "trim");
-                    ParamSlider::new(cx, GUIData::params, |params| &params.trim);
+        // every label looks its text up while building, so switching languages
+        // rebuilds the whole editor
+        Binding::new(cx, GUIData::language, |cx, language| {
+            let language = language.get(cx);
+            let root = VStack::new(cx, |cx| {
+                Binding::new(cx, GUIData::gr_needle, |cx, needle| {
+                    if needle.get(cx) {
+                        NeedleMeter::new(cx);
+                    } else {
+                        GRVizualization::new(cx);
+                    }
+                });
+                HStack::new(cx, |cx| {
+                    Label::new(cx, tr(cx, "gr.history"));
+                    Button::new(
+                        cx,
+                        |cx| cx.emit(GUIEvent::CycleTimeScale),
+                        |cx| {
+                            Label::new(
+                                cx,
+                                GUIData::time_scale.map(|scale| scale.name().to_string()),
+                            )
+                        },
+                    );
+                    Label::new(cx, tr(cx, "gr.range"));
+                    Button::new(
+                        cx,
+                        |cx| cx.emit(GUIEvent::CycleDbRange),
+                        |cx| {
+                            Label::new(cx, GUIData::db_range.map(|range| range.name().to_string()))
+                        },
+                    );
+                    Label::new(cx, tr(cx, "gr.peaks"));
+                    Button::new(
+                        cx,
+                        |cx| cx.emit(GUIEvent::CyclePeakDepth),
+                        |cx| {
+                            Label::new(
+                                cx,
+                                GUIData::peak_depth.map(move |depth| match depth.db() {
+                                    Some(db) => format!("{:.0} dB", db),
+                                    None => language.text("gr.off").to_string(),
+                                }),
+                            )
+                        },
+                    );
+                    Label::new(cx, tr(cx, "gr.ballistics"));
+                    Button::new(
+                        cx,
+                        |cx| cx.emit(GUIEvent::CycleMeterAttack),
+                        |cx| {
+                            Label::new(
+                                cx,
+                                GUIData::ballistics
+                                    .map(|ballistics| format!("{:.0} ms", ballistics.attack_ms())),
+                            )
+                        },
+                    );
+                    Button::new(
+                        cx,
+                        |cx| cx.emit(GUIEvent::CycleMeterRelease),
+                        |cx| {
+                            Label::new(
+                                cx,
+                                GUIData::ballistics
+                                    .map(|ballistics| format!("{:.0} ms", ballistics.release_ms())),
+                            )
+                        },
+                    );
+                    Button::new(
+                        cx,
+                        |cx| cx.emit(GUIEvent::TogglePause),
+                        |cx| {
+                            Label::new(
+                                cx,
+                                GUIData::paused.map(move |paused| {
+                                    language
+                                        .text(if *paused { "gr.resume" } else { "gr.pause" })
+                                        .to_string()
+                                }),
+                            )
+                        },
+                    )
+                    .toggle_class("paused", GUIData::paused);
+                    Button::new(
+                        cx,
+                        |cx| cx.emit(GUIEvent::ToggleLinkedGR),
+                        |cx| {
+                            Label::new(
+                                cx,
+                                GUIData::gr_linked.map(move |linked| {
+                                    language
+                                        .text(if *linked {
+                                            "gr.linked"
+                                        } else {
+                                            "gr.per_channel"
+                                        })
+                                        .to_string()
+                                }),
+                            )
+                        },
+                    );
+                    Button::new(
+                        cx,
+                        |cx| cx.emit(GUIEvent::ToggleNeedle),
+                        |cx| {
+                            Label::new(
+                                cx,
+                                GUIData::gr_needle.map(move |needle| {
+                                    language
+                                        .text(if *needle { "gr.needle" } else { "gr.trace" })
+                                        .to_string()
+                                }),
+                            )
+                        },
+                    );
+                    Button::new(
+                        cx,
+                        |cx| cx.emit(GUIEvent::CycleScopeMode),
+                        |cx| {
+                            Label::new(
+                                cx,
+                                GUIData::scope_mode
+                                    .map(move |mode| language.text(mode.name()).to_string()),
+                            )
+                        },
+                    );
+                    Button::new(
+                        cx,
+                        |cx| cx.emit(GUIEvent::ExportHistory),
+                        |cx| Label::new(cx, tr(cx, "gr.export")),
+                    );
+                })
+                .class("gr-controls");
+                HStack::new(cx, |cx| {
+                    Label::new(cx, "Limit2Zero")
+                        .font_family(vec![FamilyOwned::Name(String::from(assets::NOTO_SANS))])
+                        .font_weight(FontWeightKeyword::Thin)
+                        .font_size(30.0)
+                        .height(Stretch(1.0))
+                        .child_top(Stretch(1.0))
+                        .child_bottom(Pixels(0.0));
+                    Label::new(
+                        cx,
+                        GUIData::latency.map(move |latency| latency.text(language)),
+                    )
+                    .class("latency")
+                    .height(Stretch(1.0))
+                    .child_top(Stretch(1.0))
+                    .child_bottom(Stretch(1.0));
+                    loudness::loudness_controls(cx);
+                    undo::undo_controls(cx);
+                    preset::preset_controls(cx);
+                    compare::ab_controls(cx);
+                    ClipIndicator::new(cx).height(Stretch(1.0));
                 })
-                .width(Percentage(25.0));
-                VStack::new(cx, |cx| {
-                    HStack::new(cx, |cx| {
-                        FunctionGraph::Attack
-                            .build(cx, |_| {})
-                            .width(Stretch(1.0))
-                            .height(Stretch(1.0))
-                            .border_width(Pixels(1.0));
-                        Label::new(cx, "Todo: GR View")
-                            .class("gain-reduction-todo")
-                            .width(Stretch(1.0))
-                            .height(Stretch(1.0));
-                        FunctionGraph::Release
-                            .build(cx, |_| {})
-                            .width(Stretch(1.0))
-                            .height(Stretch(1.0))
-                            .border_width(Pixels(1.0));
-                    })
-                    .height(Percentage(25.0));
-                    HStack::new(cx, |cx| {
-                        ScrollView::new(cx, 0.0, 0.0, false, true, |cx| {
-                            VStack::new(cx, |cx| {
-                                Label::new(cx, "lookahead");
-                                ParamSlider::new(cx, GUIData::params, |params| &params.lookahead)
-                                    .on_mouse_move(|cx, _, _| cx.emit(GUIEvent::UpdateEnvelopes));
-                                Label::new(cx, "lookahead_accuracy");
-                                ParamSlider::new(cx, GUIData::params, |params| {
-                                    &params.lookahead_accuracy
-                                })
-                                .on_mouse_move(|cx, _, _| cx.emit(GUIEvent::UpdateEnvelopes));
-                                Label::new(cx, "attack_amt");
-                                ParamSlider::new(cx, GUIData::params, |params| &params.attack_amt)
-                                    .on_mouse_move(|cx, _, _| cx.emit(GUIEvent::UpdateEnvelopes));
-                                Label::new(cx, "atk_env_linearity");
-                                ParamSlider::new(cx, GUIData::params, |params| {
-                                    &params.atk_env_linearity
-                                })
-                                .on_mouse_move(|cx, _, _| cx.emit(GUIEvent::UpdateEnvelopes));
-                                Label::new(cx, "atk_env_center");
-                                ParamSlider::new(cx, GUIData::params, |params| {
-                                    &params.atk_env_center
-                                })
-                                .on_mouse_move(|cx, _, _| cx.emit(GUIEvent::UpdateEnvelopes));
-                                Label::new(cx, "atk_env_power_in");
-                                ParamSlider::new(cx, GUIData::params, |params| {
-                                    &params.atk_env_power_in
-                                })
-                                .on_mouse_move(|cx, _, _| cx.emit(GUIEvent::UpdateEnvelopes));
-                                Label::new(cx, "atk_env_power_out");
-                                ParamSlider::new(cx, GUIData::params, |params| {
-                                    &params.atk_env_power_out
-                                })
-                                .on_mouse_move(|cx, _, _| cx.emit(GUIEvent::UpdateEnvelopes));
-                                Label::new(cx, "atk_env_polarity_in");
-                                ParamSlider::new(cx, GUIData::params, |params| {
-                                    &params.atk_env_polarity_in
-                                })
-                                .on_mouse_move(|cx, _, _| cx.emit(GUIEvent::UpdateEnvelopes));
-                                Label::new(cx, "atk_env_polarity_out");
-                                ParamSlider::new(cx, GUIData::params, |params| {
-                                    &params.atk_env_polarity_out
-                                })
-                                .on_mouse_move(|cx, _, _| cx.emit(GUIEvent::UpdateEnvelopes));
-                                Label::new(cx, "atk_smooth_amt");
-                                ParamSlider::new(cx, GUIData::params, |params| {
-                                    &params.atk_smooth_amt
-                                })
-                                .on_mouse_move(|cx, _, _| cx.emit(GUIEvent::UpdateEnvelopes));
-                                Label::new(cx, "atk_env_sm_power_in");
-                                ParamSlider::new(cx, GUIData::params, |params| {
-                                    &params.atk_env_sm_power_in
-                                })
-                                .on_mouse_move(|cx, _, _| cx.emit(GUIEvent::UpdateEnvelopes));
-                                Label::new(cx, "atk_env_sm_power_out");
-                                ParamSlider::new(cx, GUIData::params, |params| {
-                                    &params.atk_env_sm_power_out
-                                })
-                                .on_mouse_move(|cx, _, _| cx.emit(GUIEvent::UpdateEnvelopes));
-                                Label::new(cx, "atk_env_sm_polarity_in");
-                                ParamSlider::new(cx, GUIData::params, |params| {
-                                    &params.atk_env_sm_polarity_in
-                                })
-                                .on_mouse_move(|cx, _, _| cx.emit(GUIEvent::UpdateEnvelopes));
-                                Label::new(cx, "atk_env_sm_polarity_out");
-                                ParamSlider::new(cx, GUIData::params, |params| {
-                                    &params.atk_env_sm_polarity_out
-                                })
-                                .on_mouse_move(|cx, _, _| cx.emit(GUIEvent::UpdateEnvelopes));
-                            })
-                            .height(Auto);
-                        });
-                        ScrollView::new(cx, 0.0, 0.0, false, true, |cx| {
-                            VStack::new(cx, |cx| {
-                                Label::new(cx, "hold");
-                                ParamSlider::new(cx, GUIData::params, |params| &params.hold)
-                                    .on_mouse_move(|cx, _, _| cx.emit(GUIEvent::UpdateEnvelopes));
-                                Label::new(cx, "release");
-                                ParamSlider::new(cx, GUIData::params, |params| &params.release)
-                                    .on_mouse_move(|cx, _, _| cx.emit(GUIEvent::UpdateEnvelopes));
-                                Label::new(cx, "release_amt");
-                                ParamSlider::new(cx, GUIData::params, |params| &params.release_amt)
-                                    .on_mouse_move(|cx, _, _| cx.emit(GUIEvent::UpdateEnvelopes));
-                                Label::new(cx, "rel_env_linearity");
-                                ParamSlider::new(cx, GUIData::params, |params| {
-                                    &params.rel_env_linearity
-                                })
-                                .on_mouse_move(|cx, _, _| cx.emit(GUIEvent::UpdateEnvelopes));
-                                Label::new(cx, "rel_env_center");
-                                ParamSlider::new(cx, GUIData::params, |params| {
-                                    &params.rel_env_center
-                                })
-                                .on_mouse_move(|cx, _, _| cx.emit(GUIEvent::UpdateEnvelopes));
-                                Label::new(cx, "rel_env_power_in");
-                                ParamSlider::new(cx, GUIData::params, |params| {
-                                    &params.rel_env_power_in
-                                })
-                                .on_mouse_move(|cx, _, _| cx.emit(GUIEvent::UpdateEnvelopes));
-                                Label::new(cx, "rel_env_power_out");
-                                ParamSlider::new(cx, GUIData::params, |params| {
-                                    &params.rel_env_power_out
-                                })
-                                .on_mouse_move(|cx, _, _| cx.emit(GUIEvent::UpdateEnvelopes));
-                                Label::new(cx, "rel_env_polarity_in");
-                                ParamSlider::new(cx, GUIData::params, |params| {
-                                    &params.rel_env_polarity_in
-                                })
-                                .on_mouse_move(|cx, _, _| cx.emit(GUIEvent::UpdateEnvelopes));
-                                Label::new(cx, "rel_env_polarity_out");
-                                ParamSlider::new(cx, GUIData::params, |params| {
-                                    &params.rel_env_polarity_out
-                                })
-                                .on_mouse_move(|cx, _, _| cx.emit(GUIEvent::UpdateEnvelopes));
-                                Label::new(cx, "rel_smooth_amt");
-                                ParamSlider::new(cx, GUIData::params, |params| {
-                                    &params.rel_smooth_amt
-                                })
-                                .on_mouse_move(|cx, _, _| cx.emit(GUIEvent::UpdateEnvelopes));
-                                Label::new(cx, "rel_env_sm_power_in");
-                                ParamSlider::new(cx, GUIData::params, |params| {
-                                    &params.rel_env_sm_power_in
-                                })
-                                .on_mouse_move(|cx, _, _| cx.emit(GUIEvent::UpdateEnvelopes));
-                                Label::new(cx, "rel_env_sm_power_out");
-                                ParamSlider::new(cx, GUIData::params, |params| {
-                                    &params.rel_env_sm_power_out
-                                })
-                                .on_mouse_move(|cx, _, _| cx.emit(GUIEvent::UpdateEnvelopes));
-                                Label::new(cx, "rel_env_sm_polarity_in");
-                                ParamSlider::new(cx, GUIData::params, |params| {
-                                    &params.rel_env_sm_polarity_in
-                                })
-                                .on_mouse_move(|cx, _, _| cx.emit(GUIEvent::UpdateEnvelopes));
-                                Label::new(cx, "rel_env_sm_polarity_out");
-                                ParamSlider::new(cx, GUIData::params, |params| {
-                                    &params.rel_env_sm_polarity_out
-                                });
-                            })
-                            .height(Auto);
-                        });
+                .height(Percentage(6.25));
+
+                HStack::new(cx, |cx| {
+                    FunctionGraph::new(cx, FunctionGraph::Attack)
+                        .width(Stretch(1.0))
+                        .height(Stretch(1.0))
+                        .border_width(Pixels(1.0));
+                    Binding::new(cx, GUIData::scope_mode, |cx, mode| match mode.get(cx) {
+                        ScopeMode::Scrolling => {
+                            WaveformScope::new(cx)
+                                .width(Stretch(1.0))
+                                .height(Stretch(1.0))
+                                .border_width(Pixels(1.0));
+                        }
+                        ScopeMode::Triggered => {
+                            TriggeredScope::new(cx)
+                                .width(Stretch(1.0))
+                                .height(Stretch(1.0))
+                                .border_width(Pixels(1.0));
+                        }
+                        ScopeMode::Spectrum => {
+                            DeltaSpectrum::new(cx)
+                                .width(Stretch(1.0))
+                                .height(Stretch(1.0))
+                                .border_width(Pixels(1.0));
+                        }
                     });
+                    FunctionGraph::new(cx, FunctionGraph::Release)
+                        .width(Stretch(1.0))
+                        .height(Stretch(1.0))
+                        .border_width(Pixels(1.0));
+                })
+                .height(Percentage(25.0));
+
+                pages::tab_bar(cx);
+                pages::param_search(cx);
+                ScrollView::new(cx, 0.0, 0.0, false, true, |cx| {
+                    pages::tab_page(cx);
                 });
+
+                HStack::new(cx, |cx| {
+                    for preset in SizePreset::ALL {
+                        Button::new(
+                            cx,
+                            move |cx| cx.emit(GUIEvent::SetSize(preset)),
+                            move |cx| Label::new(cx, tr(cx, preset.name())),
+                        )
+                        .class("size-preset")
+                        .toggle_class(
+                            "selected",
+                            GUIData::scale.map(move |scale| *scale == preset.scale()),
+                        );
+                    }
+                    Button::new(
+                        cx,
+                        |cx| cx.emit(GUIEvent::ScaleBy(-0.25)),
+                        |cx| Label::new(cx, "-"),
+                    );
+                    Label::new(
+                        cx,
+                        GUIData::scale.map(|scale| format!("{:.0}%", scale * 100.0)),
+                    )
+                    .width(Pixels(48.0))
+                    .child_space(Stretch(1.0))
+                    .on_double_click(|cx, _| cx.emit(GUIEvent::ResetScale));
+                    Button::new(
+                        cx,
+                        |cx| cx.emit(GUIEvent::ScaleBy(0.25)),
+                        |cx| Label::new(cx, "+"),
+                    );
+                    Button::new(
+                        cx,
+                        |cx| cx.emit(GUIEvent::ToggleLowPower),
+                        |cx| {
+                            Label::new(
+                                cx,
+                                GUIData::low_power.map(|low_power| {
+                                    if *low_power { "15 fps" } else { "60 fps" }.to_string()
+                                }),
+                            )
+                        },
+                    )
+                    .class("fps-toggle");
+                    Button::new(
+                        cx,
+                        |cx| cx.emit(GUIEvent::CycleTheme),
+                        |cx| {
+                            Label::new(
+                                cx,
+                                GUIData::theme
+                                    .map(move |theme| language.text(theme.name()).to_string()),
+                            )
+                        },
+                    )
+                    .class("theme-toggle");
+                    Button::new(
+                        cx,
+                        |cx| cx.emit(GUIEvent::CycleLanguage),
+                        |cx| {
+                            Label::new(
+                                cx,
+                                GUIData::language.map(|language| language.name().to_string()),
+                            )
+                        },
+                    )
+                    .class("language-toggle");
+                })
+                .class("scale-controls");
+            });
+            ThemeKind::ALL.into_iter().fold(root, |root, theme| {
+                root.toggle_class(theme.class(), GUIData::theme.map(move |t| *t == theme))
             });
+            ResizeHandle::new(cx);
         });
-        ResizeHandle::new(cx);
     })
 }
 
+const ENVELOPE_INPUTS: usize = 29;
+
+// every param the attack and release curves are built from
+fn envelope_inputs(params: &Limit2zeroParams) -> [f32; ENVELOPE_INPUTS] {
+    [
+        params.simple.attack_shape.value(),
+        params.simple.release_shape.value(),
+        params.simple.advanced.value() as u8 as f32,
+        params.attack.atk_env_linearity.value(),
+        params.attack.atk_env_center.value(),
+        params.attack_smoothing.atk_smooth_amt.value(),
+        params.attack.atk_env_polarity_in.value(),
+        params.attack.atk_env_polarity_out.value(),
+        params.attack.atk_env_power_in.value(),
+        params.attack.atk_env_power_out.value(),
+        params.attack_smoothing.atk_env_sm_polarity_in.value(),
+        params.attack_smoothing.atk_env_sm_polarity_out.value(),
+        params.attack_smoothing.atk_env_sm_power_in.value(),
+        params.attack_smoothing.atk_env_sm_power_out.value(),
+        params.release.rel_env_linearity.value(),
+        params.release.rel_env_center.value(),
+        params.release.rel_smooth_amt.value(),
+        params.release.rel_env_polarity_in.value(),
+        params.release.rel_env_polarity_out.value(),
+        params.release.rel_env_power_in.value(),
+        params.release.rel_env_power_out.value(),
+        params.release.rel_env_sm_polarity_in.value(),
+        params.release.rel_env_sm_polarity_out.value(),
+        params.release.rel_env_sm_power_in.value(),
+        params.release.rel_env_sm_power_out.value(),
+        params.attack.atk_morph.value(),
+        params.attack.atk_morph_on.value() as u8 as f32,
+        params.release.rel_morph.value(),
+        params.release.rel_morph_on.value() as u8 as f32,
+    ]
+}
+
 fn generate_release_graph(params: &Limit2zeroParams, resolution: usize) -> Vec<(f32, f32)> {
     let mut points = Vec::with_capacity(resolution);
 
-    let envelope = build_envelope(
-        params.rel_env_linearity.value(),
-        params.rel_env_center.value(),
-        params.rel_smooth_amt.value(),
-        params.rel_env_polarity_in.value(),
-        params.rel_env_polarity_out.value(),
-        params.rel_env_power_in.value(),
-        params.rel_env_power_out.value(),
-        params.rel_env_sm_polarity_in.value(),
-        params.rel_env_sm_polarity_out.value(),
-        params.rel_env_sm_power_in.value(),
-        params.rel_env_sm_power_out.value(),
-    );
+    // the macros' curve in simple mode
+    let envelope = Settings::from_params(params).release.build();
 
     for i in 0..=resolution {
         let x = i as f32 / resolution as f32;
@@ -504,19 +2057,7 @@ fn generate_release_graph(params: &Limit2zeroParams, resolution: usize) -> Vec<(
 fn generate_attack_graph(params: &Limit2zeroParams, resolution: usize) -> Vec<(f32, f32)> {
     let mut points = Vec::with_capacity(resolution);
 
-    let envelope = build_envelope(
-        params.atk_env_linearity.value(),
-        params.atk_env_center.value(),
-        params.atk_smooth_amt.value(),
-        params.atk_env_polarity_in.value(),
-        params.atk_env_polarity_out.value(),
-        params.atk_env_power_in.value(),
-        params.atk_env_power_out.value(),
-        params.atk_env_sm_polarity_in.value(),
-        params.atk_env_sm_polarity_out.value(),
-        params.atk_env_sm_power_in.value(),
-        params.atk_env_sm_power_out.value(),
-    );
+    let envelope = Settings::from_params(params).attack.build();
 
     for i in 0..=resolution {
         let x = i as f32 / resolution as f32;
@@ -0,0 +1,147 @@
+use nih_plug::prelude::{util, Param};
+use nih_plug_vizia::vizia::prelude::*;
+use nih_plug_vizia::widgets::RawParamEvent;
+
+use super::i18n::tr;
+use super::undo::UndoEvent;
+use crate::engine::TRUE_PEAK_LOOKAHEAD_MS;
+use crate::Limit2zeroParams;
+
+pub(super) enum CeilingEvent {
+    /// Sets the ceiling and true peak from the preset, as one undo step.
+    Apply(CeilingPreset),
+}
+
+/// Common delivery ceilings. Trim is what sets the ceiling, the limiter
+/// itself always stops at 0 dB, and output gain takes what's past its range.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum CeilingPreset {
+    Sample01,
+    TruePeak03,
+    TruePeak1,
+    // leaves room for the overshoot of lossy encoders
+    TruePeak2,
+}
+
+impl CeilingPreset {
+    pub const ALL: [CeilingPreset; 4] = [
+        CeilingPreset::Sample01,
+        CeilingPreset::TruePeak03,
+        CeilingPreset::TruePeak1,
+        CeilingPreset::TruePeak2,
+    ];
+
+    fn ceiling_db(&self) -> f32 {
+        match self {
+            CeilingPreset::Sample01 => -0.1,
+            CeilingPreset::TruePeak03 => -0.3,
+            CeilingPreset::TruePeak1 => -1.0,
+            CeilingPreset::TruePeak2 => -2.0,
+        }
+    }
+
+    fn true_peak(&self) -> bool {
+        !matches!(self, CeilingPreset::Sample01)
+    }
+
+    /// Key of the preset's label in the string table.
+    fn name(&self) -> &'static str {
+        match self {
+            CeilingPreset::Sample01 => "ceiling.sample_01",
+            CeilingPreset::TruePeak03 => "ceiling.true_peak_03",
+            CeilingPreset::TruePeak1 => "ceiling.true_peak_1",
+            CeilingPreset::TruePeak2 => "ceiling.true_peak_2",
+        }
+    }
+
+    pub fn apply(&self, cx: &mut EventContext, params: &Limit2zeroParams) {
+        let lowest_trim = params.output.trim.preview_plain(0.0);
+        let trim = self.ceiling_db().max(lowest_trim);
+
+        cx.emit(UndoEvent::BeginGroup { record: true });
+        set(cx, &params.output.trim, trim);
+        set(
+            cx,
+            &params.output.output_gain,
+            util::db_to_gain(self.ceiling_db() - trim),
+        );
+        set(cx, &params.output.true_peak, self.true_peak());
+        // true peak does nothing without some lookahead to put its peaks in
+        let lookahead = params.input.lookahead.unmodulated_plain_value();
+        if self.true_peak() && lookahead < TRUE_PEAK_LOOKAHEAD_MS {
+            set(cx, &params.input.lookahead, TRUE_PEAK_LOOKAHEAD_MS);
+        }
+        cx.emit(UndoEvent::EndGroup);
+    }
+}
+
+fn set<P: Param>(cx: &mut EventContext, param: &P, value: P::Plain) {
+    let ptr = param.as_ptr();
+    cx.emit(RawParamEvent::BeginSetParameter(ptr));
+    cx.emit(RawParamEvent::SetParameterNormalized(
+        ptr,
+        param.preview_normalized(value),
+    ));
+    cx.emit(RawParamEvent::EndSetParameter(ptr));
+}
+
+/// A button that drops down the presets, styled like the params' menu.
+#[derive(Lens)]
+pub(super) struct CeilingMenu {
+    open: bool,
+}
+
+enum CeilingMenuEvent {
+    Toggle,
+}
+
+impl CeilingMenu {
+    pub fn new(cx: &mut Context) -> Handle<'_, Self> {
+        Self { open: false }.build(cx, |cx| {
+            Button::new(
+                cx,
+                |cx| cx.emit(CeilingMenuEvent::Toggle),
+                |cx| Label::new(cx, tr(cx, "ceiling.presets")),
+            );
+            Binding::new(cx, CeilingMenu::open, |cx, open| {
+                if open.get(cx) {
+                    VStack::new(cx, |cx| {
+                        for preset in CeilingPreset::ALL {
+                            Button::new(
+                                cx,
+                                move |cx| cx.emit(CeilingEvent::Apply(preset)),
+                                move |cx| Label::new(cx, tr(cx, preset.name())),
+                            )
+                            .class("param-menu-entry");
+                        }
+                    })
+                    .class("param-menu");
+                }
+            });
+        })
+    }
+}
+
+impl View for CeilingMenu {
+    fn element(&self) -> Option<&'static str> {
+        Some("ceiling-menu")
+    }
+
+    fn event(&mut self, cx: &mut EventContext, event: &mut Event) {
+        event.map(|menu_event, meta| match menu_event {
+            CeilingMenuEvent::Toggle => {
+                self.open = !self.open;
+                meta.consume();
+            }
+        });
+
+        // left to bubble up to the editor, which applies it
+        event.map(|_: &CeilingEvent, _| self.open = false);
+
+        event.map(|window_event, _| match window_event {
+            WindowEvent::MouseLeave if self.open && !cx.is_over() => self.open = false,
+            WindowEvent::KeyDown(Code::Escape, _) if self.open => self.open = false,
+            _ => {}
+        });
+    }
+}
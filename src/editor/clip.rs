@@ -0,0 +1,43 @@
+use nih_plug_vizia::vizia::prelude::*;
+
+use super::{GUIData, GUIEvent};
+
+/// Counts the overs, output samples the attack envelope didn't bring under
+/// the ceiling and the clip stage had to catch, and stays lit once there are
+/// any. The count starts over with every playback, clicking it clears it too.
+pub(super) struct ClipIndicator;
+
+impl ClipIndicator {
+    pub fn new(cx: &'_ mut Context) -> Handle<'_, Self> {
+        ClipIndicator.build(cx, |cx| {
+            Element::new(cx)
+                .class("clip-light")
+                .toggle_class("latched", GUIData::clips.map(|clips| *clips > 0));
+            let language = GUIData::language.get(cx);
+            Label::new(
+                cx,
+                GUIData::clips.map(move |clips| match clips {
+                    0 => language.text("clips.none").to_string(),
+                    1 => language.text("clips.one").to_string(),
+                    _ => format!("{} {}", clips, language.text("clips.many")),
+                }),
+            );
+        })
+    }
+}
+
+impl View for ClipIndicator {
+    fn element(&self) -> Option<&'static str> {
+        Some("clip-indicator")
+    }
+
+    fn event(&mut self, cx: &mut EventContext, event: &mut Event) {
+        event.map(|window_event, meta| match window_event {
+            WindowEvent::MouseDown(MouseButton::Left) => {
+                cx.emit(GUIEvent::ClearClips);
+                meta.consume();
+            }
+            _ => {}
+        });
+    }
+}
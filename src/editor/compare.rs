@@ -0,0 +1,120 @@
+use nih_plug::prelude::{ParamPtr, Params};
+use nih_plug_vizia::vizia::prelude::*;
+use nih_plug_vizia::widgets::RawParamEvent;
+
+use super::i18n::tr;
+use super::undo::UndoEvent;
+use super::{GUIData, GUIEvent};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(super) enum Slot {
+    #[default]
+    A,
+    B,
+}
+
+impl Slot {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Slot::A => "A",
+            Slot::B => "B",
+        }
+    }
+
+    pub fn other(&self) -> Self {
+        match self {
+            Slot::A => Slot::B,
+            Slot::B => Slot::A,
+        }
+    }
+}
+
+// the normalized value of every parameter
+#[derive(Debug, Clone)]
+struct Snapshot(Vec<(ParamPtr, f32)>);
+
+impl Snapshot {
+    fn take(params: &impl Params) -> Self {
+        Self(
+            params
+                .param_map()
+                .into_iter()
+                // gain match and the test tone have to stay put for the two
+                // slots to compare
+                .filter(|(id, _, _)| id != "gain_match" && id != "test_tone")
+                // the params outlive the editor, so the pointers are still valid
+                .map(|(_, ptr, _)| (ptr, unsafe { ptr.unmodulated_normalized_value() }))
+                .collect(),
+        )
+    }
+
+    // goes through the same events as the widgets, so every change reaches
+    // the host as a regular automation gesture, and switching slots can be
+    // undone in one step
+    fn apply(&self, cx: &mut EventContext) {
+        cx.emit(UndoEvent::BeginGroup { record: true });
+        for (ptr, value) in &self.0 {
+            cx.emit(RawParamEvent::BeginSetParameter(*ptr));
+            cx.emit(RawParamEvent::SetParameterNormalized(*ptr, *value));
+            cx.emit(RawParamEvent::EndSetParameter(*ptr));
+        }
+        cx.emit(UndoEvent::EndGroup);
+    }
+}
+
+/// Two complete parameter states to flip between. The active slot is always
+/// whatever the plugin is currently set to, only the other one is stored.
+#[derive(Debug, Default, Clone)]
+pub(super) struct ABCompare {
+    pub active: Slot,
+    stored: Option<Snapshot>,
+}
+
+impl ABCompare {
+    /// Switches to the other slot. A slot that was never visited starts out as
+    /// a copy of the current one.
+    pub fn toggle(&mut self, cx: &mut EventContext, params: &impl Params) {
+        let current = Snapshot::take(params);
+        if let Some(stored) = self.stored.replace(current) {
+            stored.apply(cx);
+        }
+        self.active = self.active.other();
+    }
+
+    /// Overwrites the other slot with the current settings.
+    pub fn copy_to_other(&mut self, params: &impl Params) {
+        self.stored = Some(Snapshot::take(params));
+    }
+}
+
+impl Data for ABCompare {
+    fn same(&self, other: &Self) -> bool {
+        self.active == other.active && self.stored.is_some() == other.stored.is_some()
+    }
+}
+
+pub(super) fn ab_controls(cx: &mut Context) {
+    HStack::new(cx, |cx| {
+        Button::new(
+            cx,
+            |cx| cx.emit(GUIEvent::ToggleAB),
+            |cx| Label::new(cx, GUIData::ab.map(|ab| ab.active.name().to_string())),
+        )
+        .class("ab-slot");
+        Button::new(
+            cx,
+            |cx| cx.emit(GUIEvent::CopyAB),
+            |cx| {
+                let to = tr(cx, "ab.to");
+                Label::new(
+                    cx,
+                    GUIData::ab.map(move |ab| {
+                        format!("{} {} {}", ab.active.name(), to, ab.active.other().name())
+                    }),
+                )
+            },
+        )
+        .class("ab-copy");
+    })
+    .class("ab-controls");
+}
@@ -0,0 +1,46 @@
+use nih_plug::nih_error;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use super::{GRBuffer, GR_FPS};
+
+/// Asks the user where to save the GR history and writes it out as CSV.
+pub(super) fn export_history(buffer: &GRBuffer) {
+    let Some(path) = rfd::FileDialog::new()
+        .set_title("Export gain reduction history")
+        .set_file_name("limit2zero-history.csv")
+        .add_filter("CSV", &["csv"])
+        .save_file()
+    else {
+        return;
+    };
+
+    if let Err(err) = write_csv(buffer, &path) {
+        nih_error!("Could not export history to {}: {}", path.display(), err);
+    }
+}
+
+// time is in seconds relative to the newest point, so the last row is 0.0
+fn write_csv(buffer: &GRBuffer, path: &Path) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    writeln!(
+        writer,
+        "time_s,pre_l_db,pre_r_db,post_l_db,post_r_db,gr_l_db,gr_r_db,unlinked_gr_l_db,unlinked_gr_r_db"
+    )?;
+
+    let len = buffer.pre.len();
+    let seconds_per_point = buffer.frames_per_point as f32 / GR_FPS;
+    for i in 0..len {
+        let time = -((len - 1 - i) as f32) * seconds_per_point;
+        let (pre, post) = (buffer.pre[i], buffer.post[i]);
+        let (env, channel_env) = (buffer.env[i], buffer.channel_env[i]);
+        writeln!(
+            writer,
+            "{:.3},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2},{:.2}",
+            time, pre[0], pre[1], post[0], post[1], env[0], env[1], channel_env[0], channel_env[1]
+        )?;
+    }
+
+    writer.flush()
+}
@@ -0,0 +1,142 @@
+use nih_plug::prelude::Param;
+use nih_plug_vizia::vizia::prelude::*;
+use nih_plug_vizia::vizia::vg::{Paint, Path};
+use nih_plug_vizia::widgets::param_base::ParamWidgetBase;
+
+use super::lock::param_id;
+use super::{graph_area, FunctionGraph, GUIData};
+
+// how close to the handle a click has to land to grab it
+const GRAB_RADIUS: f32 = 10.0;
+const HANDLE_RADIUS: f32 = 5.0;
+
+/// Draggable point on top of a curve graph. Horizontal moves the S-curve
+/// center, vertical the smoothing amount.
+pub(super) struct CurveHandle {
+    center: ParamWidgetBase,
+    smoothing: ParamWidgetBase,
+    // lock IDs of the center and smoothing params
+    ids: [String; 2],
+    // which of the two params the drag is allowed to move, locked ones stay put
+    drag: Option<[bool; 2]>,
+}
+
+impl CurveHandle {
+    pub fn new(cx: &mut Context, graph: FunctionGraph) -> Handle<'_, Self> {
+        let params = GUIData::params.get(cx);
+        let ptrs = match graph {
+            FunctionGraph::Attack => [
+                params.attack.atk_env_center.as_ptr(),
+                params.attack_smoothing.atk_smooth_amt.as_ptr(),
+            ],
+            FunctionGraph::Release => [
+                params.release.rel_env_center.as_ptr(),
+                params.release.rel_smooth_amt.as_ptr(),
+            ],
+        };
+        let ids = ptrs.map(|ptr| param_id(&params, ptr).unwrap_or_default());
+
+        let (center, smoothing) = match graph {
+            FunctionGraph::Attack => (
+                ParamWidgetBase::new(cx, GUIData::params, |params| &params.attack.atk_env_center),
+                ParamWidgetBase::new(cx, GUIData::params, |params| {
+                    &params.attack_smoothing.atk_smooth_amt
+                }),
+            ),
+            FunctionGraph::Release => (
+                ParamWidgetBase::new(cx, GUIData::params, |params| &params.release.rel_env_center),
+                ParamWidgetBase::new(cx, GUIData::params, |params| &params.release.rel_smooth_amt),
+            ),
+        };
+
+        Self {
+            center,
+            smoothing,
+            ids,
+            drag: None,
+        }
+        .build(cx, |_| {})
+    }
+
+    // the handle in window coordinates
+    fn position(&self, bounds: BoundingBox) -> (f32, f32) {
+        let (left, top, wh) = graph_area(bounds);
+        (
+            left + self.center.modulated_normalized_value() * wh,
+            top + (1.0 - self.smoothing.modulated_normalized_value()) * wh,
+        )
+    }
+}
+
+impl View for CurveHandle {
+    fn element(&self) -> Option<&'static str> {
+        Some("curve-handle")
+    }
+
+    fn event(&mut self, cx: &mut EventContext, event: &mut Event) {
+        event.map(|window_event, meta| match window_event {
+            WindowEvent::MouseDown(MouseButton::Left) => {
+                let (x, y) = self.position(cx.bounds());
+                let (mouse_x, mouse_y) = (cx.mouse().cursorx, cx.mouse().cursory);
+                let grab = GRAB_RADIUS * cx.scale_factor();
+                if (mouse_x - x).hypot(mouse_y - y) > grab {
+                    return;
+                }
+
+                let locks = GUIData::locks.get(cx);
+                let moves = [!locks.contains(&self.ids[0]), !locks.contains(&self.ids[1])];
+                if moves[0] {
+                    self.center.begin_set_parameter(cx);
+                }
+                if moves[1] {
+                    self.smoothing.begin_set_parameter(cx);
+                }
+                self.drag = Some(moves);
+
+                cx.capture();
+                cx.set_active(true);
+                meta.consume();
+            }
+            WindowEvent::MouseMove(x, y) => {
+                if let Some([center, smoothing]) = self.drag {
+                    let (left, top, wh) = graph_area(cx.bounds());
+                    if center {
+                        self.center
+                            .set_normalized_value(cx, ((x - left) / wh).clamp(0.0, 1.0));
+                    }
+                    if smoothing {
+                        self.smoothing
+                            .set_normalized_value(cx, (1.0 - (y - top) / wh).clamp(0.0, 1.0));
+                    }
+                    meta.consume();
+                }
+            }
+            WindowEvent::MouseUp(MouseButton::Left) => {
+                if let Some([center, smoothing]) = self.drag.take() {
+                    if center {
+                        self.center.end_set_parameter(cx);
+                    }
+                    if smoothing {
+                        self.smoothing.end_set_parameter(cx);
+                    }
+                    cx.release();
+                    cx.set_active(false);
+                    meta.consume();
+                }
+            }
+            _ => {}
+        });
+    }
+
+    fn draw(&self, cx: &mut DrawContext, canvas: &mut Canvas) {
+        let (x, y) = self.position(cx.bounds());
+        let palette = GUIData::theme.0.get(cx).palette();
+
+        let mut path = Path::new();
+        path.circle(x, y, HANDLE_RADIUS * cx.scale_factor());
+
+        let mut paint = Paint::color(palette.highlight);
+        paint.set_line_width(if self.drag.is_some() { 3.0 } else { 1.5 });
+        canvas.stroke_path(&path, &paint);
+    }
+}
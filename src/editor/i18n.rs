@@ -0,0 +1,347 @@
+use nih_plug_vizia::vizia::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::GUIData;
+
+/// Language of the editor's labels. Adding one means adding a variant and a
+/// table below, keys missing from a table fall back to English.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) enum Language {
+    #[default]
+    English,
+    German,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::German];
+
+    /// The language's own name for itself.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::German => "Deutsch",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL
+            .iter()
+            .position(|language| *language == self)
+            .unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    fn table(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Language::English => ENGLISH,
+            Language::German => GERMAN,
+        }
+    }
+
+    pub fn text(&self, key: &'static str) -> &'static str {
+        let lookup = |table: &'static [(&'static str, &'static str)]| {
+            table
+                .iter()
+                .find(|(entry, _)| *entry == key)
+                .map(|(_, text)| *text)
+        };
+
+        lookup(self.table())
+            .or_else(|| lookup(ENGLISH))
+            .unwrap_or(key)
+    }
+}
+
+impl Data for Language {
+    fn same(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+/// Looks up `key` in the editor's current language. The whole editor is
+/// rebuilt when the language changes, so this only needs to run at build time.
+pub(super) fn tr(cx: &mut Context, key: &'static str) -> &'static str {
+    GUIData::language.get(cx).text(key)
+}
+
+const ENGLISH: &[(&str, &str)] = &[
+    ("tab.main", "Main"),
+    ("tab.attack", "Attack"),
+    ("tab.release", "Release"),
+    ("tab.detector", "Detector"),
+    ("tab.advanced", "Advanced"),
+    ("search.label", "find"),
+    ("search.clear", "clear"),
+    ("gr.history", "history"),
+    ("gr.range", "range"),
+    ("gr.peaks", "peaks"),
+    ("gr.off", "off"),
+    ("gr.ballistics", "atk/rel"),
+    ("gr.pause", "pause"),
+    ("gr.resume", "resume"),
+    ("gr.linked", "linked"),
+    ("gr.per_channel", "per channel"),
+    ("gr.needle", "needle"),
+    ("gr.trace", "trace"),
+    ("gr.export", "export"),
+    ("scope.scrolling", "scroll"),
+    ("scope.triggered", "trigger"),
+    ("scope.spectrum", "delta spectrum"),
+    ("header.latency", "latency"),
+    ("header.samples", "samples"),
+    ("loudness.drive", "drive"),
+    ("clips.none", "no overs"),
+    ("clips.one", "1 over"),
+    ("clips.many", "overs"),
+    ("undo.undo", "undo"),
+    ("undo.redo", "redo"),
+    ("preset.load", "load"),
+    ("preset.save", "save"),
+    ("ab.to", "to"),
+    ("random.randomize", "randomize"),
+    ("random.mutate", "mutate"),
+    ("random.lock", "lock"),
+    ("random.attack", "attack"),
+    ("random.release", "release"),
+    ("random.smoothing", "smoothing"),
+    ("morph.detail", "to detailed"),
+    ("lock.lock", "lock"),
+    ("lock.locked", "locked"),
+    ("menu.reset", "Reset to default"),
+    ("ceiling.presets", "Ceiling presets"),
+    ("ceiling.sample_01", "-0.1 dBFS"),
+    ("ceiling.true_peak_03", "-0.3 dBTP"),
+    ("ceiling.true_peak_1", "-1.0 dBTP"),
+    ("ceiling.true_peak_2", "-2.0 dBTP (lossy)"),
+    ("menu.enter", "Enter value..."),
+    ("menu.copy", "Copy value"),
+    ("menu.paste", "Paste value"),
+    ("menu.midi_learn", "MIDI learn"),
+    ("menu.midi_cancel", "Cancel MIDI learn"),
+    ("menu.midi_forget", "Forget MIDI CC"),
+    ("menu.macro_assign", "Assign to macro"),
+    ("menu.macro", "Macro"),
+    ("menu.macro_from", "start here"),
+    ("menu.macro_to", "end here"),
+    ("menu.macro_remove", "remove"),
+    ("macro.curve_linear", "linear"),
+    ("macro.curve_slow_start", "slow start"),
+    ("macro.curve_fast_start", "fast start"),
+    ("graph.time", "time"),
+    ("graph.reduction", "reduction"),
+    ("size.small", "S"),
+    ("size.medium", "M"),
+    ("size.large", "L"),
+    ("theme.dark", "dark"),
+    ("theme.light", "light"),
+    ("theme.contrast", "contrast"),
+    ("param.drive", "Drive"),
+    ("param.trim", "trim"),
+    ("param.output_gain", "output"),
+    ("param.stereo_link", "stereo_link"),
+    ("param.width", "width"),
+    ("param.balance_guard", "balance guard"),
+    ("param.attack_shape", "attack shape"),
+    ("param.release_shape", "release shape"),
+    ("param.speed", "speed"),
+    ("param.amount", "amount"),
+    ("param.macro_1", "macro 1"),
+    ("param.macro_2", "macro 2"),
+    ("param.macro_3", "macro 3"),
+    ("param.macro_4", "macro 4"),
+    ("param.attack_amt", "attack_amt"),
+    ("param.attack_bite", "bite"),
+    ("param.atk_env_linearity", "atk_env_linearity"),
+    ("param.atk_env_center", "atk_env_center"),
+    ("param.atk_env_power_in", "atk_env_power_in"),
+    ("param.atk_env_power_out", "atk_env_power_out"),
+    ("param.atk_env_polarity_in", "atk_env_polarity_in"),
+    ("param.atk_env_polarity_out", "atk_env_polarity_out"),
+    ("param.atk_smooth_amt", "atk_smooth_amt"),
+    ("param.atk_morph", "attack morph"),
+    ("param.hold", "hold"),
+    ("param.release", "release"),
+    ("param.hold_level", "hold level"),
+    ("param.release_start", "release start"),
+    ("param.rel_env_linearity", "rel_env_linearity"),
+    ("param.rel_env_center", "rel_env_center"),
+    ("param.rel_env_power_in", "rel_env_power_in"),
+    ("param.rel_env_power_out", "rel_env_power_out"),
+    ("param.rel_env_polarity_in", "rel_env_polarity_in"),
+    ("param.rel_env_polarity_out", "rel_env_polarity_out"),
+    ("param.rel_smooth_amt", "rel_smooth_amt"),
+    ("param.rel_morph", "release morph"),
+    ("param.lookahead", "lookahead"),
+    ("param.lookahead_accuracy", "lookahead_accuracy"),
+    ("param.lookahead_offset", "lookahead offset"),
+    ("param.gr_midi_cc", "GR MIDI CC"),
+    ("param.automation_smoothing", "automation smoothing"),
+    ("param.test_tone_signal", "test tone"),
+    ("param.test_tone_freq", "test tone frequency"),
+    ("param.test_tone_level", "test tone level"),
+    ("param.algorithm", "algorithm"),
+    ("param.detector_weighting", "detector weighting"),
+    ("param.detector_tilt", "detector tilt"),
+    ("param.detector_low", "low"),
+    ("param.detector_mid", "mid"),
+    ("param.detector_mid_freq", "mid freq"),
+    ("param.detector_high", "high"),
+    ("param.gr_slew", "GR slew limit"),
+    ("param.leveling_target", "leveling target"),
+    ("param.leveling_range", "leveling range"),
+    ("param.atk_env_sm_power_in", "atk_env_sm_power_in"),
+    ("param.atk_env_sm_power_out", "atk_env_sm_power_out"),
+    ("param.atk_env_sm_polarity_in", "atk_env_sm_polarity_in"),
+    ("param.atk_env_sm_polarity_out", "atk_env_sm_polarity_out"),
+    ("param.rel_env_sm_power_in", "rel_env_sm_power_in"),
+    ("param.rel_env_sm_power_out", "rel_env_sm_power_out"),
+    ("param.rel_env_sm_polarity_in", "rel_env_sm_polarity_in"),
+    ("param.rel_env_sm_polarity_out", "rel_env_sm_polarity_out"),
+];
+
+const GERMAN: &[(&str, &str)] = &[
+    ("tab.main", "Haupt"),
+    ("tab.attack", "Attack"),
+    ("tab.release", "Release"),
+    ("tab.detector", "Detektor"),
+    ("tab.advanced", "Erweitert"),
+    ("search.label", "Suchen"),
+    ("search.clear", "Leeren"),
+    ("gr.history", "Verlauf"),
+    ("gr.range", "Bereich"),
+    ("gr.peaks", "Spitzen"),
+    ("gr.off", "aus"),
+    ("gr.ballistics", "Atk/Rel"),
+    ("gr.pause", "Pause"),
+    ("gr.resume", "weiter"),
+    ("gr.linked", "gekoppelt"),
+    ("gr.per_channel", "pro Kanal"),
+    ("gr.needle", "Zeiger"),
+    ("gr.trace", "Kurve"),
+    ("gr.export", "Export"),
+    ("scope.scrolling", "laufend"),
+    ("scope.triggered", "Trigger"),
+    ("scope.spectrum", "Delta-Spektrum"),
+    ("header.latency", "Latenz"),
+    ("header.samples", "Samples"),
+    ("loudness.drive", "Drive"),
+    ("clips.none", "keine Overs"),
+    ("clips.one", "1 Over"),
+    ("clips.many", "Overs"),
+    ("undo.undo", "Rückgängig"),
+    ("undo.redo", "Wiederholen"),
+    ("preset.load", "Laden"),
+    ("preset.save", "Sichern"),
+    ("ab.to", "nach"),
+    ("random.randomize", "Zufall"),
+    ("random.mutate", "Variieren"),
+    ("random.lock", "sperren"),
+    ("random.attack", "Attack"),
+    ("random.release", "Release"),
+    ("random.smoothing", "Glättung"),
+    ("morph.detail", "übernehmen"),
+    ("lock.lock", "sperren"),
+    ("lock.locked", "gesperrt"),
+    ("menu.reset", "Auf Standard zurücksetzen"),
+    ("ceiling.presets", "Ceiling-Presets"),
+    ("ceiling.sample_01", "-0,1 dBFS"),
+    ("ceiling.true_peak_03", "-0,3 dBTP"),
+    ("ceiling.true_peak_1", "-1,0 dBTP"),
+    ("ceiling.true_peak_2", "-2,0 dBTP (verlustbehaftet)"),
+    ("menu.enter", "Wert eingeben..."),
+    ("menu.copy", "Wert kopieren"),
+    ("menu.paste", "Wert einfügen"),
+    ("menu.midi_learn", "MIDI lernen"),
+    ("menu.midi_cancel", "MIDI lernen abbrechen"),
+    ("menu.midi_forget", "MIDI-CC vergessen"),
+    ("menu.macro_assign", "Makro zuweisen"),
+    ("menu.macro", "Makro"),
+    ("menu.macro_from", "Start hier"),
+    ("menu.macro_to", "Ende hier"),
+    ("menu.macro_remove", "entfernen"),
+    ("macro.curve_linear", "linear"),
+    ("macro.curve_slow_start", "langsamer Start"),
+    ("macro.curve_fast_start", "schneller Start"),
+    ("graph.time", "Zeit"),
+    ("graph.reduction", "Reduktion"),
+    ("size.small", "K"),
+    ("size.medium", "M"),
+    ("size.large", "G"),
+    ("theme.dark", "dunkel"),
+    ("theme.light", "hell"),
+    ("theme.contrast", "Kontrast"),
+    ("param.drive", "Drive"),
+    ("param.trim", "Trim"),
+    ("param.output_gain", "Ausgang"),
+    ("param.stereo_link", "Stereo-Kopplung"),
+    ("param.width", "Breite"),
+    ("param.balance_guard", "Balance-Schutz"),
+    ("param.attack_shape", "Attack-Form"),
+    ("param.release_shape", "Release-Form"),
+    ("param.speed", "Tempo"),
+    ("param.amount", "Menge"),
+    ("param.macro_1", "Makro 1"),
+    ("param.macro_2", "Makro 2"),
+    ("param.macro_3", "Makro 3"),
+    ("param.macro_4", "Makro 4"),
+    ("param.attack_amt", "Attack-Menge"),
+    ("param.attack_bite", "Biss"),
+    ("param.atk_env_linearity", "Attack-Linearität"),
+    ("param.atk_env_center", "Attack-S-Mitte"),
+    ("param.atk_env_power_in", "Attack-Potenz ein"),
+    ("param.atk_env_power_out", "Attack-Potenz aus"),
+    ("param.atk_env_polarity_in", "Attack-Polarität ein"),
+    ("param.atk_env_polarity_out", "Attack-Polarität aus"),
+    ("param.atk_smooth_amt", "Attack-Glättung"),
+    ("param.atk_morph", "Attack-Morph"),
+    ("param.hold", "Halten"),
+    ("param.release", "Release"),
+    ("param.hold_level", "Halte-Pegel"),
+    ("param.release_start", "Release-Start"),
+    ("param.rel_env_linearity", "Release-Linearität"),
+    ("param.rel_env_center", "Release-S-Mitte"),
+    ("param.rel_env_power_in", "Release-Potenz ein"),
+    ("param.rel_env_power_out", "Release-Potenz aus"),
+    ("param.rel_env_polarity_in", "Release-Polarität ein"),
+    ("param.rel_env_polarity_out", "Release-Polarität aus"),
+    ("param.rel_smooth_amt", "Release-Glättung"),
+    ("param.rel_morph", "Release-Morph"),
+    ("param.lookahead", "Lookahead"),
+    ("param.lookahead_accuracy", "Lookahead-Genauigkeit"),
+    ("param.lookahead_offset", "Lookahead-Versatz"),
+    ("param.gr_midi_cc", "GR-MIDI-CC"),
+    ("param.automation_smoothing", "Automationsglättung"),
+    ("param.test_tone_signal", "Testsignal"),
+    ("param.test_tone_freq", "Testsignal-Frequenz"),
+    ("param.test_tone_level", "Testsignal-Pegel"),
+    ("param.algorithm", "Algorithmus"),
+    ("param.detector_weighting", "Detektor-Gewichtung"),
+    ("param.detector_tilt", "Detektor-Neigung"),
+    ("param.detector_low", "Tiefen"),
+    ("param.detector_mid", "Mitten"),
+    ("param.detector_mid_freq", "Mittenfrequenz"),
+    ("param.detector_high", "Höhen"),
+    ("param.gr_slew", "GR-Anstiegsgrenze"),
+    ("param.leveling_target", "Pegel-Ziel"),
+    ("param.leveling_range", "Pegel-Bereich"),
+    ("param.atk_env_sm_power_in", "Attack-Glättung Potenz ein"),
+    ("param.atk_env_sm_power_out", "Attack-Glättung Potenz aus"),
+    (
+        "param.atk_env_sm_polarity_in",
+        "Attack-Glättung Polarität ein",
+    ),
+    (
+        "param.atk_env_sm_polarity_out",
+        "Attack-Glättung Polarität aus",
+    ),
+    ("param.rel_env_sm_power_in", "Release-Glättung Potenz ein"),
+    ("param.rel_env_sm_power_out", "Release-Glättung Potenz aus"),
+    (
+        "param.rel_env_sm_polarity_in",
+        "Release-Glättung Polarität ein",
+    ),
+    (
+        "param.rel_env_sm_polarity_out",
+        "Release-Glättung Polarität aus",
+    ),
+];
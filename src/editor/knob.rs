@@ -0,0 +1,290 @@
+use nih_plug::prelude::Param;
+use nih_plug_vizia::vizia::prelude::*;
+use nih_plug_vizia::vizia::vg::{LineCap, Paint, Path, Solidity};
+use nih_plug_vizia::widgets::param_base::ParamWidgetBase;
+use std::f32::consts::PI;
+
+use super::GUIData;
+
+/// How far the mouse has to travel vertically to sweep the whole range.
+const DRAG_DISTANCE: f32 = 200.0;
+/// Holding shift while dragging or scrolling slows the knob down by this factor.
+const GRANULAR_DRAG_MULTIPLIER: f32 = 0.1;
+/// Normalized distance a single scroll wheel line moves the knob.
+pub(super) const DEFAULT_SCROLL_STEP: f32 = 0.02;
+
+const ARC_WIDTH: f32 = 4.0;
+const ARC_START: f32 = PI * 0.75;
+const ARC_END: f32 = PI * 2.25;
+
+/// A rotary knob with the formatted value printed in the middle. Alt+click
+/// swaps the value for a text box, parsed with the parameter's
+/// `string_to_value`, and double clicking resets it to its default.
+#[derive(Lens)]
+pub(super) struct ParamKnob {
+    #[lens(ignore)]
+    param_base: ParamWidgetBase,
+    #[lens(ignore)]
+    drag: Option<DragStatus>,
+    #[lens(ignore)]
+    scroll_step: f32,
+    #[lens(ignore)]
+    scrolled_lines: f32,
+    text_input_active: bool,
+}
+
+enum ParamKnobEvent {
+    CancelTextInput,
+    TextInput(String),
+}
+
+// the drag is re-anchored whenever shift is pressed or released, so switching
+// to fine control mid-drag doesn't make the value jump
+#[derive(Debug, Clone, Copy)]
+struct DragStatus {
+    start_y: f32,
+    start_value: f32,
+    granular: bool,
+}
+
+impl ParamKnob {
+    pub fn new<L, Params, P, FMap>(
+        cx: &mut Context,
+        params: L,
+        params_to_param: FMap,
+    ) -> Handle<'_, Self>
+    where
+        L: Lens<Target = Params> + Clone,
+        Params: 'static,
+        P: Param + 'static,
+        FMap: Fn(&Params) -> &P + Copy + 'static,
+    {
+        Self {
+            param_base: ParamWidgetBase::new(cx, params.clone(), params_to_param),
+            drag: None,
+            scroll_step: DEFAULT_SCROLL_STEP,
+            scrolled_lines: 0.0,
+            text_input_active: false,
+        }
+        .build(
+            cx,
+            ParamWidgetBase::build_view(params, params_to_param, |cx, param_data| {
+                Binding::new(
+                    cx,
+                    ParamKnob::text_input_active,
+                    move |cx, text_input_active| {
+                        if text_input_active.get(cx) {
+                            Textbox::new(cx, param_data.make_lens(|param| param.to_string()))
+                                .class("value-entry")
+                                .on_submit(|cx, string, success| {
+                                    if success {
+                                        cx.emit(ParamKnobEvent::TextInput(string))
+                                    } else {
+                                        cx.emit(ParamKnobEvent::CancelTextInput);
+                                    }
+                                })
+                                .on_build(|cx| {
+                                    cx.emit(TextEvent::StartEdit);
+                                    cx.emit(TextEvent::SelectAll);
+                                })
+                                .width(Stretch(1.0))
+                                .top(Stretch(1.0))
+                                .bottom(Stretch(1.0));
+                        } else {
+                            Label::new(cx, param_data.make_lens(|param| param.to_string()))
+                                .class("value")
+                                .width(Stretch(1.0))
+                                .height(Stretch(1.0))
+                                .child_space(Stretch(1.0))
+                                .hoverable(false);
+                        }
+                    },
+                );
+            }),
+        )
+    }
+}
+
+impl ParamKnob {
+    fn reset_to_default(&mut self, cx: &mut EventContext) {
+        self.param_base.begin_set_parameter(cx);
+        self.param_base
+            .set_normalized_value(cx, self.param_base.default_normalized_value());
+        self.param_base.end_set_parameter(cx);
+    }
+}
+
+impl View for ParamKnob {
+    fn element(&self) -> Option<&'static str> {
+        Some("param-knob")
+    }
+
+    fn event(&mut self, cx: &mut EventContext, event: &mut Event) {
+        event.map(|knob_event, meta| match knob_event {
+            ParamKnobEvent::CancelTextInput => {
+                self.text_input_active = false;
+                cx.set_active(false);
+
+                meta.consume();
+            }
+            ParamKnobEvent::TextInput(string) => {
+                if let Some(normalized_value) = self.param_base.string_to_normalized_value(string) {
+                    self.param_base.begin_set_parameter(cx);
+                    self.param_base.set_normalized_value(cx, normalized_value);
+                    self.param_base.end_set_parameter(cx);
+                }
+
+                self.text_input_active = false;
+
+                meta.consume();
+            }
+        });
+
+        event.map(|window_event, meta| match window_event {
+            WindowEvent::MouseDown(MouseButton::Left) if cx.modifiers().alt() => {
+                self.text_input_active = true;
+                cx.set_active(true);
+
+                meta.consume();
+            }
+            // same as nih-plug's sliders, Ctrl+click and double clicks reset the
+            // parameter instead of starting a drag
+            WindowEvent::MouseDown(MouseButton::Left) if cx.modifiers().command() => {
+                self.reset_to_default(cx);
+                meta.consume();
+            }
+            WindowEvent::MouseDoubleClick(MouseButton::Left) if !self.text_input_active => {
+                self.reset_to_default(cx);
+                meta.consume();
+            }
+            WindowEvent::MouseDown(MouseButton::Left) if !self.text_input_active => {
+                self.param_base.begin_set_parameter(cx);
+                self.drag = Some(DragStatus {
+                    start_y: cx.mouse().cursory,
+                    start_value: self.param_base.unmodulated_normalized_value(),
+                    granular: cx.modifiers().shift(),
+                });
+
+                cx.capture();
+                cx.set_active(true);
+                meta.consume();
+            }
+            WindowEvent::MouseMove(_, y) => {
+                if let Some(drag) = &mut self.drag {
+                    let granular = cx.modifiers().shift();
+                    if granular != drag.granular {
+                        *drag = DragStatus {
+                            start_y: *y,
+                            start_value: self.param_base.unmodulated_normalized_value(),
+                            granular,
+                        };
+                    }
+
+                    let speed = if granular {
+                        GRANULAR_DRAG_MULTIPLIER
+                    } else {
+                        1.0
+                    };
+                    let delta = (drag.start_y - *y) / (DRAG_DISTANCE * cx.scale_factor()) * speed;
+
+                    self.param_base
+                        .set_normalized_value(cx, (drag.start_value + delta).clamp(0.0, 1.0));
+                }
+            }
+            WindowEvent::MouseScroll(_, scroll_y) => {
+                self.scrolled_lines += scroll_y;
+
+                if self.scrolled_lines.abs() >= 1.0 {
+                    // stepped params always move a whole step per line
+                    let step = match self.param_base.step_count() {
+                        Some(step_count) => (step_count as f32).recip(),
+                        None if cx.modifiers().shift() => {
+                            self.scroll_step * GRANULAR_DRAG_MULTIPLIER
+                        }
+                        None => self.scroll_step,
+                    };
+                    let lines = self.scrolled_lines.trunc();
+                    self.scrolled_lines -= lines;
+
+                    let value = self.param_base.unmodulated_normalized_value() + lines * step;
+
+                    if self.drag.is_none() {
+                        self.param_base.begin_set_parameter(cx);
+                    }
+                    self.param_base
+                        .set_normalized_value(cx, value.clamp(0.0, 1.0));
+                    if self.drag.is_none() {
+                        self.param_base.end_set_parameter(cx);
+                    }
+                }
+
+                meta.consume();
+            }
+            WindowEvent::MouseUp(MouseButton::Left) => {
+                if self.drag.take().is_some() {
+                    cx.release();
+                    cx.set_active(false);
+                    self.param_base.end_set_parameter(cx);
+                    meta.consume();
+                }
+            }
+            _ => {}
+        });
+    }
+
+    fn draw(&self, cx: &mut DrawContext, canvas: &mut Canvas) {
+        let bounds = cx.bounds();
+        let radius = bounds.w.min(bounds.h) / 2.0 - ARC_WIDTH;
+        if radius <= 0.0 {
+            return;
+        }
+
+        let (center_x, center_y) = (bounds.x + bounds.w / 2.0, bounds.y + bounds.h / 2.0);
+        let value = self.param_base.modulated_normalized_value();
+        let palette = GUIData::theme.0.get(cx).palette();
+        let angle = ARC_START + (ARC_END - ARC_START) * value;
+
+        let mut track = Path::new();
+        track.arc(
+            center_x,
+            center_y,
+            radius,
+            ARC_START,
+            ARC_END,
+            Solidity::Hole,
+        );
+
+        let mut paint_track = Paint::color(palette.track);
+        paint_track.set_line_width(ARC_WIDTH);
+        paint_track.set_line_cap(LineCap::Round);
+        canvas.stroke_path(&track, &paint_track);
+
+        let mut active = Path::new();
+        active.arc(center_x, center_y, radius, ARC_START, angle, Solidity::Hole);
+        active.move_to(
+            center_x + angle.cos() * radius * 0.6,
+            center_y + angle.sin() * radius * 0.6,
+        );
+        active.line_to(
+            center_x + angle.cos() * radius,
+            center_y + angle.sin() * radius,
+        );
+
+        let mut paint_active = Paint::color(palette.accent);
+        paint_active.set_line_width(ARC_WIDTH);
+        paint_active.set_line_cap(LineCap::Round);
+        canvas.stroke_path(&active, &paint_active);
+    }
+}
+
+pub(super) trait ParamKnobExt {
+    /// Normalized distance a single scroll wheel line moves the knob. Skewed
+    /// ranges want a smaller step than the default.
+    fn scroll_step(self, step: f32) -> Self;
+}
+
+impl ParamKnobExt for Handle<'_, ParamKnob> {
+    fn scroll_step(self, step: f32) -> Self {
+        self.modify(|knob: &mut ParamKnob| knob.scroll_step = step)
+    }
+}
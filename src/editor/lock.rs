@@ -0,0 +1,79 @@
+use nih_plug::prelude::{ParamPtr, Params};
+use nih_plug_vizia::vizia::prelude::*;
+use std::collections::HashSet;
+
+use super::GUIData;
+use crate::Limit2zeroParams;
+
+pub(super) enum LockEvent {
+    Toggle(String),
+}
+
+/// Params that can't be changed from the editor until they're unlocked
+/// again, by ID. Stored in the plugin state, host automation still applies.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(super) struct ParamLocks(HashSet<String>);
+
+impl ParamLocks {
+    pub fn load(params: &Limit2zeroParams) -> Self {
+        Self(params.locked_params.read().unwrap().clone())
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.0.contains(id)
+    }
+
+    pub fn contains_ptr(&self, params: &Limit2zeroParams, param: ParamPtr) -> bool {
+        param_id(params, param).map_or(false, |id| self.contains(&id))
+    }
+
+    pub fn toggle(&mut self, params: &Limit2zeroParams, id: &str) {
+        if !self.0.remove(id) {
+            self.0.insert(id.to_string());
+        }
+        *params.locked_params.write().unwrap() = self.0.clone();
+    }
+}
+
+impl Data for ParamLocks {
+    fn same(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+pub(super) fn param_id(params: &Limit2zeroParams, param: ParamPtr) -> Option<String> {
+    params
+        .param_map()
+        .into_iter()
+        .find(|(_, ptr, _)| *ptr == param)
+        .map(|(id, _, _)| id)
+}
+
+pub(super) fn lock_toggle(cx: &mut Context, id: String) {
+    let label_id = id.clone();
+    let class_id = id.clone();
+    Button::new(
+        cx,
+        move |cx| cx.emit(LockEvent::Toggle(id.clone())),
+        move |cx| {
+            let id = label_id.clone();
+            let language = GUIData::language.get(cx);
+            Label::new(
+                cx,
+                GUIData::locks.map(move |locks| {
+                    if locks.contains(&id) {
+                        language.text("lock.locked")
+                    } else {
+                        language.text("lock.lock")
+                    }
+                    .to_string()
+                }),
+            )
+        },
+    )
+    .class("lock-toggle")
+    .toggle_class(
+        "locked",
+        GUIData::locks.map(move |locks| locks.contains(&class_id)),
+    );
+}
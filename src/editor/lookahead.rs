@@ -0,0 +1,122 @@
+use nih_plug_vizia::vizia::prelude::*;
+use nih_plug_vizia::vizia::vg::{Paint, Path};
+use std::array;
+use std::sync::{atomic::Ordering, Arc};
+
+use super::GUIData;
+use crate::{LookaheadSnapshot, LOOKAHEAD_BINS};
+
+// dB range of the bars, anything over 0 dB is a peak the limiter has to catch
+const FLOOR_DB: f32 = -48.0;
+const CEILING_DB: f32 = 12.0;
+
+#[derive(Clone)]
+pub(super) struct LookaheadAtomics(pub Arc<LookaheadSnapshot>);
+
+impl Data for LookaheadAtomics {
+    fn same(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct LookaheadFrame {
+    bins: [[f32; LOOKAHEAD_BINS]; 2],
+    tracked_peak: [f32; 2],
+}
+
+impl Default for LookaheadFrame {
+    fn default() -> Self {
+        Self {
+            bins: [[-100.0; LOOKAHEAD_BINS]; 2],
+            tracked_peak: [-1.0; 2],
+        }
+    }
+}
+
+impl LookaheadFrame {
+    pub fn load(atomics: &LookaheadAtomics) -> Self {
+        let snapshot = &atomics.0;
+        Self {
+            bins: array::from_fn(|channel| {
+                array::from_fn(|bin| snapshot.bins[channel][bin].load(Ordering::Relaxed))
+            }),
+            tracked_peak: array::from_fn(|channel| {
+                snapshot.tracked_peak[channel].load(Ordering::Relaxed)
+            }),
+        }
+    }
+}
+
+impl Data for LookaheadFrame {
+    fn same(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+/// The contents of the lookahead window, oldest samples (the ones about to be
+/// output) on the left. Bins that contain a peak are highlighted and the peak
+/// the attack envelope is currently tracking is marked with a line.
+pub(super) struct LookaheadView;
+
+impl LookaheadView {
+    pub fn new(cx: &'_ mut Context) -> Handle<'_, Self> {
+        LookaheadView.build(cx, |_| {})
+    }
+}
+
+impl View for LookaheadView {
+    fn element(&self) -> Option<&'static str> {
+        Some("lookahead-view")
+    }
+
+    fn draw(&self, cx: &mut DrawContext, canvas: &mut Canvas) {
+        let frame = GUIData::lookahead.0.get(cx);
+
+        let bounds = cx.bounds();
+        let lane_h = bounds.h / 2.0;
+        let bin_w = bounds.w / LOOKAHEAD_BINS as f32;
+
+        let mut quiet = Path::new();
+        let mut peaks = Path::new();
+        let mut zero_lines = Path::new();
+        let mut tracked = Path::new();
+
+        for channel in 0..2 {
+            let bottom = bounds.y + lane_h * (channel + 1) as f32;
+            let db_to_h = |db: f32| {
+                (db.clamp(FLOOR_DB, CEILING_DB) - FLOOR_DB) / (CEILING_DB - FLOOR_DB) * lane_h
+            };
+
+            for (bin, db) in frame.bins[channel].iter().enumerate() {
+                let h = db_to_h(*db);
+                let path = if *db > 0.0 { &mut peaks } else { &mut quiet };
+                path.rect(bounds.x + bin as f32 * bin_w, bottom - h, bin_w * 0.8, h);
+            }
+
+            let zero_y = bottom - db_to_h(0.0);
+            zero_lines.move_to(bounds.x, zero_y);
+            zero_lines.line_to(bounds.x + bounds.w, zero_y);
+
+            let peak = frame.tracked_peak[channel];
+            if peak >= 0.0 {
+                let x = bounds.x + peak * bounds.w;
+                tracked.move_to(x, bottom - lane_h);
+                tracked.line_to(x, bottom);
+            }
+        }
+
+        let palette = GUIData::theme.0.get(cx).palette();
+
+        canvas.fill_path(&quiet, &Paint::color(palette.quiet));
+        canvas.fill_path(&peaks, &Paint::color(palette.env));
+
+        let mut paint_zero = Paint::color(palette.zero);
+        paint_zero.set_line_width(1.0);
+        canvas.stroke_path(&zero_lines, &paint_zero);
+
+        let mut paint_tracked = Paint::color(palette.highlight);
+        paint_tracked.set_line_width(2.0);
+        canvas.stroke_path(&tracked, &paint_tracked);
+    }
+}
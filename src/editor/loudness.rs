@@ -0,0 +1,95 @@
+use nih_plug::prelude::{util, Param};
+use nih_plug_vizia::vizia::prelude::*;
+use nih_plug_vizia::widgets::RawParamEvent;
+use std::sync::{atomic::Ordering, Arc};
+
+use super::i18n::Language;
+use super::undo::UndoEvent;
+use super::{GUIData, GUIEvent};
+use crate::loudness::LoudnessSnapshot;
+use crate::Limit2zeroParams;
+
+#[derive(Clone)]
+pub(super) struct LoudnessAtomics(pub Arc<LoudnessSnapshot>);
+
+impl Data for LoudnessAtomics {
+    fn same(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+// rounded to what the labels show, so tiny changes don't count as activity
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(super) struct LoudnessFrame {
+    lufs: Option<f32>,
+    suggested_drive: f32,
+}
+
+impl LoudnessFrame {
+    pub fn load(atomics: &LoudnessAtomics) -> Self {
+        let round = |value: f32| (value * 10.0).round() / 10.0;
+        let lufs = atomics.0.lufs.load(Ordering::Relaxed);
+        Self {
+            lufs: lufs.is_finite().then(|| round(lufs)),
+            suggested_drive: round(atomics.0.suggested_drive.load(Ordering::Relaxed)),
+        }
+    }
+
+    fn lufs_text(&self) -> String {
+        match self.lufs {
+            Some(lufs) => format!("{lufs:.1} LUFS"),
+            None => String::from("-- LUFS"),
+        }
+    }
+
+    fn drive_text(&self, language: Language) -> String {
+        format!(
+            "{} +{:.1} dB",
+            language.text("loudness.drive"),
+            self.suggested_drive
+        )
+    }
+
+    /// Sets drive to the suggestion, as one undo step.
+    pub fn apply(&self, cx: &mut EventContext, params: &Limit2zeroParams) {
+        if self.lufs.is_none() {
+            return;
+        }
+        let drive = &params.input.drive;
+        let value = drive.preview_normalized(util::db_to_gain(self.suggested_drive));
+
+        cx.emit(UndoEvent::BeginGroup { record: true });
+        cx.emit(RawParamEvent::BeginSetParameter(drive.as_ptr()));
+        cx.emit(RawParamEvent::SetParameterNormalized(drive.as_ptr(), value));
+        cx.emit(RawParamEvent::EndSetParameter(drive.as_ptr()));
+        cx.emit(UndoEvent::EndGroup);
+    }
+}
+
+impl Data for LoudnessFrame {
+    fn same(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+/// The input's measured loudness and the drive that would bring it to the
+/// target, which sets drive when clicked.
+pub(super) fn loudness_controls(cx: &mut Context) {
+    let language = GUIData::language.get(cx);
+    HStack::new(cx, |cx| {
+        Label::new(cx, GUIData::loudness.map(|loudness| loudness.lufs_text()))
+            .class("loudness-lufs");
+        Button::new(
+            cx,
+            |cx| cx.emit(GUIEvent::ApplySuggestedDrive),
+            move |cx| {
+                Label::new(
+                    cx,
+                    GUIData::loudness.map(move |loudness| loudness.drive_text(language)),
+                )
+            },
+        )
+        .class("loudness-drive");
+    })
+    .class("loudness-controls");
+}
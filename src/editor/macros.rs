@@ -0,0 +1,51 @@
+use nih_plug_vizia::vizia::prelude::*;
+
+use crate::macro_knobs::{MacroMap, MacroTable};
+use crate::Limit2zeroParams;
+
+/// Changes to the macro assignments, by param ID. The start and end take the
+/// param's current normalized value.
+pub(super) enum MacroEvent {
+    Assign(usize, String),
+    Remove(String),
+    SetFrom(String, f32),
+    SetTo(String, f32),
+    CycleCurve(String),
+}
+
+impl MacroEvent {
+    /// Applies it to the plugin's mapping and hands that to the audio
+    /// thread, gives the editor's new copy.
+    pub fn apply(&self, params: &Limit2zeroParams) -> MacroMap {
+        let mut map = params.macro_map.write().unwrap();
+        match self {
+            MacroEvent::Assign(knob, id) => map.assign(*knob, id.clone()),
+            MacroEvent::Remove(id) => map.remove(id),
+            MacroEvent::SetFrom(id, value) => {
+                if let Some(target) = map.target_mut(id) {
+                    target.from = *value;
+                }
+            }
+            MacroEvent::SetTo(id, value) => {
+                if let Some(target) = map.target_mut(id) {
+                    target.to = *value;
+                }
+            }
+            MacroEvent::CycleCurve(id) => {
+                if let Some(target) = map.target_mut(id) {
+                    target.curve = target.curve.next();
+                }
+            }
+        }
+        let copy = map.clone();
+        drop(map);
+        MacroTable::publish(params);
+        copy
+    }
+}
+
+impl Data for MacroMap {
+    fn same(&self, other: &Self) -> bool {
+        self == other
+    }
+}
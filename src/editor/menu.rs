@@ -0,0 +1,260 @@
+use nih_plug::prelude::Param;
+use nih_plug_vizia::vizia::prelude::*;
+use nih_plug_vizia::widgets::param_base::ParamWidgetBase;
+
+use super::i18n::tr;
+use super::macros::MacroEvent;
+use super::midi::MidiEvent;
+use super::{GUIData, GUIEvent};
+use crate::macro_knobs::MACRO_KNOBS;
+
+/// Wraps a parameter widget with a right click menu for resetting, typing in,
+/// copying and pasting its value, for mapping a MIDI CC to it and for
+/// assigning it to a macro knob. Copied values are kept as text so they can
+/// be pasted into any other param that parses them.
+#[derive(Lens)]
+pub(super) struct ParamMenu {
+    #[lens(ignore)]
+    param_base: ParamWidgetBase,
+    #[lens(ignore)]
+    id: String,
+    open: bool,
+    entering: bool,
+    entry: String,
+}
+
+#[derive(Clone)]
+enum ParamMenuEvent {
+    Reset,
+    StartEntry,
+    Entry(String),
+    Copy,
+    Paste,
+    MidiLearn,
+    MidiForget,
+    MacroAssign(usize),
+    MacroFrom,
+    MacroTo,
+    MacroCurve,
+    MacroRemove,
+    Close,
+}
+
+impl ParamMenu {
+    pub fn new<L, Params, P, FMap>(
+        cx: &mut Context,
+        params: L,
+        params_to_param: FMap,
+        id: String,
+        content: impl FnOnce(&mut Context),
+    ) -> Handle<'_, Self>
+    where
+        L: Lens<Target = Params> + Clone,
+        Params: 'static,
+        P: Param + 'static,
+        FMap: Fn(&Params) -> &P + Copy + 'static,
+    {
+        let id_for_menu = id.clone();
+        Self {
+            param_base: ParamWidgetBase::new(cx, params, params_to_param),
+            id,
+            open: false,
+            entering: false,
+            entry: String::new(),
+        }
+        .build(cx, |cx| {
+            content(cx);
+            Binding::new(cx, ParamMenu::open, move |cx, open| {
+                if open.get(cx) {
+                    menu(cx, &id_for_menu);
+                }
+            });
+        })
+    }
+
+    fn set_value(&self, cx: &mut EventContext, normalized_value: f32) {
+        self.param_base.begin_set_parameter(cx);
+        self.param_base.set_normalized_value(cx, normalized_value);
+        self.param_base.end_set_parameter(cx);
+    }
+
+    fn formatted_value(&self) -> String {
+        self.param_base
+            .normalized_value_to_string(self.param_base.unmodulated_normalized_value(), true)
+    }
+}
+
+fn menu(cx: &mut Context, id: &str) {
+    let midi = GUIData::midi.get(cx);
+    let id = id.to_string();
+    VStack::new(cx, |cx| {
+        Binding::new(cx, ParamMenu::entering, move |cx, entering| {
+            if entering.get(cx) {
+                Textbox::new(cx, ParamMenu::entry)
+                    .on_submit(|cx, string, success| {
+                        if success {
+                            cx.emit(ParamMenuEvent::Entry(string));
+                        } else {
+                            cx.emit(ParamMenuEvent::Close);
+                        }
+                    })
+                    .on_build(|cx| {
+                        cx.emit(TextEvent::StartEdit);
+                        cx.emit(TextEvent::SelectAll);
+                    })
+                    .width(Stretch(1.0));
+            } else {
+                menu_entry(cx, tr(cx, "menu.reset"), ParamMenuEvent::Reset);
+                menu_entry(cx, tr(cx, "menu.enter"), ParamMenuEvent::StartEntry);
+                menu_entry(cx, tr(cx, "menu.copy"), ParamMenuEvent::Copy);
+                menu_entry(cx, tr(cx, "menu.paste"), ParamMenuEvent::Paste)
+                    .disabled(GUIData::param_clipboard.map(|clipboard| clipboard.is_none()));
+                if midi.is_learning(&id) {
+                    menu_entry(cx, tr(cx, "menu.midi_cancel"), ParamMenuEvent::MidiLearn);
+                } else {
+                    menu_entry(cx, tr(cx, "menu.midi_learn"), ParamMenuEvent::MidiLearn);
+                }
+                if let Some(cc) = midi.cc_for(&id) {
+                    let label = format!("{} {cc}", tr(cx, "menu.midi_forget"));
+                    text_entry(cx, label, ParamMenuEvent::MidiForget);
+                }
+                macro_entries(cx, &id);
+            }
+        });
+    })
+    .class("param-menu");
+}
+
+// the macros can't drive each other
+fn macro_entries(cx: &mut Context, id: &str) {
+    if id.starts_with("macro_") {
+        return;
+    }
+    let macros = GUIData::macros.get(cx);
+    match macros.target(id) {
+        Some((knob, target)) => {
+            let name = format!("{} {}", tr(cx, "menu.macro"), knob + 1);
+            for (key, event) in [
+                ("menu.macro_from", ParamMenuEvent::MacroFrom),
+                ("menu.macro_to", ParamMenuEvent::MacroTo),
+                (target.curve.name(), ParamMenuEvent::MacroCurve),
+                ("menu.macro_remove", ParamMenuEvent::MacroRemove),
+            ] {
+                let label = format!("{name}: {}", tr(cx, key));
+                text_entry(cx, label, event);
+            }
+        }
+        None => {
+            for knob in 0..MACRO_KNOBS {
+                let label = format!("{} {}", tr(cx, "menu.macro_assign"), knob + 1);
+                text_entry(cx, label, ParamMenuEvent::MacroAssign(knob));
+            }
+        }
+    }
+}
+
+// for labels put together at build time
+fn text_entry(cx: &mut Context, label: String, event: ParamMenuEvent) {
+    Button::new(
+        cx,
+        move |cx| cx.emit(event.clone()),
+        move |cx| Label::new(cx, label),
+    )
+    .class("param-menu-entry");
+}
+
+fn menu_entry<'a>(
+    cx: &'a mut Context,
+    label: &'static str,
+    event: ParamMenuEvent,
+) -> Handle<'a, Button> {
+    Button::new(
+        cx,
+        move |cx| cx.emit(event.clone()),
+        move |cx| Label::new(cx, label),
+    )
+    .class("param-menu-entry")
+}
+
+impl View for ParamMenu {
+    fn element(&self) -> Option<&'static str> {
+        Some("param-menu-host")
+    }
+
+    fn event(&mut self, cx: &mut EventContext, event: &mut Event) {
+        event.map(|menu_event, meta| {
+            match menu_event {
+                ParamMenuEvent::Reset => {
+                    self.set_value(cx, self.param_base.default_normalized_value())
+                }
+                ParamMenuEvent::StartEntry => {
+                    self.entry = self.formatted_value();
+                    self.entering = true;
+                    meta.consume();
+                    return;
+                }
+                ParamMenuEvent::Entry(string) => {
+                    if let Some(value) = self.param_base.string_to_normalized_value(string) {
+                        self.set_value(cx, value);
+                    }
+                }
+                ParamMenuEvent::Copy => cx.emit(GUIEvent::CopyParamValue(self.formatted_value())),
+                ParamMenuEvent::Paste => {
+                    let value = GUIData::param_clipboard
+                        .get(cx)
+                        .and_then(|string| self.param_base.string_to_normalized_value(&string));
+                    if let Some(value) = value {
+                        self.set_value(cx, value);
+                    }
+                }
+                ParamMenuEvent::MidiLearn => {
+                    if GUIData::midi.get(cx).is_learning(&self.id) {
+                        cx.emit(MidiEvent::CancelLearn);
+                    } else {
+                        cx.emit(MidiEvent::Learn(self.id.clone()));
+                    }
+                }
+                ParamMenuEvent::MidiForget => cx.emit(MidiEvent::Forget(self.id.clone())),
+                ParamMenuEvent::MacroAssign(knob) => {
+                    cx.emit(MacroEvent::Assign(*knob, self.id.clone()))
+                }
+                ParamMenuEvent::MacroFrom => cx.emit(MacroEvent::SetFrom(
+                    self.id.clone(),
+                    self.param_base.unmodulated_normalized_value(),
+                )),
+                ParamMenuEvent::MacroTo => cx.emit(MacroEvent::SetTo(
+                    self.id.clone(),
+                    self.param_base.unmodulated_normalized_value(),
+                )),
+                ParamMenuEvent::MacroCurve => cx.emit(MacroEvent::CycleCurve(self.id.clone())),
+                ParamMenuEvent::MacroRemove => cx.emit(MacroEvent::Remove(self.id.clone())),
+                ParamMenuEvent::Close => (),
+            }
+
+            self.open = false;
+            self.entering = false;
+            meta.consume();
+        });
+
+        event.map(|window_event, meta| match window_event {
+            WindowEvent::MouseDown(MouseButton::Right) => {
+                // locked params can't be changed from here either
+                if !GUIData::locks.get(cx).contains(&self.id) {
+                    self.open = true;
+                    self.entering = false;
+                }
+                meta.consume();
+            }
+            // the menu is a child, so moving onto it doesn't count as leaving
+            WindowEvent::MouseLeave if self.open && !self.entering && !cx.is_over() => {
+                self.open = false;
+            }
+            WindowEvent::KeyDown(Code::Escape, _) if self.open => {
+                self.open = false;
+                self.entering = false;
+                meta.consume();
+            }
+            _ => {}
+        });
+    }
+}
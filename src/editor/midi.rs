@@ -0,0 +1,68 @@
+use nih_plug_vizia::vizia::prelude::*;
+use std::sync::atomic::Ordering;
+
+use crate::midi::{CcTable, MidiMap, NO_CC};
+use crate::Limit2zeroParams;
+
+pub(super) enum MidiEvent {
+    Learn(String),
+    Forget(String),
+    CancelLearn,
+}
+
+/// The editor's copy of the MIDI mapping and of which param is waiting for a
+/// CC. The audio thread notes the CC that comes in, so learning is finished
+/// and this reloaded on every frame.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(super) struct MidiLearn {
+    map: MidiMap,
+    learning: Option<String>,
+}
+
+impl MidiLearn {
+    pub fn load(params: &Limit2zeroParams) -> Self {
+        Self {
+            map: params.midi_map.read().unwrap().clone(),
+            learning: params.midi_learn.read().unwrap().clone(),
+        }
+    }
+
+    pub fn cc_for(&self, id: &str) -> Option<u8> {
+        self.map.cc_for(id)
+    }
+
+    pub fn is_learning(&self, id: &str) -> bool {
+        self.learning.as_deref() == Some(id)
+    }
+
+    pub fn event(&mut self, params: &Limit2zeroParams, event: &MidiEvent) {
+        match event {
+            MidiEvent::Learn(id) => {
+                // a CC from before doesn't count
+                params.midi_learned.store(NO_CC, Ordering::Relaxed);
+                *params.midi_learn.write().unwrap() = Some(id.clone());
+            }
+            MidiEvent::Forget(id) => {
+                params.midi_map.write().unwrap().forget(id);
+                CcTable::publish(params);
+            }
+            MidiEvent::CancelLearn => *params.midi_learn.write().unwrap() = None,
+        }
+        *self = Self::load(params);
+    }
+
+    // only replaces itself when something changed, so bindings don't rebuild
+    pub fn refresh(&mut self, params: &Limit2zeroParams) {
+        CcTable::finish_learning(params);
+        let current = Self::load(params);
+        if current != *self {
+            *self = current;
+        }
+    }
+}
+
+impl Data for MidiLearn {
+    fn same(&self, other: &Self) -> bool {
+        self == other
+    }
+}
@@ -0,0 +1,118 @@
+use nih_plug::prelude::{FloatParam, Param};
+use nih_plug_vizia::vizia::prelude::*;
+use nih_plug_vizia::widgets::RawParamEvent;
+
+use super::i18n::tr;
+use super::undo::UndoEvent;
+use crate::engine::{EnvelopeShape, ATTACK_MORPHS, RELEASE_MORPHS};
+use crate::Limit2zeroParams;
+
+pub(super) enum MorphEvent {
+    /// Writes the morphed curve into the detailed params and switches the
+    /// morph off, so fine-tuning starts from what was just auditioned.
+    Detail(Morph),
+}
+
+/// The curves with a morph control.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Morph {
+    Attack,
+    Release,
+}
+
+impl Morph {
+    pub fn detail(&self, cx: &mut EventContext, params: &Limit2zeroParams) {
+        match self {
+            Morph::Attack => {
+                let attack = &params.attack;
+                let smoothing = &params.attack_smoothing;
+                let shape = EnvelopeShape::morph(&ATTACK_MORPHS, attack.atk_morph.value());
+
+                cx.emit(UndoEvent::BeginGroup { record: true });
+                write_shape(
+                    cx,
+                    &shape,
+                    [
+                        &attack.atk_env_linearity,
+                        &attack.atk_env_center,
+                        &smoothing.atk_smooth_amt,
+                        &attack.atk_env_polarity_in,
+                        &attack.atk_env_polarity_out,
+                        &attack.atk_env_power_in,
+                        &attack.atk_env_power_out,
+                        &smoothing.atk_env_sm_polarity_in,
+                        &smoothing.atk_env_sm_polarity_out,
+                        &smoothing.atk_env_sm_power_in,
+                        &smoothing.atk_env_sm_power_out,
+                    ],
+                );
+                set(cx, &attack.atk_morph_on, false);
+                cx.emit(UndoEvent::EndGroup);
+            }
+            Morph::Release => {
+                let release = &params.release;
+                let shape = EnvelopeShape::morph(&RELEASE_MORPHS, release.rel_morph.value());
+
+                // one undo step for the whole curve and the switch
+                cx.emit(UndoEvent::BeginGroup { record: true });
+                write_shape(
+                    cx,
+                    &shape,
+                    [
+                        &release.rel_env_linearity,
+                        &release.rel_env_center,
+                        &release.rel_smooth_amt,
+                        &release.rel_env_polarity_in,
+                        &release.rel_env_polarity_out,
+                        &release.rel_env_power_in,
+                        &release.rel_env_power_out,
+                        &release.rel_env_sm_polarity_in,
+                        &release.rel_env_sm_polarity_out,
+                        &release.rel_env_sm_power_in,
+                        &release.rel_env_sm_power_out,
+                    ],
+                );
+                set(cx, &release.rel_morph_on, false);
+                cx.emit(UndoEvent::EndGroup);
+            }
+        }
+    }
+}
+
+// in the order of the shape's fields
+fn write_shape(cx: &mut EventContext, shape: &EnvelopeShape, params: [&FloatParam; 11]) {
+    let values = [
+        shape.linearity,
+        shape.center,
+        shape.smooth_amount,
+        shape.polarity_in,
+        shape.polarity_out,
+        shape.power_in,
+        shape.power_out,
+        shape.sm_polarity_in,
+        shape.sm_polarity_out,
+        shape.sm_power_in,
+        shape.sm_power_out,
+    ];
+    for (param, value) in params.into_iter().zip(values) {
+        set(cx, param, value);
+    }
+}
+
+fn set<P: Param>(cx: &mut EventContext, param: &P, value: P::Plain) {
+    let ptr = param.as_ptr();
+    cx.emit(RawParamEvent::BeginSetParameter(ptr));
+    cx.emit(RawParamEvent::SetParameterNormalized(
+        ptr,
+        param.preview_normalized(value),
+    ));
+    cx.emit(RawParamEvent::EndSetParameter(ptr));
+}
+
+pub(super) fn detail_button(cx: &mut Context, morph: Morph) {
+    Button::new(
+        cx,
+        move |cx| cx.emit(MorphEvent::Detail(morph)),
+        |cx| Label::new(cx, tr(cx, "morph.detail")),
+    );
+}
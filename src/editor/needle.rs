@@ -0,0 +1,115 @@
+use nih_plug_vizia::vizia::prelude::*;
+use nih_plug_vizia::vizia::vg::{LineCap, Paint, Path, Solidity};
+use std::f32::consts::PI;
+
+use super::{GUIData, GR_FPS};
+
+// the needle sweeps from 0 dB on the right down to this on the left
+const FLOOR_DB: f32 = -20.0;
+const TICKS_DB: [f32; 9] = [0.0, -1.0, -2.0, -3.0, -5.0, -7.0, -10.0, -15.0, -20.0];
+
+// half the sweep, measured from straight up
+const SWEEP: f32 = PI * 0.3;
+
+// a damped spring tuned like a VU movement, it reaches its reading in about
+// 300 ms and overshoots it by a hair
+const NATURAL_FREQ: f32 = 2.0 * PI * 2.1;
+const DAMPING: f32 = 0.8;
+
+/// Position and velocity of the needle in dB of gain reduction.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(super) struct Needle {
+    db: f32,
+    velocity: f32,
+}
+
+impl Needle {
+    /// Moves the needle towards `target` for the given number of frames.
+    pub fn update(&mut self, target: f32, frames: usize) {
+        let target = target.clamp(FLOOR_DB, 0.0);
+        let dt = GR_FPS.recip();
+        for _ in 0..frames {
+            let accel = NATURAL_FREQ * NATURAL_FREQ * (target - self.db)
+                - 2.0 * DAMPING * NATURAL_FREQ * self.velocity;
+            self.velocity += accel * dt;
+            self.db += self.velocity * dt;
+        }
+    }
+}
+
+impl Data for Needle {
+    fn same(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+/// Gain reduction on an analog style meter, as an alternative to the history.
+pub(super) struct NeedleMeter;
+
+impl NeedleMeter {
+    pub fn new(cx: &'_ mut Context) -> Handle<'_, Self> {
+        NeedleMeter.build(cx, |_| {})
+    }
+}
+
+fn db_to_angle(db: f32) -> f32 {
+    // straight up is -PI/2 in canvas coordinates
+    let t = (db.clamp(FLOOR_DB, 0.0) - FLOOR_DB) / -FLOOR_DB;
+    -PI / 2.0 - SWEEP + t * SWEEP * 2.0
+}
+
+impl View for NeedleMeter {
+    fn element(&self) -> Option<&'static str> {
+        Some("needle-meter")
+    }
+
+    fn draw(&self, cx: &mut DrawContext, canvas: &mut Canvas) {
+        let bounds = cx.bounds();
+        let palette = GUIData::theme.0.get(cx).palette();
+        let needle = GUIData::needle.0.get(cx);
+
+        // pivot sits below the bottom edge like on a real meter face
+        let radius = f32::min(bounds.w * 0.45 / SWEEP.sin(), bounds.h * 0.9);
+        if radius <= 0.0 {
+            return;
+        }
+        let (pivot_x, pivot_y) = (
+            bounds.x + bounds.w / 2.0,
+            bounds.y + bounds.h * 0.05 + radius,
+        );
+        let at = |angle: f32, r: f32| (pivot_x + angle.cos() * r, pivot_y + angle.sin() * r);
+
+        let mut scale = Path::new();
+        scale.arc(
+            pivot_x,
+            pivot_y,
+            radius * 0.9,
+            db_to_angle(FLOOR_DB),
+            db_to_angle(0.0),
+            Solidity::Hole,
+        );
+        for db in TICKS_DB {
+            // every 10 dB gets a longer tick
+            let length = if db % 10.0 == 0.0 { 0.14 } else { 0.08 };
+            let (x0, y0) = at(db_to_angle(db), radius * 0.9);
+            let (x1, y1) = at(db_to_angle(db), radius * (0.9 - length));
+            scale.move_to(x0, y0);
+            scale.line_to(x1, y1);
+        }
+
+        let mut paint_scale = Paint::color(palette.zero);
+        paint_scale.set_line_width(1.5);
+        canvas.stroke_path(&scale, &paint_scale);
+
+        let (tip_x, tip_y) = at(db_to_angle(needle.db), radius * 0.95);
+        let (base_x, base_y) = at(db_to_angle(needle.db), radius * 0.3);
+        let mut path_needle = Path::new();
+        path_needle.move_to(base_x, base_y);
+        path_needle.line_to(tip_x, tip_y);
+
+        let mut paint_needle = Paint::color(palette.env);
+        paint_needle.set_line_width(palette.stroke_width);
+        paint_needle.set_line_cap(LineCap::Round);
+        canvas.stroke_path(&path_needle, &paint_needle);
+    }
+}
@@ -0,0 +1,494 @@
+use nih_plug::prelude::Param;
+use nih_plug_vizia::vizia::prelude::*;
+use nih_plug_vizia::widgets::*;
+use std::sync::Arc;
+
+use super::ceiling::CeilingMenu;
+use super::i18n::tr;
+use super::knob::{ParamKnob, ParamKnobExt, DEFAULT_SCROLL_STEP};
+use super::lock::{lock_toggle, param_id};
+use super::lookahead::LookaheadView;
+use super::menu::ParamMenu;
+use super::morph::{detail_button, Morph};
+use super::randomize::randomize_controls;
+use super::transfer::TransferCurve;
+use super::{GUIData, GUIEvent};
+use crate::Limit2zeroParams;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(super) enum Tab {
+    #[default]
+    Main,
+    Attack,
+    Release,
+    Detector,
+    Advanced,
+}
+
+impl Tab {
+    pub const ALL: [Tab; 5] = [
+        Tab::Main,
+        Tab::Attack,
+        Tab::Release,
+        Tab::Detector,
+        Tab::Advanced,
+    ];
+
+    /// Key of the tab's label in the string table.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Tab::Main => "tab.main",
+            Tab::Attack => "tab.attack",
+            Tab::Release => "tab.release",
+            Tab::Detector => "tab.detector",
+            Tab::Advanced => "tab.advanced",
+        }
+    }
+
+    /// The pages that only hold detailed params, which do nothing in simple mode.
+    pub fn detailed(&self) -> bool {
+        matches!(self, Tab::Attack | Tab::Release | Tab::Advanced)
+    }
+}
+
+impl Data for Tab {
+    fn same(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+pub(super) fn tab_bar(cx: &mut Context) {
+    Binding::new(cx, GUIData::advanced, |cx, advanced| {
+        let advanced = advanced.get(cx);
+        HStack::new(cx, |cx| {
+            for tab in Tab::ALL
+                .into_iter()
+                .filter(|tab| advanced || !tab.detailed())
+            {
+                Button::new(
+                    cx,
+                    move |cx| cx.emit(GUIEvent::SetTab(tab)),
+                    move |cx| Label::new(cx, tr(cx, tab.name())),
+                )
+                .class("tab")
+                .toggle_class(
+                    "selected",
+                    GUIData::tab.map(move |selected| *selected == tab),
+                );
+            }
+        })
+        .class("tab-bar");
+    });
+}
+
+/// A box that narrows the page down to the sliders and knobs whose label,
+/// name or ID has what's typed in it.
+pub(super) fn param_search(cx: &mut Context) {
+    HStack::new(cx, |cx| {
+        Label::new(cx, tr(cx, "search.label"));
+        Textbox::new(cx, GUIData::param_filter)
+            .on_edit(|cx, text| cx.emit(GUIEvent::SetParamFilter(text)));
+        Button::new(
+            cx,
+            |cx| cx.emit(GUIEvent::SetParamFilter(String::new())),
+            |cx| Label::new(cx, tr(cx, "search.clear")),
+        );
+    })
+    .class("param-search");
+}
+
+// ignoring case, and `_` the same as a space so IDs can be typed either way
+fn param_filter(filter: &str, texts: &[String]) -> bool {
+    let normalize = |text: &str| text.to_lowercase().replace('_', " ");
+    let filter = normalize(filter.trim());
+    filter.is_empty() || texts.iter().any(|text| normalize(text).contains(&filter))
+}
+
+// whether the param shows under the current filter, by its label, name and ID
+fn filter_lens<P, FMap>(
+    cx: &mut Context,
+    label: &'static str,
+    id: &str,
+    params_to_param: FMap,
+) -> impl Lens<Target = bool> + Clone
+where
+    P: Param + 'static,
+    FMap: Fn(&Arc<Limit2zeroParams>) -> &P + Copy + 'static,
+{
+    let params = GUIData::params.get(cx);
+    let texts = [
+        tr(cx, label).to_string(),
+        params_to_param(&params).name().to_string(),
+        id.to_string(),
+    ];
+    GUIData::param_filter.map(move |filter| param_filter(filter, &texts))
+}
+
+pub(super) fn tab_page(cx: &mut Context) {
+    Binding::new(cx, GUIData::tab, |cx, tab| {
+        VStack::new(cx, |cx| match tab.get(cx) {
+            Tab::Main => main_page(cx),
+            Tab::Attack => attack_page(cx),
+            Tab::Release => release_page(cx),
+            Tab::Detector => detector_page(cx),
+            Tab::Advanced => advanced_page(cx),
+        })
+        .class("tab-page")
+        .height(Auto);
+    });
+}
+
+// the ID the param's lock is stored under
+fn widget_param_id<P, FMap>(cx: &mut Context, params_to_param: FMap) -> String
+where
+    P: Param + 'static,
+    FMap: Fn(&Arc<Limit2zeroParams>) -> &P + Copy + 'static,
+{
+    let params = GUIData::params.get(cx);
+    param_id(&params, params_to_param(&params).as_ptr()).unwrap_or_default()
+}
+
+// `label` is a key in the string table
+fn param_slider<P, FMap>(cx: &mut Context, label: &'static str, params_to_param: FMap)
+where
+    P: Param + 'static,
+    FMap: Fn(&Arc<Limit2zeroParams>) -> &P + Copy + 'static,
+{
+    let id = widget_param_id(cx, params_to_param);
+    let shown = filter_lens(cx, label, &id, params_to_param);
+    let lock_id = id.clone();
+    HStack::new(cx, move |cx| {
+        Label::new(cx, tr(cx, label));
+        lock_toggle(cx, lock_id);
+    })
+    .class("param-label-row")
+    .display(shown.clone());
+    // locked widgets stop taking mouse input altogether
+    let lock_id = id.clone();
+    ParamMenu::new(cx, GUIData::params, params_to_param, id, move |cx| {
+        ParamSlider::new(cx, GUIData::params, params_to_param)
+            .hoverable(GUIData::locks.map(move |locks| !locks.contains(&lock_id)));
+    })
+    .height(Auto)
+    .display(shown);
+}
+
+// the skewed power ranges need a much finer scroll step to land on useful values
+const POWER_STEP: f32 = 0.005;
+
+fn param_knob<P, FMap>(
+    cx: &mut Context,
+    label: &'static str,
+    scroll_step: f32,
+    params_to_param: FMap,
+) where
+    P: Param + 'static,
+    FMap: Fn(&Arc<Limit2zeroParams>) -> &P + Copy + 'static,
+{
+    let id = widget_param_id(cx, params_to_param);
+    let shown = filter_lens(cx, label, &id, params_to_param);
+    VStack::new(cx, move |cx| {
+        let lock_id = id.clone();
+        HStack::new(cx, move |cx| {
+            Label::new(cx, tr(cx, label)).class("knob-label");
+            lock_toggle(cx, lock_id);
+        })
+        .class("param-label-row");
+        let lock_id = id.clone();
+        ParamMenu::new(cx, GUIData::params, params_to_param, id, move |cx| {
+            ParamKnob::new(cx, GUIData::params, params_to_param)
+                .scroll_step(scroll_step)
+                .hoverable(GUIData::locks.map(move |locks| !locks.contains(&lock_id)));
+        })
+        .size(Auto);
+    })
+    .class("knob-cell")
+    .display(shown);
+}
+
+fn main_page(cx: &mut Context) {
+    HStack::new(cx, |cx| {
+        param_knob(cx, "param.drive", DEFAULT_SCROLL_STEP, |params| {
+            &params.input.drive
+        });
+        param_knob(cx, "param.trim", DEFAULT_SCROLL_STEP, |params| {
+            &params.output.trim
+        });
+        param_knob(cx, "param.output_gain", DEFAULT_SCROLL_STEP, |params| {
+            &params.output.output_gain
+        });
+        param_knob(cx, "param.stereo_link", DEFAULT_SCROLL_STEP, |params| {
+            &params.input.stereo_link
+        });
+        param_knob(cx, "param.width", DEFAULT_SCROLL_STEP, |params| {
+            &params.output.width
+        });
+        param_knob(cx, "param.balance_guard", DEFAULT_SCROLL_STEP, |params| {
+            &params.input.balance_guard
+        });
+    })
+    .class("knob-row");
+    CeilingMenu::new(cx);
+    Binding::new(cx, GUIData::advanced, |cx, advanced| {
+        if !advanced.get(cx) {
+            simple_knobs(cx);
+        }
+    });
+    macro_knobs(cx);
+    TransferCurve::new(cx);
+    ParamButton::new(cx, GUIData::params, |params| &params.simple.advanced);
+    ParamButton::new(cx, GUIData::params, |params| &params.output.true_peak);
+    ParamButton::new(cx, GUIData::params, |params| {
+        &params.output.true_peak_catcher
+    });
+    ParamButton::new(cx, GUIData::params, |params| &params.input.live);
+    ParamButton::new(cx, GUIData::params, |params| &params.output.inverse);
+    ParamButton::new(cx, GUIData::params, |params| &params.output.compensate);
+    ParamButton::new(cx, GUIData::params, |params| {
+        &params.output.compensate_loudness
+    });
+    ParamButton::new(cx, GUIData::params, |params| &params.output.gain_match);
+    ParamButton::new(cx, GUIData::params, |params| {
+        &params.hold_release.auto_release
+    });
+    ParamButton::new(cx, GUIData::params, |params| &params.hold_release.freeze);
+    ParamButton::new(cx, GUIData::params, |params| &params.bypass);
+    randomize_controls(cx);
+}
+
+fn simple_knobs(cx: &mut Context) {
+    HStack::new(cx, |cx| {
+        param_knob(cx, "param.attack_shape", DEFAULT_SCROLL_STEP, |params| {
+            &params.simple.attack_shape
+        });
+        param_knob(cx, "param.release_shape", DEFAULT_SCROLL_STEP, |params| {
+            &params.simple.release_shape
+        });
+        param_knob(cx, "param.speed", DEFAULT_SCROLL_STEP, |params| {
+            &params.simple.speed
+        });
+        param_knob(cx, "param.amount", DEFAULT_SCROLL_STEP, |params| {
+            &params.simple.amount
+        });
+    })
+    .class("knob-row");
+}
+
+// assigned from any param's right click menu
+fn macro_knobs(cx: &mut Context) {
+    HStack::new(cx, |cx| {
+        param_knob(cx, "param.macro_1", DEFAULT_SCROLL_STEP, |params| {
+            &params.macros.macro_1
+        });
+        param_knob(cx, "param.macro_2", DEFAULT_SCROLL_STEP, |params| {
+            &params.macros.macro_2
+        });
+        param_knob(cx, "param.macro_3", DEFAULT_SCROLL_STEP, |params| {
+            &params.macros.macro_3
+        });
+        param_knob(cx, "param.macro_4", DEFAULT_SCROLL_STEP, |params| {
+            &params.macros.macro_4
+        });
+    })
+    .class("knob-row");
+}
+
+fn attack_page(cx: &mut Context) {
+    param_slider(cx, "param.attack_amt", |params| &params.attack.attack_amt);
+    param_slider(cx, "param.attack_bite", |params| &params.attack.attack_bite);
+    HStack::new(cx, |cx| {
+        param_knob(cx, "param.atk_morph", DEFAULT_SCROLL_STEP, |params| {
+            &params.attack.atk_morph
+        });
+        VStack::new(cx, |cx| {
+            ParamButton::new(cx, GUIData::params, |params| &params.attack.atk_morph_on);
+            detail_button(cx, Morph::Attack);
+        })
+        .size(Auto);
+    })
+    .class("knob-row");
+    param_slider(cx, "param.atk_env_linearity", |params| {
+        &params.attack.atk_env_linearity
+    });
+    param_slider(cx, "param.atk_env_center", |params| {
+        &params.attack.atk_env_center
+    });
+    HStack::new(cx, |cx| {
+        param_knob(cx, "param.atk_env_power_in", POWER_STEP, |params| {
+            &params.attack.atk_env_power_in
+        });
+        param_knob(cx, "param.atk_env_power_out", POWER_STEP, |params| {
+            &params.attack.atk_env_power_out
+        });
+    })
+    .class("knob-row");
+    param_slider(cx, "param.atk_env_polarity_in", |params| {
+        &params.attack.atk_env_polarity_in
+    });
+    param_slider(cx, "param.atk_env_polarity_out", |params| {
+        &params.attack.atk_env_polarity_out
+    });
+    param_slider(cx, "param.atk_smooth_amt", |params| {
+        &params.attack_smoothing.atk_smooth_amt
+    });
+}
+
+fn release_page(cx: &mut Context) {
+    HStack::new(cx, |cx| {
+        param_knob(cx, "param.hold", DEFAULT_SCROLL_STEP, |params| {
+            &params.hold_release.hold
+        });
+        param_knob(cx, "param.release", DEFAULT_SCROLL_STEP, |params| {
+            &params.hold_release.release
+        });
+    })
+    .class("knob-row");
+    param_slider(cx, "param.hold_level", |params| {
+        &params.hold_release.hold_level
+    });
+    ParamButton::new(cx, GUIData::params, |params| &params.hold_release.true_hold);
+    param_slider(cx, "param.release_start", |params| {
+        &params.hold_release.release_start
+    });
+    ParamButton::new(cx, GUIData::params, |params| {
+        &params.hold_release.release_scaling
+    });
+    HStack::new(cx, |cx| {
+        param_knob(cx, "param.rel_morph", DEFAULT_SCROLL_STEP, |params| {
+            &params.release.rel_morph
+        });
+        VStack::new(cx, |cx| {
+            ParamButton::new(cx, GUIData::params, |params| &params.release.rel_morph_on);
+            detail_button(cx, Morph::Release);
+        })
+        .size(Auto);
+    })
+    .class("knob-row");
+    param_slider(cx, "param.rel_env_linearity", |params| {
+        &params.release.rel_env_linearity
+    });
+    param_slider(cx, "param.rel_env_center", |params| {
+        &params.release.rel_env_center
+    });
+    HStack::new(cx, |cx| {
+        param_knob(cx, "param.rel_env_power_in", POWER_STEP, |params| {
+            &params.release.rel_env_power_in
+        });
+        param_knob(cx, "param.rel_env_power_out", POWER_STEP, |params| {
+            &params.release.rel_env_power_out
+        });
+    })
+    .class("knob-row");
+    param_slider(cx, "param.rel_env_polarity_in", |params| {
+        &params.release.rel_env_polarity_in
+    });
+    param_slider(cx, "param.rel_env_polarity_out", |params| {
+        &params.release.rel_env_polarity_out
+    });
+    param_slider(cx, "param.rel_smooth_amt", |params| {
+        &params.release.rel_smooth_amt
+    });
+}
+
+fn detector_page(cx: &mut Context) {
+    LookaheadView::new(cx);
+
+    HStack::new(cx, |cx| {
+        param_knob(cx, "param.lookahead", DEFAULT_SCROLL_STEP, |params| {
+            &params.input.lookahead
+        });
+        param_knob(
+            cx,
+            "param.lookahead_offset",
+            DEFAULT_SCROLL_STEP,
+            |params| &params.input.lookahead_offset,
+        );
+        param_knob(cx, "param.detector_tilt", DEFAULT_SCROLL_STEP, |params| {
+            &params.input.detector_tilt
+        });
+    })
+    .class("knob-row");
+    HStack::new(cx, |cx| {
+        param_knob(cx, "param.detector_low", DEFAULT_SCROLL_STEP, |params| {
+            &params.input.detector_low
+        });
+        param_knob(cx, "param.detector_mid", DEFAULT_SCROLL_STEP, |params| {
+            &params.input.detector_mid
+        });
+        param_knob(
+            cx,
+            "param.detector_mid_freq",
+            DEFAULT_SCROLL_STEP,
+            |params| &params.input.detector_mid_freq,
+        );
+        param_knob(cx, "param.detector_high", DEFAULT_SCROLL_STEP, |params| {
+            &params.input.detector_high
+        });
+    })
+    .class("knob-row");
+    param_slider(cx, "param.detector_weighting", |params| {
+        &params.input.detector_weighting
+    });
+    ParamButton::new(cx, GUIData::params, |params| &params.input.detector_listen);
+    param_slider(cx, "param.algorithm", |params| &params.input.algorithm);
+    param_slider(cx, "param.lookahead_accuracy", |params| {
+        &params.input.lookahead_accuracy
+    });
+    param_slider(cx, "param.gr_slew", |params| &params.hold_release.gr_slew);
+    ParamButton::new(cx, GUIData::params, |params| &params.input.lookahead_glide);
+    ParamButton::new(cx, GUIData::params, |params| &params.input.reset_on_play);
+    ParamButton::new(cx, GUIData::params, |params| &params.input.dc_block);
+
+    ParamButton::new(cx, GUIData::params, |params| &params.input.leveling);
+    HStack::new(cx, |cx| {
+        param_knob(cx, "param.leveling_target", DEFAULT_SCROLL_STEP, |params| {
+            &params.input.leveling_target
+        });
+        param_knob(cx, "param.leveling_range", DEFAULT_SCROLL_STEP, |params| {
+            &params.input.leveling_range
+        });
+    })
+    .class("knob-row");
+}
+
+fn advanced_page(cx: &mut Context) {
+    ParamButton::new(cx, GUIData::params, |params| &params.output.gr_midi_out);
+    param_slider(cx, "param.gr_midi_cc", |params| &params.output.gr_midi_cc);
+    param_slider(cx, "param.automation_smoothing", |params| {
+        &params.input.automation_smoothing
+    });
+    ParamButton::new(cx, GUIData::params, |params| &params.test_tone.enabled);
+    param_slider(cx, "param.test_tone_signal", |params| {
+        &params.test_tone.signal
+    });
+    param_slider(cx, "param.test_tone_freq", |params| {
+        &params.test_tone.frequency
+    });
+    param_slider(cx, "param.test_tone_level", |params| {
+        &params.test_tone.level
+    });
+    param_slider(cx, "param.atk_env_sm_power_in", |params| {
+        &params.attack_smoothing.atk_env_sm_power_in
+    });
+    param_slider(cx, "param.atk_env_sm_power_out", |params| {
+        &params.attack_smoothing.atk_env_sm_power_out
+    });
+    param_slider(cx, "param.atk_env_sm_polarity_in", |params| {
+        &params.attack_smoothing.atk_env_sm_polarity_in
+    });
+    param_slider(cx, "param.atk_env_sm_polarity_out", |params| {
+        &params.attack_smoothing.atk_env_sm_polarity_out
+    });
+    param_slider(cx, "param.rel_env_sm_power_in", |params| {
+        &params.release.rel_env_sm_power_in
+    });
+    param_slider(cx, "param.rel_env_sm_power_out", |params| {
+        &params.release.rel_env_sm_power_out
+    });
+    param_slider(cx, "param.rel_env_sm_polarity_in", |params| {
+        &params.release.rel_env_sm_polarity_in
+    });
+    param_slider(cx, "param.rel_env_sm_polarity_out", |params| {
+        &params.release.rel_env_sm_polarity_out
+    });
+}
@@ -0,0 +1,134 @@
+use nih_plug::nih_error;
+use nih_plug::prelude::{ParamPtr, Params};
+use nih_plug_vizia::vizia::prelude::*;
+use nih_plug_vizia::widgets::RawParamEvent;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter};
+use std::path::Path;
+
+use super::i18n::tr;
+use super::undo::UndoEvent;
+use super::GUIEvent;
+use crate::Limit2zeroParams;
+
+/// Bumped whenever a param gets renamed or changes its range, so older files
+/// can still be read in correctly.
+const PRESET_VERSION: u32 = 2;
+
+// bypass, gain match and the test tone are about the session, not the
+// sound, so they don't go into presets
+const EXCLUDED_PARAMS: &[&str] = &["bypass", "gain_match", "test_tone"];
+
+/// Plain values by param ID, readable enough to be edited by hand.
+#[derive(Debug, Serialize, Deserialize)]
+struct PresetFile {
+    version: u32,
+    params: BTreeMap<String, f32>,
+}
+
+impl PresetFile {
+    fn take(params: &Limit2zeroParams) -> Self {
+        Self {
+            version: PRESET_VERSION,
+            params: params
+                .param_map()
+                .into_iter()
+                .filter(|(id, _, _)| !EXCLUDED_PARAMS.contains(&id.as_str()))
+                // the params outlive the editor, so the pointers are still valid
+                .map(|(id, ptr, _)| (id, unsafe { ptr.unmodulated_plain_value() }))
+                .collect(),
+        }
+    }
+
+    fn read(path: &Path) -> io::Result<Self> {
+        let mut preset: PresetFile = serde_json::from_reader(BufReader::new(File::open(path)?))?;
+        if preset.version > PRESET_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("made by a newer version (format {})", preset.version),
+            ));
+        }
+        // presets from before simple mode were made with the detailed params
+        if preset.version < 2 {
+            preset.params.entry(String::from("advanced")).or_insert(1.0);
+        }
+        Ok(preset)
+    }
+
+    fn write(&self, path: &Path) -> io::Result<()> {
+        serde_json::to_writer_pretty(BufWriter::new(File::create(path)?), self)?;
+        Ok(())
+    }
+
+    // params missing from the file keep their current value, IDs that don't
+    // exist (anymore) are skipped
+    fn values(&self, params: &Limit2zeroParams) -> Vec<(ParamPtr, f32)> {
+        params
+            .param_map()
+            .into_iter()
+            .filter_map(|(id, ptr, _)| {
+                let plain = self.params.get(&id)?;
+                Some((ptr, unsafe { ptr.preview_normalized(*plain) }))
+            })
+            .collect()
+    }
+}
+
+/// Asks where to save the current settings and writes them out as JSON.
+pub(super) fn export_preset(params: &Limit2zeroParams) {
+    let Some(path) = dialog().set_title("Save preset").save_file() else {
+        return;
+    };
+
+    if let Err(err) = PresetFile::take(params).write(&path) {
+        nih_error!("Could not save preset to {}: {}", path.display(), err);
+    }
+}
+
+/// Asks for a preset file and loads it through the same events as the
+/// widgets, so the host sees regular automation and it can be undone in one step.
+pub(super) fn import_preset(cx: &mut EventContext, params: &Limit2zeroParams) {
+    let Some(path) = dialog().set_title("Load preset").pick_file() else {
+        return;
+    };
+
+    let preset = match PresetFile::read(&path) {
+        Ok(preset) => preset,
+        Err(err) => {
+            nih_error!("Could not load preset from {}: {}", path.display(), err);
+            return;
+        }
+    };
+
+    cx.emit(UndoEvent::BeginGroup { record: true });
+    for (ptr, value) in preset.values(params) {
+        cx.emit(RawParamEvent::BeginSetParameter(ptr));
+        cx.emit(RawParamEvent::SetParameterNormalized(ptr, value));
+        cx.emit(RawParamEvent::EndSetParameter(ptr));
+    }
+    cx.emit(UndoEvent::EndGroup);
+}
+
+fn dialog() -> rfd::FileDialog {
+    rfd::FileDialog::new()
+        .set_file_name("limit2zero-preset.json")
+        .add_filter("limit2zero preset", &["json"])
+}
+
+pub(super) fn preset_controls(cx: &mut Context) {
+    HStack::new(cx, |cx| {
+        Button::new(
+            cx,
+            |cx| cx.emit(GUIEvent::ImportPreset),
+            |cx| Label::new(cx, tr(cx, "preset.load")),
+        );
+        Button::new(
+            cx,
+            |cx| cx.emit(GUIEvent::ExportPreset),
+            |cx| Label::new(cx, tr(cx, "preset.save")),
+        );
+    })
+    .class("preset-controls");
+}
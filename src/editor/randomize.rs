@@ -0,0 +1,236 @@
+use nih_plug::prelude::{Param, ParamPtr};
+use nih_plug_vizia::vizia::prelude::*;
+use nih_plug_vizia::widgets::RawParamEvent;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::i18n::tr;
+use super::lock::ParamLocks;
+use super::undo::UndoEvent;
+use super::GUIData;
+use crate::Limit2zeroParams;
+
+const MUTATE_AMOUNTS: [f32; 3] = [0.05, 0.1, 0.25];
+
+pub(super) enum RandomizeEvent {
+    Randomize,
+    Mutate,
+    ToggleLock(ShapeGroup),
+    CycleMutateAmount,
+}
+
+/// The envelope shape params, grouped the way they're usually explored.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum ShapeGroup {
+    Attack,
+    Release,
+    Smoothing,
+}
+
+impl ShapeGroup {
+    pub const ALL: [ShapeGroup; 3] = [
+        ShapeGroup::Attack,
+        ShapeGroup::Release,
+        ShapeGroup::Smoothing,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ShapeGroup::Attack => "random.attack",
+            ShapeGroup::Release => "random.release",
+            ShapeGroup::Smoothing => "random.smoothing",
+        }
+    }
+
+    fn params(&self, params: &Limit2zeroParams) -> Vec<ParamPtr> {
+        match self {
+            ShapeGroup::Attack => vec![
+                params.attack.atk_env_linearity.as_ptr(),
+                params.attack.atk_env_center.as_ptr(),
+                params.attack.atk_env_polarity_in.as_ptr(),
+                params.attack.atk_env_polarity_out.as_ptr(),
+                params.attack.atk_env_power_in.as_ptr(),
+                params.attack.atk_env_power_out.as_ptr(),
+            ],
+            ShapeGroup::Release => vec![
+                params.release.rel_env_linearity.as_ptr(),
+                params.release.rel_env_center.as_ptr(),
+                params.release.rel_env_polarity_in.as_ptr(),
+                params.release.rel_env_polarity_out.as_ptr(),
+                params.release.rel_env_power_in.as_ptr(),
+                params.release.rel_env_power_out.as_ptr(),
+            ],
+            ShapeGroup::Smoothing => vec![
+                params.attack_smoothing.atk_smooth_amt.as_ptr(),
+                params.attack_smoothing.atk_env_sm_polarity_in.as_ptr(),
+                params.attack_smoothing.atk_env_sm_polarity_out.as_ptr(),
+                params.attack_smoothing.atk_env_sm_power_in.as_ptr(),
+                params.attack_smoothing.atk_env_sm_power_out.as_ptr(),
+                params.release.rel_smooth_amt.as_ptr(),
+                params.release.rel_env_sm_polarity_in.as_ptr(),
+                params.release.rel_env_sm_polarity_out.as_ptr(),
+                params.release.rel_env_sm_power_in.as_ptr(),
+                params.release.rel_env_sm_power_out.as_ptr(),
+            ],
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            ShapeGroup::Attack => 0,
+            ShapeGroup::Release => 1,
+            ShapeGroup::Smoothing => 2,
+        }
+    }
+}
+
+/// Rolls new envelope shapes, either from scratch or as a small variation on
+/// the current ones. Locked groups and locked params are left alone.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct Randomizer {
+    pub locked: [bool; 3],
+    pub mutate_amount: f32,
+    rng: XorShift,
+}
+
+impl Default for Randomizer {
+    fn default() -> Self {
+        Self {
+            locked: [false; 3],
+            mutate_amount: MUTATE_AMOUNTS[1],
+            rng: XorShift::from_time(),
+        }
+    }
+}
+
+impl Randomizer {
+    pub fn is_locked(&self, group: ShapeGroup) -> bool {
+        self.locked[group.index()]
+    }
+
+    pub fn toggle_lock(&mut self, group: ShapeGroup) {
+        self.locked[group.index()] = !self.locked[group.index()];
+    }
+
+    pub fn cycle_mutate_amount(&mut self) {
+        let i = MUTATE_AMOUNTS
+            .iter()
+            .position(|amount| *amount == self.mutate_amount)
+            .unwrap_or(0);
+        self.mutate_amount = MUTATE_AMOUNTS[(i + 1) % MUTATE_AMOUNTS.len()];
+    }
+
+    pub fn randomize(
+        &mut self,
+        cx: &mut EventContext,
+        params: &Limit2zeroParams,
+        locks: &ParamLocks,
+    ) {
+        self.apply(cx, params, locks, |rng, _| rng.next_f32());
+    }
+
+    /// Nudges every unlocked param by up to `mutate_amount` of its range.
+    pub fn mutate(&mut self, cx: &mut EventContext, params: &Limit2zeroParams, locks: &ParamLocks) {
+        let amount = self.mutate_amount;
+        self.apply(cx, params, locks, |rng, value| {
+            value + (rng.next_f32() * 2.0 - 1.0) * amount
+        });
+    }
+
+    // sent as one group so the whole roll is a single undo step
+    fn apply(
+        &mut self,
+        cx: &mut EventContext,
+        params: &Limit2zeroParams,
+        locks: &ParamLocks,
+        mut roll: impl FnMut(&mut XorShift, f32) -> f32,
+    ) {
+        cx.emit(UndoEvent::BeginGroup { record: true });
+        for group in ShapeGroup::ALL {
+            if self.is_locked(group) {
+                continue;
+            }
+
+            for param in group.params(params) {
+                if locks.contains_ptr(params, param) {
+                    continue;
+                }
+
+                // the params outlive the editor, so the pointer is still valid
+                let value = unsafe { param.unmodulated_normalized_value() };
+                let value = roll(&mut self.rng, value).clamp(0.0, 1.0);
+
+                cx.emit(RawParamEvent::BeginSetParameter(param));
+                cx.emit(RawParamEvent::SetParameterNormalized(param, value));
+                cx.emit(RawParamEvent::EndSetParameter(param));
+            }
+        }
+        cx.emit(UndoEvent::EndGroup);
+    }
+}
+
+impl Data for Randomizer {
+    fn same(&self, other: &Self) -> bool {
+        self.locked == other.locked && self.mutate_amount == other.mutate_amount
+    }
+}
+
+// nothing here needs good randomness, just different curves every click
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct XorShift(u64);
+
+impl XorShift {
+    fn from_time() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_nanos() as u64)
+            .unwrap_or_default();
+        // the state must never be zero
+        Self(nanos | 1)
+    }
+
+    fn next_f32(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+pub(super) fn randomize_controls(cx: &mut Context) {
+    HStack::new(cx, |cx| {
+        Button::new(
+            cx,
+            |cx| cx.emit(RandomizeEvent::Randomize),
+            |cx| Label::new(cx, tr(cx, "random.randomize")),
+        );
+        Button::new(
+            cx,
+            |cx| cx.emit(RandomizeEvent::Mutate),
+            |cx| Label::new(cx, tr(cx, "random.mutate")),
+        );
+        Button::new(
+            cx,
+            |cx| cx.emit(RandomizeEvent::CycleMutateAmount),
+            |cx| {
+                Label::new(
+                    cx,
+                    GUIData::randomizer
+                        .map(|randomizer| format!("{:.0}%", randomizer.mutate_amount * 100.0)),
+                )
+            },
+        );
+        Label::new(cx, tr(cx, "random.lock"));
+        for group in ShapeGroup::ALL {
+            Button::new(
+                cx,
+                move |cx| cx.emit(RandomizeEvent::ToggleLock(group)),
+                move |cx| Label::new(cx, tr(cx, group.name())),
+            )
+            .toggle_class(
+                "locked",
+                GUIData::randomizer.map(move |randomizer| randomizer.is_locked(group)),
+            );
+        }
+    })
+    .class("randomize-controls");
+}
@@ -0,0 +1,151 @@
+use atomic_float::AtomicF32;
+use nih_plug::util;
+use nih_plug_vizia::vizia::prelude::*;
+use nih_plug_vizia::vizia::vg::{LineCap, LineJoin, Paint, Path};
+use std::array;
+use std::collections::VecDeque;
+use std::sync::{atomic::Ordering, Arc};
+
+use super::GUIData;
+
+pub(super) const SCOPE_LEN: usize = 300;
+
+#[derive(Debug, Clone)]
+pub(super) struct ScopeAtomics {
+    pub peak: [Arc<AtomicF32>; 2],
+    pub gr: [Arc<AtomicF32>; 2],
+}
+
+impl Data for ScopeAtomics {
+    fn same(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+// peak is the highest absolute output sample since the last frame,
+// gr is the deepest reduction (in dB) applied during that frame
+#[derive(Debug)]
+pub(super) struct ScopeBuffer {
+    peak: VecDeque<[f32; 2]>,
+    gr: VecDeque<[f32; 2]>,
+}
+
+impl Default for ScopeBuffer {
+    fn default() -> Self {
+        Self {
+            peak: VecDeque::from_iter((0..SCOPE_LEN).map(|_| [0.0; 2])),
+            gr: VecDeque::from_iter((0..SCOPE_LEN).map(|_| [0.0; 2])),
+        }
+    }
+}
+
+impl ScopeBuffer {
+    /// Pushes what happened since the last tick, repeated once per frame the
+    /// tick covers. Returns whether it differs from the previous frame.
+    pub fn update(&mut self, atomics: &ScopeAtomics, frames: usize) -> bool {
+        let peak = array::from_fn(|i| atomics.peak[i].swap(0.0, Ordering::Relaxed));
+        let gr = array::from_fn(|i| atomics.gr[i].swap(0.0, Ordering::Relaxed));
+
+        let changed = self.peak.back() != Some(&peak) || self.gr.back() != Some(&gr);
+
+        for _ in 0..frames {
+            self.peak.pop_front();
+            self.gr.pop_front();
+
+            self.peak.push_back(peak);
+            self.gr.push_back(gr);
+        }
+
+        changed
+    }
+}
+
+/// Scrolling waveform of the delayed output, one lane per channel. The gain
+/// reduction envelope hangs down from the top of each lane (and up from the
+/// bottom) so the transients that triggered it line up underneath.
+pub(super) struct WaveformScope;
+
+impl WaveformScope {
+    pub fn new(cx: &'_ mut Context) -> Handle<'_, Self> {
+        WaveformScope.build(cx, |_| {})
+    }
+}
+
+impl View for WaveformScope {
+    fn element(&self) -> Option<&'static str> {
+        Some("waveform-scope")
+    }
+
+    fn draw(&self, cx: &mut DrawContext, canvas: &mut Canvas) {
+        let shared = GUIData::scope_buffer.0.get(cx);
+        let buffer = shared.read();
+        let len = buffer.peak.len();
+
+        if len < 2 {
+            return;
+        }
+
+        let bounds = cx.bounds();
+        let lane_h = bounds.h / 2.0;
+        let half = lane_h / 2.0;
+
+        let mut wave = [Path::new(), Path::new()];
+        let mut env = [Path::new(), Path::new()];
+        let mut center_lines = Path::new();
+
+        for channel in 0..2 {
+            let center = bounds.y + lane_h * channel as f32 + half;
+            let x_at = |i: usize| bounds.x + (i as f32 / (len - 1) as f32) * bounds.w;
+
+            center_lines.move_to(bounds.x, center);
+            center_lines.line_to(bounds.x + bounds.w, center);
+
+            // upper edge left to right, then the lower edge back again
+            for i in 0..len {
+                let y = center - buffer.peak[i][channel].min(1.0) * half;
+                if i == 0 {
+                    wave[channel].move_to(x_at(i), y);
+                } else {
+                    wave[channel].line_to(x_at(i), y);
+                }
+            }
+            for i in (0..len).rev() {
+                let y = center + buffer.peak[i][channel].min(1.0) * half;
+                wave[channel].line_to(x_at(i), y);
+            }
+            wave[channel].close();
+
+            for (edge, direction) in [(center - half, 1.0), (center + half, -1.0)] {
+                for i in 0..len {
+                    let reduction = half * (1.0 - util::db_to_gain(buffer.gr[i][channel]));
+                    let y = edge + reduction * direction;
+                    if i == 0 {
+                        env[channel].move_to(x_at(i), y);
+                    } else {
+                        env[channel].line_to(x_at(i), y);
+                    }
+                }
+            }
+        }
+
+        let palette = GUIData::theme.0.get(cx).palette();
+
+        let mut paint_center = Paint::color(palette.track);
+        paint_center.set_line_width(1.0);
+
+        let mut wave_color = palette.accent;
+        wave_color.set_alpha(160);
+        let paint_wave = Paint::color(wave_color);
+
+        let mut paint_env = Paint::color(palette.env);
+        paint_env.set_line_width(palette.stroke_width * 0.75);
+        paint_env.set_line_cap(LineCap::Round);
+        paint_env.set_line_join(LineJoin::Round);
+
+        canvas.stroke_path(&center_lines, &paint_center);
+        for i in 0..wave.len() {
+            canvas.fill_path(&wave[i], &paint_wave);
+            canvas.stroke_path(&env[i], &paint_env);
+        }
+    }
+}
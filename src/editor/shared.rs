@@ -0,0 +1,46 @@
+use nih_plug_vizia::vizia::prelude::*;
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// A buffer the model updates in place and the views read through a shared
+/// handle. Cloning one only bumps a refcount, and vizia compares the version
+/// counter instead of walking the contents.
+#[derive(Debug, Default)]
+pub(super) struct Shared<T> {
+    inner: Arc<RwLock<T>>,
+    version: u64,
+}
+
+impl<T> Shared<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            inner: Arc::new(RwLock::new(value)),
+            version: 0,
+        }
+    }
+
+    pub fn read(&self) -> RwLockReadGuard<'_, T> {
+        self.inner.read().unwrap()
+    }
+
+    /// Every write counts as a change, whether or not anything was modified.
+    pub fn write(&mut self) -> RwLockWriteGuard<'_, T> {
+        self.version = self.version.wrapping_add(1);
+        self.inner.write().unwrap()
+    }
+}
+
+// derived Clone would require `T: Clone`
+impl<T> Clone for Shared<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            version: self.version,
+        }
+    }
+}
+
+impl<T: 'static> Data for Shared<T> {
+    fn same(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner) && self.version == other.version
+    }
+}
@@ -0,0 +1,197 @@
+use nih_plug_vizia::vizia::prelude::*;
+use nih_plug_vizia::vizia::vg::{LineJoin, Paint, Path};
+use std::f32::consts::TAU;
+use std::sync::{atomic::Ordering, Arc};
+
+use super::GUIData;
+use crate::{DeltaSnapshot, DELTA_LEN};
+
+const BANDS: usize = 48;
+const LOWEST_HZ: f32 = 20.0;
+const HIGHEST_HZ: f32 = 20000.0;
+const FLOOR_DB: f32 = -90.0;
+// how fast a band falls back once the limiter stops taking anything out there
+const FALL_DB_PER_FRAME: f32 = 1.5;
+
+#[derive(Clone)]
+pub(super) struct DeltaAtomics(pub Arc<DeltaSnapshot>);
+
+impl Data for DeltaAtomics {
+    fn same(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+/// The spectrum of what the limiter took out, in log spaced bands. Rises
+/// straight away and falls back slowly, like a peak meter.
+#[derive(Debug)]
+pub(super) struct SpectrumBuffer {
+    bands: [f32; BANDS],
+    written: u32,
+    window: Vec<f32>,
+    // the FFT's working space, real and imaginary
+    re: Vec<f32>,
+    im: Vec<f32>,
+}
+
+impl Default for SpectrumBuffer {
+    fn default() -> Self {
+        Self {
+            bands: [FLOOR_DB; BANDS],
+            written: 0,
+            window: (0..DELTA_LEN)
+                .map(|i| 0.5 - 0.5 * (TAU * i as f32 / DELTA_LEN as f32).cos())
+                .collect(),
+            re: vec![0.0; DELTA_LEN],
+            im: vec![0.0; DELTA_LEN],
+        }
+    }
+}
+
+impl SpectrumBuffer {
+    /// Analyzes the newest `DELTA_LEN` samples if any came in since the last
+    /// call. Returns whether the bands moved.
+    pub fn update(&mut self, atomics: &DeltaAtomics, sample_rate: f32) -> bool {
+        let snapshot = &atomics.0;
+        let written = snapshot.written.load(Ordering::Acquire);
+        if written == self.written {
+            return false;
+        }
+        self.written = written;
+
+        // oldest first, the ring's newest sample sits just before `written`
+        for i in 0..DELTA_LEN {
+            let index = (written as usize).wrapping_add(i) % DELTA_LEN;
+            self.re[i] = snapshot.samples[index].load(Ordering::Relaxed) * self.window[i];
+            self.im[i] = 0.0;
+        }
+        fft(&mut self.re, &mut self.im);
+
+        // a full scale sine reads 0 dB through the window
+        let norm = 2.0 / self.window.iter().sum::<f32>();
+        let bin_hz = sample_rate / DELTA_LEN as f32;
+        let highest = HIGHEST_HZ.min(sample_rate * 0.5);
+        let before = self.bands;
+        for (band, level) in self.bands.iter_mut().enumerate() {
+            let hz_at = |edge: f32| LOWEST_HZ * (highest / LOWEST_HZ).powf(edge / BANDS as f32);
+            let (low, high) = (hz_at(band as f32), hz_at(band as f32 + 1.0));
+            // a band narrower than a bin takes the bin it's in
+            let first = ((low / bin_hz).ceil() as usize).max(1);
+            let last = ((high / bin_hz).floor() as usize).max(first);
+            let last = last.min(DELTA_LEN / 2);
+            let first = first.min(last);
+            let power = (first..=last)
+                .map(|bin| self.re[bin] * self.re[bin] + self.im[bin] * self.im[bin])
+                .sum::<f32>()
+                / (last - first + 1) as f32;
+
+            let db = (10.0 * (power * norm * norm).max(1e-12).log10()).max(FLOOR_DB);
+            *level = db.max(*level - FALL_DB_PER_FRAME);
+        }
+        self.bands != before
+    }
+}
+
+// in place, radix 2, `re` and `im` a power of two long
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let len = re.len();
+    let mut j = 0;
+    for i in 1..len {
+        let mut bit = len >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut size = 2;
+    while size <= len {
+        let (sin, cos) = (-TAU / size as f32).sin_cos();
+        for start in (0..len).step_by(size) {
+            let (mut w_re, mut w_im) = (1.0f32, 0.0f32);
+            for k in 0..size / 2 {
+                let (a, b) = (start + k, start + k + size / 2);
+                let t_re = re[b] * w_re - im[b] * w_im;
+                let t_im = re[b] * w_im + im[b] * w_re;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+                (w_re, w_im) = (w_re * cos - w_im * sin, w_re * sin + w_im * cos);
+            }
+        }
+        size *= 2;
+    }
+}
+
+/// Where the limiter's work goes across the spectrum: the difference between
+/// what went in and what came out, from 20 Hz to 20 kHz on a log scale.
+/// Mostly lows means it's riding the kick and bass, mostly highs means it's
+/// shaving off detail.
+pub(super) struct DeltaSpectrum;
+
+impl DeltaSpectrum {
+    pub fn new(cx: &'_ mut Context) -> Handle<'_, Self> {
+        DeltaSpectrum.build(cx, |_| {})
+    }
+}
+
+impl View for DeltaSpectrum {
+    fn element(&self) -> Option<&'static str> {
+        Some("waveform-scope")
+    }
+
+    fn draw(&self, cx: &mut DrawContext, canvas: &mut Canvas) {
+        let shared = GUIData::spectrum_buffer.0.get(cx);
+        let buffer = shared.read();
+
+        let bounds = cx.bounds();
+        let x_at = |band: f32| bounds.x + band / (BANDS - 1) as f32 * bounds.w;
+        let y_at = |db: f32| bounds.y + db / FLOOR_DB * bounds.h;
+
+        let mut guides = Path::new();
+        // every 10 dB down to the floor
+        let mut db = -10.0;
+        while db > FLOOR_DB {
+            guides.move_to(bounds.x, y_at(db));
+            guides.line_to(bounds.x + bounds.w, y_at(db));
+            db -= 10.0;
+        }
+
+        let mut fill = Path::new();
+        let mut line = Path::new();
+        fill.move_to(bounds.x, bounds.y + bounds.h);
+        for (band, level) in buffer.bands.iter().enumerate() {
+            let (x, y) = (x_at(band as f32), y_at(*level));
+            fill.line_to(x, y);
+            if band == 0 {
+                line.move_to(x, y);
+            } else {
+                line.line_to(x, y);
+            }
+        }
+        fill.line_to(bounds.x + bounds.w, bounds.y + bounds.h);
+        fill.close();
+
+        let palette = GUIData::theme.0.get(cx).palette();
+
+        let mut paint_guides = Paint::color(palette.track);
+        paint_guides.set_line_width(1.0);
+
+        let mut fill_color = palette.env;
+        fill_color.set_alphaf(0.3);
+
+        let mut paint_line = Paint::color(palette.env);
+        paint_line.set_line_width(palette.stroke_width);
+        paint_line.set_line_join(LineJoin::Round);
+
+        canvas.stroke_path(&guides, &paint_guides);
+        canvas.fill_path(&fill, &Paint::color(fill_color));
+        canvas.stroke_path(&line, &paint_line);
+    }
+}
@@ -0,0 +1,214 @@
+use nih_plug_vizia::vizia::prelude::*;
+use nih_plug_vizia::vizia::vg::Color;
+use serde::{Deserialize, Serialize};
+
+/// The editor's color scheme. Stored in the plugin state so every instance
+/// reopens with whatever it was last set to.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) enum ThemeKind {
+    #[default]
+    Dark,
+    Light,
+    HighContrast,
+}
+
+impl ThemeKind {
+    pub const ALL: [ThemeKind; 3] = [ThemeKind::Dark, ThemeKind::Light, ThemeKind::HighContrast];
+
+    /// Key of the theme's label in the string table.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ThemeKind::Dark => "theme.dark",
+            ThemeKind::Light => "theme.light",
+            ThemeKind::HighContrast => "theme.contrast",
+        }
+    }
+
+    /// Class toggled on the root view, `THEME_STYLE` keys everything off it.
+    pub fn class(&self) -> &'static str {
+        match self {
+            ThemeKind::Dark => "theme-dark",
+            ThemeKind::Light => "theme-light",
+            ThemeKind::HighContrast => "theme-high-contrast",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL
+            .iter()
+            .position(|theme| *theme == self)
+            .unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    pub fn palette(&self) -> Palette {
+        match self {
+            ThemeKind::Dark => Palette {
+                grid: Color::rgb(62, 62, 62),
+                track: Color::rgb(78, 78, 78),
+                quiet: Color::rgb(90, 90, 90),
+                zero: Color::rgb(120, 120, 120),
+                accent: Color::rgb(77, 205, 102),
+                post: Color::rgb(102, 77, 205),
+                env: Color::rgb(205, 77, 102),
+                highlight: Color::rgb(230, 200, 80),
+                stroke_width: 2.0,
+                patterned: false,
+            },
+            ThemeKind::Light => Palette {
+                grid: Color::rgb(210, 210, 210),
+                track: Color::rgb(185, 185, 185),
+                quiet: Color::rgb(165, 165, 165),
+                zero: Color::rgb(120, 120, 120),
+                accent: Color::rgb(30, 150, 60),
+                post: Color::rgb(80, 50, 180),
+                env: Color::rgb(190, 40, 70),
+                highlight: Color::rgb(200, 140, 0),
+                stroke_width: 2.0,
+                patterned: false,
+            },
+            ThemeKind::HighContrast => Palette {
+                grid: Color::rgb(96, 96, 96),
+                track: Color::rgb(140, 140, 140),
+                quiet: Color::rgb(150, 150, 150),
+                zero: Color::rgb(255, 255, 255),
+                accent: Color::rgb(0, 230, 255),
+                post: Color::rgb(255, 255, 255),
+                env: Color::rgb(255, 70, 70),
+                highlight: Color::rgb(255, 230, 0),
+                stroke_width: 3.5,
+                patterned: true,
+            },
+        }
+    }
+}
+
+impl Data for ThemeKind {
+    fn same(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+/// Colors for the views that draw straight to the canvas. Everything else
+/// gets its colors from `THEME_STYLE`.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Palette {
+    pub grid: Color,
+    pub track: Color,
+    pub quiet: Color,
+    pub zero: Color,
+    /// Input trace, curves and the active part of knobs.
+    pub accent: Color,
+    pub post: Color,
+    pub env: Color,
+    pub highlight: Color,
+    /// Line width of the meter traces and curves.
+    pub stroke_width: f32,
+    /// Tell the traces apart by shape as well as color, the post trace gets
+    /// dashed and the envelope gets markers.
+    pub patterned: bool,
+}
+
+pub(super) const THEME_STYLE: &str = r#"
+    .theme-dark {
+        background-color: #262626;
+        color: #e0e0e0;
+    }
+    .theme-dark function-graph {
+        background-color: #2e2e2e;
+        border-color: #4e4e4e;
+    }
+    .theme-dark lookahead-view, .theme-dark waveform-scope, .theme-dark needle-meter,
+    .theme-dark transfer-curve {
+        background-color: #1e1e1e;
+        border-color: #4e4e4e;
+    }
+    .theme-dark .clip-light {
+        background-color: #3e2222;
+    }
+    .theme-dark .clip-light.latched {
+        background-color: #e0303a;
+    }
+    .theme-dark .tab-bar > .tab {
+        background-color: #2e2e2e;
+    }
+    .theme-dark .tab-bar > .tab.selected, .theme-dark .size-preset.selected {
+        background-color: #4e4e4e;
+    }
+    .theme-dark .param-menu {
+        background-color: #303030;
+        border-color: #5e5e5e;
+    }
+
+    .theme-light {
+        background-color: #ececec;
+        color: #1a1a1a;
+    }
+    .theme-light function-graph {
+        background-color: #fafafa;
+        border-color: #b0b0b0;
+    }
+    .theme-light lookahead-view, .theme-light waveform-scope, .theme-light needle-meter,
+    .theme-light transfer-curve {
+        background-color: #ffffff;
+        border-color: #b0b0b0;
+    }
+    .theme-light .clip-light {
+        background-color: #e6c8c8;
+    }
+    .theme-light .clip-light.latched {
+        background-color: #d0202a;
+    }
+    .theme-light .tab-bar > .tab {
+        background-color: #dadada;
+    }
+    .theme-light .tab-bar > .tab.selected, .theme-light .size-preset.selected {
+        background-color: #bcbcbc;
+    }
+    .theme-light .param-menu {
+        background-color: #f4f4f4;
+        border-color: #9a9a9a;
+    }
+
+    .theme-high-contrast {
+        background-color: #000000;
+        color: #ffffff;
+        font-size: 16;
+    }
+    .theme-high-contrast label {
+        font-size: 16;
+    }
+    .theme-high-contrast param-knob .value {
+        font-size: 15;
+    }
+    .theme-high-contrast function-graph,
+    .theme-high-contrast lookahead-view,
+    .theme-high-contrast waveform-scope,
+    .theme-high-contrast needle-meter,
+    .theme-high-contrast transfer-curve {
+        background-color: #000000;
+        border-color: #ffffff;
+        border-width: 2px;
+    }
+    .theme-high-contrast .clip-light {
+        background-color: #000000;
+        border-color: #ff4646;
+        border-width: 2px;
+    }
+    .theme-high-contrast .clip-light.latched {
+        background-color: #ff4646;
+    }
+    .theme-high-contrast .tab-bar > .tab {
+        background-color: #000000;
+        border-color: #8c8c8c;
+        border-width: 1px;
+    }
+    .theme-high-contrast .tab-bar > .tab.selected, .theme-high-contrast .size-preset.selected {
+        background-color: #ffffff;
+        color: #000000;
+    }
+    .theme-high-contrast .param-menu {
+        background-color: #000000;
+        border-color: #ffffff;
+    }
+"#;
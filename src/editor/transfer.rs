@@ -0,0 +1,94 @@
+use nih_plug_vizia::vizia::prelude::*;
+use nih_plug_vizia::vizia::vg::{LineCap, LineJoin, Paint, Path};
+
+use super::GUIData;
+use crate::Settings;
+
+// same range on both axes, so unity gain is the diagonal
+const FLOOR_DB: f32 = -60.0;
+const CEILING_DB: f32 = 6.0;
+const GRID_STEP_DB: f32 = 12.0;
+const RESOLUTION: usize = 120;
+
+/// Static input to output curve of the current settings, as it'd look with a
+/// steady signal. The thinner line is how far the attack envelope gets on its
+/// own, the gap between it and the output is what the clip stage catches.
+pub(super) struct TransferCurve;
+
+impl TransferCurve {
+    pub fn new(cx: &'_ mut Context) -> Handle<'_, Self> {
+        TransferCurve.build(cx, |_| {})
+    }
+}
+
+impl View for TransferCurve {
+    fn element(&self) -> Option<&'static str> {
+        Some("transfer-curve")
+    }
+
+    fn draw(&self, cx: &mut DrawContext, canvas: &mut Canvas) {
+        let params = GUIData::params.0.get(cx);
+
+        let bounds = cx.bounds();
+        let wh = f32::min(bounds.w, bounds.h);
+        let (left, top) = (
+            bounds.x + (bounds.w - wh) / 2.0,
+            bounds.y + (bounds.h - wh) / 2.0,
+        );
+        let to_unit =
+            |db: f32| (db.clamp(FLOOR_DB, CEILING_DB) - FLOOR_DB) / (CEILING_DB - FLOOR_DB);
+        let x_at = |db: f32| left + to_unit(db) * wh;
+        let y_at = |db: f32| top + (1.0 - to_unit(db)) * wh;
+
+        let mut grid = Path::new();
+        let mut db = 0.0;
+        while db > FLOOR_DB {
+            grid.move_to(x_at(db), top);
+            grid.line_to(x_at(db), top + wh);
+            grid.move_to(left, y_at(db));
+            grid.line_to(left + wh, y_at(db));
+            db -= GRID_STEP_DB;
+        }
+
+        let mut unity = Path::new();
+        unity.move_to(x_at(FLOOR_DB), y_at(FLOOR_DB));
+        unity.line_to(x_at(CEILING_DB), y_at(CEILING_DB));
+
+        let settings = Settings::from_params(&params);
+        let mut attack = Path::new();
+        let mut output = Path::new();
+        for i in 0..=RESOLUTION {
+            let input_db = FLOOR_DB + (CEILING_DB - FLOOR_DB) * i as f32 / RESOLUTION as f32;
+            let (attack_db, output_db) = settings.steady_state_transfer(input_db);
+            let x = x_at(input_db);
+            if i == 0 {
+                attack.move_to(x, y_at(attack_db));
+                output.move_to(x, y_at(output_db));
+            } else {
+                attack.line_to(x, y_at(attack_db));
+                output.line_to(x, y_at(output_db));
+            }
+        }
+
+        let palette = GUIData::theme.0.get(cx).palette();
+
+        let mut paint_grid = Paint::color(palette.grid);
+        paint_grid.set_line_width(1.0);
+        canvas.stroke_path(&grid, &paint_grid);
+
+        let mut paint_unity = Paint::color(palette.track);
+        paint_unity.set_line_width(1.0);
+        canvas.stroke_path(&unity, &paint_unity);
+
+        let mut paint_attack = Paint::color(palette.accent);
+        paint_attack.set_line_width(palette.stroke_width * 0.75);
+        paint_attack.set_line_join(LineJoin::Round);
+        canvas.stroke_path(&attack, &paint_attack);
+
+        let mut paint_output = Paint::color(palette.env);
+        paint_output.set_line_width(palette.stroke_width);
+        paint_output.set_line_cap(LineCap::Round);
+        paint_output.set_line_join(LineJoin::Round);
+        canvas.stroke_path(&output, &paint_output);
+    }
+}
@@ -0,0 +1,179 @@
+use nih_plug_vizia::vizia::prelude::*;
+use nih_plug_vizia::vizia::vg::{LineCap, LineJoin, Paint, Path};
+use std::sync::{atomic::Ordering, Arc};
+
+use super::GUIData;
+use crate::{TriggerSnapshot, TRIGGER_POINTS, TRIGGER_POST_MS, TRIGGER_PRE_MS};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(super) enum ScopeMode {
+    #[default]
+    Scrolling,
+    Triggered,
+    // what the limiter took out, see `spectrum::DeltaSpectrum`
+    Spectrum,
+}
+
+impl ScopeMode {
+    // key of the mode's label in the string table
+    pub fn name(&self) -> &'static str {
+        match self {
+            ScopeMode::Scrolling => "scope.scrolling",
+            ScopeMode::Triggered => "scope.triggered",
+            ScopeMode::Spectrum => "scope.spectrum",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            ScopeMode::Scrolling => ScopeMode::Triggered,
+            ScopeMode::Triggered => ScopeMode::Spectrum,
+            ScopeMode::Spectrum => ScopeMode::Scrolling,
+        }
+    }
+}
+
+impl Data for ScopeMode {
+    fn same(&self, other: &Self) -> bool {
+        self == other
+    }
+}
+
+#[derive(Clone)]
+pub(super) struct TriggerAtomics(pub Arc<TriggerSnapshot>);
+
+impl Data for TriggerAtomics {
+    fn same(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+/// Copy of the last complete capture, only replaced once a new one is in.
+#[derive(Debug)]
+pub(super) struct TriggerBuffer {
+    input: [Vec<f32>; 2],
+    output: [Vec<f32>; 2],
+    captures: u32,
+}
+
+impl Default for TriggerBuffer {
+    fn default() -> Self {
+        Self {
+            input: [vec![0.0; TRIGGER_POINTS], vec![0.0; TRIGGER_POINTS]],
+            output: [vec![0.0; TRIGGER_POINTS], vec![0.0; TRIGGER_POINTS]],
+            captures: 0,
+        }
+    }
+}
+
+impl TriggerBuffer {
+    /// Whether the audio thread has finished a capture since the last call.
+    pub fn is_stale(&self, atomics: &TriggerAtomics) -> bool {
+        atomics.0.captures.load(Ordering::Acquire) != self.captures
+    }
+
+    pub fn load(&mut self, atomics: &TriggerAtomics) {
+        let snapshot = &atomics.0;
+        self.captures = snapshot.captures.load(Ordering::Acquire);
+        for channel in 0..2 {
+            for point in 0..TRIGGER_POINTS {
+                self.input[channel][point] = snapshot.input[channel][point].load(Ordering::Relaxed);
+                self.output[channel][point] =
+                    snapshot.output[channel][point].load(Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+/// A few ms of waveform around the last sample that went over the ceiling,
+/// one lane per channel. The input (after drive) is drawn behind the output so
+/// what the attack did to the hit shows up as the difference between them.
+pub(super) struct TriggeredScope;
+
+impl TriggeredScope {
+    pub fn new(cx: &'_ mut Context) -> Handle<'_, Self> {
+        TriggeredScope.build(cx, |_| {})
+    }
+}
+
+impl View for TriggeredScope {
+    fn element(&self) -> Option<&'static str> {
+        Some("waveform-scope")
+    }
+
+    fn draw(&self, cx: &mut DrawContext, canvas: &mut Canvas) {
+        let shared = GUIData::trigger_buffer.0.get(cx);
+        let buffer = shared.read();
+        if buffer.captures == 0 {
+            return;
+        }
+
+        let bounds = cx.bounds();
+        let lane_h = bounds.h / 2.0;
+        let half = lane_h / 2.0;
+        let x_at = |i: usize| bounds.x + (i as f32 / (TRIGGER_POINTS - 1) as f32) * bounds.w;
+
+        // driven input can go well over full scale, keep all of it in view
+        let scale = buffer
+            .input
+            .iter()
+            .flatten()
+            .fold(1.0_f32, |max, sample| max.max(sample.abs()));
+
+        let mut input = Path::new();
+        let mut output = Path::new();
+        let mut guides = Path::new();
+
+        for channel in 0..2 {
+            let center = bounds.y + lane_h * channel as f32 + half;
+            guides.move_to(bounds.x, center);
+            guides.line_to(bounds.x + bounds.w, center);
+            // where full scale sits, which is what the output gets held to
+            for edge in [center - half / scale, center + half / scale] {
+                guides.move_to(bounds.x, edge);
+                guides.line_to(bounds.x + bounds.w, edge);
+            }
+
+            for (path, samples) in [
+                (&mut input, &buffer.input[channel]),
+                (&mut output, &buffer.output[channel]),
+            ] {
+                for (i, sample) in samples.iter().enumerate() {
+                    let y = center - sample / scale * half;
+                    if i == 0 {
+                        path.move_to(x_at(i), y);
+                    } else {
+                        path.line_to(x_at(i), y);
+                    }
+                }
+            }
+        }
+
+        let trigger_x = bounds.x + TRIGGER_PRE_MS / (TRIGGER_PRE_MS + TRIGGER_POST_MS) * bounds.w;
+        let mut trigger = Path::new();
+        trigger.move_to(trigger_x, bounds.y);
+        trigger.line_to(trigger_x, bounds.y + bounds.h);
+
+        let palette = GUIData::theme.0.get(cx).palette();
+
+        let mut paint_guides = Paint::color(palette.track);
+        paint_guides.set_line_width(1.0);
+
+        let mut paint_trigger = Paint::color(palette.highlight);
+        paint_trigger.set_line_width(1.0);
+
+        let mut paint_input = Paint::color(palette.accent);
+        paint_input.set_line_width(palette.stroke_width * 0.75);
+        paint_input.set_line_join(LineJoin::Round);
+
+        let mut paint_output = Paint::color(palette.env);
+        paint_output.set_line_width(palette.stroke_width);
+        paint_output.set_line_cap(LineCap::Round);
+        paint_output.set_line_join(LineJoin::Round);
+
+        canvas.stroke_path(&guides, &paint_guides);
+        canvas.stroke_path(&trigger, &paint_trigger);
+        canvas.stroke_path(&input, &paint_input);
+        canvas.stroke_path(&output, &paint_output);
+    }
+}
@@ -0,0 +1,157 @@
+use nih_plug::prelude::ParamPtr;
+use nih_plug_vizia::vizia::prelude::*;
+use nih_plug_vizia::widgets::RawParamEvent;
+
+use super::i18n::tr;
+use super::GUIData;
+
+// hosts don't reliably pick up edits made from inside the plugin, so a long
+// session of tweaking curves gets its own history
+const MAX_UNDO: usize = 256;
+
+pub(super) enum UndoEvent {
+    Undo,
+    Redo,
+    /// Everything up to the matching `EndGroup` becomes a single edit, or
+    /// isn't recorded at all when `record` is false.
+    BeginGroup {
+        record: bool,
+    },
+    EndGroup,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Change {
+    param: ParamPtr,
+    before: f32,
+    after: f32,
+}
+
+/// Editor-local undo history built from the parameter gestures the widgets
+/// send to the host. Overlapping gestures, like dragging a curve that moves
+/// several params at once, end up as a single edit.
+#[derive(Debug, Default, Clone)]
+pub(super) struct UndoStack {
+    undo: Vec<Vec<Change>>,
+    redo: Vec<Vec<Change>>,
+    pending: Vec<Change>,
+    gestures: usize,
+    groups: usize,
+    ignoring: bool,
+}
+
+impl UndoStack {
+    pub fn observe(&mut self, event: &RawParamEvent) {
+        if self.ignoring {
+            return;
+        }
+
+        match *event {
+            RawParamEvent::BeginSetParameter(param) => {
+                self.gestures += 1;
+                if !self.pending.iter().any(|change| change.param == param) {
+                    // the params outlive the editor, so the pointer is still valid
+                    let before = unsafe { param.unmodulated_normalized_value() };
+                    self.pending.push(Change {
+                        param,
+                        before,
+                        after: before,
+                    });
+                }
+            }
+            RawParamEvent::SetParameterNormalized(param, value) => {
+                if let Some(change) = self.pending.iter_mut().find(|c| c.param == param) {
+                    change.after = value;
+                }
+            }
+            RawParamEvent::EndSetParameter(_) => {
+                self.gestures = self.gestures.saturating_sub(1);
+                self.commit();
+            }
+            _ => (),
+        }
+    }
+
+    pub fn begin_group(&mut self, record: bool) {
+        self.groups += 1;
+        self.ignoring = !record;
+    }
+
+    pub fn end_group(&mut self) {
+        self.groups = self.groups.saturating_sub(1);
+        if self.groups == 0 {
+            self.ignoring = false;
+            self.commit();
+        }
+    }
+
+    pub fn undo(&mut self, cx: &mut EventContext) {
+        if let Some(edit) = self.undo.pop() {
+            replay(cx, edit.iter().map(|change| (change.param, change.before)));
+            self.redo.push(edit);
+        }
+    }
+
+    pub fn redo(&mut self, cx: &mut EventContext) {
+        if let Some(edit) = self.redo.pop() {
+            replay(cx, edit.iter().map(|change| (change.param, change.after)));
+            self.undo.push(edit);
+        }
+    }
+
+    fn commit(&mut self) {
+        if self.gestures > 0 || self.groups > 0 {
+            return;
+        }
+
+        let edit: Vec<Change> = self
+            .pending
+            .drain(..)
+            .filter(|change| change.before != change.after)
+            .collect();
+        if edit.is_empty() {
+            return;
+        }
+
+        if self.undo.len() == MAX_UNDO {
+            self.undo.remove(0);
+        }
+        self.undo.push(edit);
+        self.redo.clear();
+    }
+}
+
+impl Data for UndoStack {
+    fn same(&self, other: &Self) -> bool {
+        self.undo.len() == other.undo.len() && self.redo.len() == other.redo.len()
+    }
+}
+
+// sets the params like a widget would, without recording it as a new edit
+fn replay(cx: &mut EventContext, values: impl Iterator<Item = (ParamPtr, f32)>) {
+    cx.emit(UndoEvent::BeginGroup { record: false });
+    for (param, value) in values {
+        cx.emit(RawParamEvent::BeginSetParameter(param));
+        cx.emit(RawParamEvent::SetParameterNormalized(param, value));
+        cx.emit(RawParamEvent::EndSetParameter(param));
+    }
+    cx.emit(UndoEvent::EndGroup);
+}
+
+pub(super) fn undo_controls(cx: &mut Context) {
+    HStack::new(cx, |cx| {
+        Button::new(
+            cx,
+            |cx| cx.emit(UndoEvent::Undo),
+            |cx| Label::new(cx, tr(cx, "undo.undo")),
+        )
+        .disabled(GUIData::undo.map(|undo| undo.undo.is_empty()));
+        Button::new(
+            cx,
+            |cx| cx.emit(UndoEvent::Redo),
+            |cx| Label::new(cx, tr(cx, "undo.redo")),
+        )
+        .disabled(GUIData::undo.map(|undo| undo.redo.is_empty()));
+    })
+    .class("undo-controls");
+}
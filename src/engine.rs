@@ -0,0 +1,1483 @@
+//! The limiter's DSP, without anything from nih-plug so it can run outside of
+//! a plugin host. The plugin and the offline CLI both drive a [`Processor`].
+
+use std::collections::VecDeque;
+
+mod dc_block;
+mod detector_eq;
+pub mod easing;
+mod history;
+mod leveler;
+mod moving_min;
+mod peak_pyramid;
+pub mod signal;
+mod tilt;
+mod true_peak;
+mod weighting;
+
+use dc_block::DcBlock;
+use detector_eq::{DetectorEq, EqBands};
+use easing::{Ease, EaseIn, EaseOut, Linear, LinearBlend, SCurve};
+use history::GrHistory;
+use leveler::Leveler;
+use moving_min::MovingMin;
+use peak_pyramid::{AttackReach, PeakPyramid};
+use tilt::Tilt;
+use true_peak::TruePeak;
+pub(crate) use weighting::k_weighting;
+use weighting::DetectorWeighting;
+pub use weighting::Weighting;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct SampleDB {
+    pub sample: f32,
+    pub db: f32,
+    // what the detector sees, after the weighting, tilt and EQ
+    pub detect: f32,
+    pub detect_db: f32,
+    // the input as it came in, before drive, for bypassing with the same delay
+    pub dry: f32,
+}
+
+impl SampleDB {
+    pub fn peak(&self) -> bool {
+        self.db > 0.0
+    }
+
+    // over the ceiling as far as the attack is concerned
+    fn detected(&self) -> bool {
+        self.detect_db > 0.0
+    }
+}
+
+#[derive(Default, Debug, PartialEq, Clone, Copy)]
+pub(crate) enum EnvState {
+    Hold(f32),
+    Release(f32),
+    #[default]
+    Off,
+}
+
+const BYPASS_FADE_MS: f32 = 10.0;
+// the most the inverse envelope boosts by, however deep the reduction goes
+const INVERSE_MAX_BOOST_DB: f32 = 24.0;
+
+/// The true peak catcher's lookahead, just enough for the attack to see the
+/// inter-sample peaks coming.
+pub const CATCHER_LOOKAHEAD_MS: f32 = 1.5;
+/// The least lookahead the true peak presets set. The estimate lands
+/// `true_peak::DELAY` samples back, so with less it has nowhere to go, and
+/// this is more than that down to 8 kHz.
+pub const TRUE_PEAK_LOOKAHEAD_MS: f32 = 1.0;
+const CATCHER_RELEASE_MS: f32 = 20.0;
+
+/// The stereo link ties channels together in pairs, so on a surround bus the
+/// fronts, center and LFE, and surrounds each stay balanced on their own
+/// instead of the whole mix ducking for a hit on one speaker. Mono and stereo
+/// are a single group, channel `i` is in group `link_groups(channels)[i]`.
+pub fn link_groups(channels: usize) -> Vec<usize> {
+    if channels <= 2 {
+        vec![0; channels]
+    } else {
+        (0..channels).map(|channel| channel / 2).collect()
+    }
+}
+
+struct LimiterBuffer {
+    channels: usize,
+    buffers: Vec<VecDeque<SampleDB>>,
+    state: Vec<EnvState>,
+    target: Vec<f32>,
+    // the reduction the envelope last went down to, where the hold level
+    // and the release start are measured from
+    peak: Vec<f32>,
+    envelope: Vec<f32>,
+    current_peaks: CurrentPeaks,
+}
+
+struct CurrentPeaks {
+    db: Vec<f32>,
+    position: Vec<f32>,
+    lerp_len: Vec<f32>,
+}
+
+pub(crate) struct CurrentPeakSingleMut<'a> {
+    pub db: &'a mut f32,
+    pub position: &'a mut f32,
+    pub lerp_len: &'a mut f32,
+}
+
+impl CurrentPeaks {
+    fn get_mut(&mut self, channel: usize) -> CurrentPeakSingleMut<'_> {
+        if channel >= self.db.len() {
+            panic!("outta bounds");
+        }
+        CurrentPeakSingleMut {
+            db: self.db.get_mut(channel).unwrap(),
+            position: self.position.get_mut(channel).unwrap(),
+            lerp_len: self.lerp_len.get_mut(channel).unwrap(),
+        }
+    }
+}
+
+impl<'a> CurrentPeakSingleMut<'a> {
+    fn read(&mut self, ease: impl Ease, overshoot: f32) -> Option<f32> {
+        *self.position += 1.0;
+        let progress = (*self.position + 1.0) / (*self.lerp_len + 1.0);
+        if progress > 1.0 {
+            *self.position -= 1.0;
+            return None;
+        }
+        Some(calc_atk_reduction(
+            *self.db,
+            attack_progress(ease, progress, overshoot),
+        ))
+    }
+}
+
+pub(crate) struct Limiter<'a> {
+    pub buffer: &'a mut VecDeque<SampleDB>,
+    pub state: &'a mut EnvState,
+    target: &'a mut f32,
+    peak: &'a mut f32,
+    envelope: &'a mut f32,
+    pub current_peak: CurrentPeakSingleMut<'a>,
+}
+
+impl LimiterBuffer {
+    fn new(channels: usize, sample_len: usize) -> Self {
+        let mut rv = LimiterBuffer {
+            channels,
+            // one over, a new sample goes in before the delayed one comes out
+            buffers: vec![VecDeque::with_capacity(sample_len + 1); channels],
+            state: vec![EnvState::Off; channels],
+            target: vec![0.0; channels],
+            peak: vec![0.0; channels],
+            envelope: vec![0.0; channels],
+            current_peaks: CurrentPeaks {
+                db: vec![0.0; channels],
+                position: vec![2.0; channels],
+                lerp_len: vec![1.0; channels],
+            },
+        };
+
+        for b in rv.buffers.iter_mut() {
+            for _ in 0..sample_len {
+                b.push_back(SampleDB {
+                    sample: 0.0,
+                    db: -100.0,
+                    detect: 0.0,
+                    detect_db: -100.0,
+                    dry: 0.0,
+                });
+            }
+        }
+
+        rv
+    }
+
+    // fills the lookahead with silence again, reusing the buffers when
+    // they're already big enough
+    fn refill(&mut self, sample_len: usize) {
+        for b in self.buffers.iter_mut() {
+            b.clear();
+            b.reserve(sample_len + 1);
+            b.resize(
+                sample_len,
+                SampleDB {
+                    sample: 0.0,
+                    db: -100.0,
+                    detect: 0.0,
+                    detect_db: -100.0,
+                    dry: 0.0,
+                },
+            );
+        }
+    }
+
+    fn get_mut(&'_ mut self, channel: usize) -> Limiter<'_> {
+        let channel = channel.clamp(0, self.channels - 1);
+        Limiter {
+            buffer: self.buffers.get_mut(channel).unwrap(),
+            state: self.state.get_mut(channel).unwrap(),
+            target: self.target.get_mut(channel).unwrap(),
+            peak: self.peak.get_mut(channel).unwrap(),
+            envelope: self.envelope.get_mut(channel).unwrap(),
+            current_peak: self.current_peaks.get_mut(channel),
+        }
+    }
+}
+
+/// Builds the attack or release curve from its shape params.
+pub fn build_envelope(
+    linearity: f32,
+    center: f32,
+    smooth_amount: f32,
+    pol_i: f32,
+    pol_o: f32,
+    pow_i: f32,
+    pow_o: f32,
+    sm_pol_i: f32,
+    sm_pol_o: f32,
+    sm_pow_i: f32,
+    sm_pow_o: f32,
+) -> Envelope {
+    let linear_smoothing_factor = (1.0 - smooth_amount) * linearity.powi(2);
+    LinearBlend::new(
+        SCurve::new(
+            EaseIn::new(pol_i, pow_i),
+            EaseOut::new(pol_o, pow_o),
+            center,
+            smooth_amount + linear_smoothing_factor,
+            SCurve::new(
+                EaseIn::new(sm_pol_i, sm_pow_i),
+                EaseOut::new(sm_pol_o, sm_pow_o),
+                0.5,
+                0.25 * linearity.powi(2),
+                Linear,
+            ),
+        ),
+        linearity,
+    )
+}
+
+pub type Envelope = LinearBlend<SCurve<SCurve<Linear>>>;
+
+/// Shape of the attack or release curve, see [`build_envelope`].
+#[derive(Debug, Clone, Copy)]
+pub struct EnvelopeShape {
+    pub linearity: f32,
+    pub center: f32,
+    pub smooth_amount: f32,
+    pub polarity_in: f32,
+    pub polarity_out: f32,
+    pub power_in: f32,
+    pub power_out: f32,
+    pub sm_polarity_in: f32,
+    pub sm_polarity_out: f32,
+    pub sm_power_in: f32,
+    pub sm_power_out: f32,
+}
+
+impl Default for EnvelopeShape {
+    fn default() -> Self {
+        Self {
+            linearity: 1.0,
+            center: 0.5,
+            smooth_amount: 0.0,
+            polarity_in: 1.0,
+            polarity_out: 1.0,
+            power_in: 2.0,
+            power_out: 2.0,
+            sm_polarity_in: 1.0,
+            sm_polarity_out: 1.0,
+            sm_power_in: 2.0,
+            sm_power_out: 2.0,
+        }
+    }
+}
+
+/// The release curves the morph control blends between, in knob order.
+pub const RELEASE_MORPHS: [(&str, EnvelopeShape); 4] = [
+    // most of the way back right away, then settling
+    ("fast-log", EnvelopeShape::eased(0.0, 0.0, 1.0, 3.0)),
+    ("linear", EnvelopeShape::eased(1.0, 0.5, 1.0, 2.0)),
+    // holding on to the reduction and letting go at the end
+    ("slow-exp", EnvelopeShape::eased(0.0, 1.0, 1.0, 3.0)),
+    // a quick first half, a plateau, then a quick finish
+    ("two-stage", EnvelopeShape::eased(0.0, 0.5, 0.0, 3.0)),
+];
+
+/// The attack curves the morph control blends between, in knob order.
+pub const ATTACK_MORPHS: [(&str, EnvelopeShape); 3] = [
+    // nearly all of the reduction as soon as the peak is in sight
+    ("instant clamp", EnvelopeShape::eased(0.0, 0.0, 1.0, 6.0)),
+    ("linear", EnvelopeShape::eased(1.0, 0.5, 1.0, 2.0)),
+    // barely reducing until the peak is close
+    ("slow lean-in", EnvelopeShape::eased(0.0, 1.0, 1.0, 6.0)),
+];
+
+impl EnvelopeShape {
+    // a curve without smoothing, same polarity and power on both sides
+    const fn eased(linearity: f32, center: f32, polarity: f32, power: f32) -> Self {
+        Self {
+            linearity,
+            center,
+            smooth_amount: 0.0,
+            polarity_in: polarity,
+            polarity_out: polarity,
+            power_in: power,
+            power_out: power,
+            sm_polarity_in: 1.0,
+            sm_polarity_out: 1.0,
+            sm_power_in: 2.0,
+            sm_power_out: 2.0,
+        }
+    }
+
+    /// Blends every setting of the two shapes, `t` of the way to `other`.
+    pub fn lerp(&self, other: &Self, t: f32) -> Self {
+        let mix = |a: f32, b: f32| a + (b - a) * t;
+        Self {
+            linearity: mix(self.linearity, other.linearity),
+            center: mix(self.center, other.center),
+            smooth_amount: mix(self.smooth_amount, other.smooth_amount),
+            polarity_in: mix(self.polarity_in, other.polarity_in),
+            polarity_out: mix(self.polarity_out, other.polarity_out),
+            power_in: mix(self.power_in, other.power_in),
+            power_out: mix(self.power_out, other.power_out),
+            sm_polarity_in: mix(self.sm_polarity_in, other.sm_polarity_in),
+            sm_polarity_out: mix(self.sm_polarity_out, other.sm_polarity_out),
+            sm_power_in: mix(self.sm_power_in, other.sm_power_in),
+            sm_power_out: mix(self.sm_power_out, other.sm_power_out),
+        }
+    }
+
+    /// Walks through `shapes` as `position` goes from 0 to 1, blending the
+    /// two it's between.
+    pub fn morph(shapes: &[(&str, Self)], position: f32) -> Self {
+        let last = shapes.len() - 1;
+        let position = position.clamp(0.0, 1.0) * last as f32;
+        let i = (position.floor() as usize).min(last.saturating_sub(1));
+        match shapes.get(i + 1) {
+            Some((_, next)) => shapes[i].1.lerp(next, position - i as f32),
+            None => shapes[i].1,
+        }
+    }
+
+    /// Name of the shape `position` is closest to, see [`EnvelopeShape::morph`].
+    pub fn morph_name<'a>(shapes: &[(&'a str, Self)], position: f32) -> &'a str {
+        let last = shapes.len() - 1;
+        let i = (position.clamp(0.0, 1.0) * last as f32).round() as usize;
+        shapes[i].0
+    }
+
+    /// A whole curve from one control: easing in below 0.5, out above it, and
+    /// linear right in the middle.
+    pub fn from_macro(shape: f32) -> Self {
+        let shape = shape.clamp(0.0, 1.0);
+        Self {
+            linearity: 1.0 - (shape * 2.0 - 1.0).abs(),
+            center: if shape < 0.5 { 1.0 } else { 0.0 },
+            power_in: 3.0,
+            power_out: 3.0,
+            ..Self::default()
+        }
+    }
+
+    pub fn build(&self) -> Envelope {
+        build_envelope(
+            self.linearity,
+            self.center,
+            self.smooth_amount,
+            self.polarity_in,
+            self.polarity_out,
+            self.power_in,
+            self.power_out,
+            self.sm_polarity_in,
+            self.sm_polarity_out,
+            self.sm_power_in,
+            self.sm_power_out,
+        )
+    }
+}
+
+/// How the reduction is worked out from what the detector sees.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    /// Peak by peak, along the attack, hold and release curves.
+    #[default]
+    Envelope,
+    /// The deepest reduction over the lookahead, held, released and then
+    /// averaged over the lookahead. The curves don't apply.
+    MovingMin,
+}
+
+/// Plain values of everything the limiter reads from its params, so the same
+/// processing can run without a host. Drive and output gain are gains, trim is
+/// in dB and times are in ms, like the params.
+#[derive(Debug, Clone, Copy)]
+pub struct Settings {
+    pub drive: f32,
+    pub trim: f32,
+    pub output_gain: f32,
+    pub lookahead_ms: f32,
+    pub lookahead_accuracy: i32,
+    /// Keeps the delay at the longest lookahead the processor has room for
+    /// and only moves the window the detector looks ahead over, so the
+    /// lookahead can change without clearing the limiter.
+    pub lookahead_glide: bool,
+    /// How much further ahead, in ms, the right channel looks than the
+    /// left, or the left than the right when negative. With more than two
+    /// channels the odd ones count as right. The delay covers the longer of
+    /// the two, so the channels stay in time.
+    pub lookahead_offset_ms: f32,
+    /// For monitoring live: no lookahead whatever `lookahead_ms` says, no
+    /// true peak estimate and no catcher, so nothing is delayed at all.
+    pub live: bool,
+    /// Takes any DC offset out of the input with a 5 Hz high pass, before
+    /// the detector. Changes the audio, not just what the detector sees.
+    pub dc_block: bool,
+    /// Slowly rides the input towards `leveling_target_db` before the
+    /// limiter, by no more than `leveling_range_db` either way.
+    pub leveling: bool,
+    pub leveling_target_db: f32,
+    pub leveling_range_db: f32,
+    /// Tilts what the detector sees, positive reacts more to highs and
+    /// negative more to lows. In dB at either end, the audio isn't touched.
+    pub detector_tilt_db: f32,
+    /// The curve the detector hears through, before the tilt.
+    pub detector_weighting: Weighting,
+    /// A low shelf, a peak at `detector_mid_hz` and a high shelf on what the
+    /// detector sees, after the tilt. In dB, the audio isn't touched.
+    pub detector_low_db: f32,
+    pub detector_mid_db: f32,
+    pub detector_mid_hz: f32,
+    pub detector_high_db: f32,
+    /// Puts out what the detector sees instead of the limited audio.
+    pub detector_listen: bool,
+    /// Boosts by as much as the limiter would have reduced, turning it into a
+    /// transient shaper that brings out what it would have caught. The
+    /// boosted output is clipped at the same ceiling the limiter keeps to.
+    pub inverse: bool,
+    pub algorithm: Algorithm,
+    pub attack_amt: f32,
+    /// How much deeper than needed the attack goes before relaxing back to
+    /// the exact reduction as the peak comes out, 0.5 is half again.
+    pub attack_overshoot: f32,
+    pub hold_ms: f32,
+    pub release_ms: f32,
+    /// How much of the peak reduction the hold stays at, from 0 to 1.
+    pub hold_level: f32,
+    /// Holds right where the attack left the envelope instead, ignoring
+    /// `hold_level`, so going into the hold never steps.
+    pub true_hold: bool,
+    /// How much of the peak reduction the release starts from, from 0 to 1.
+    pub release_start: f32,
+    /// Scales the release time with the average reduction over the last
+    /// second, so light limiting recovers faster than `release_ms` and heavy
+    /// limiting slower.
+    pub release_scaling: bool,
+    /// The fastest the reduction may change, in dB per ms. Whatever that
+    /// leaves over the ceiling is clipped off the sample instead.
+    pub gr_slew_db_per_ms: Option<f32>,
+    /// Holds the reduction where it is and pauses the envelope until let go.
+    /// Anything the held reduction leaves over the ceiling is clipped.
+    pub freeze: bool,
+    /// Goes by the peaks between samples too, estimated from the input
+    /// upsampled 4x, so the output stays under the ceiling in dBTP. Needs
+    /// a few samples of lookahead to see them in time.
+    pub true_peak: bool,
+    /// Runs a second, fast limiter with true peak detection after this one,
+    /// see [`Settings::catcher`].
+    pub true_peak_catcher: bool,
+    pub stereo_link: f32,
+    /// Stereo width after limiting, 1 leaves it as it is, 0 is mono and 2
+    /// doubles the side. Over 1 whatever the wider side takes over the
+    /// ceiling is clipped, the catcher also keeps it under in dBTP.
+    pub width: f32,
+    /// How far, in dB, a channel's reduction may stay above the deepest one
+    /// of its link group, whatever the stereo link. None leaves it to the
+    /// link alone.
+    pub balance_guard_db: Option<f32>,
+    pub compensate: bool,
+    /// A measured compensation in dB that replaces the one `compensate`
+    /// works out from drive.
+    pub measured_compensation_db: Option<f32>,
+    pub bypass: bool,
+    pub attack: EnvelopeShape,
+    pub release: EnvelopeShape,
+}
+
+impl Settings {
+    /// The attack and release curves.
+    pub fn envelopes(&self) -> (Envelope, Envelope) {
+        (self.attack.build(), self.release.build())
+    }
+
+    /// Settings for the true peak catcher that follows the limiter, a second
+    /// [`Processor`] with a short lookahead, a fast release and the channels
+    /// linked. It limits at the same ceiling as the output, trim and output
+    /// gain, so it only touches what the limiter let through. Without
+    /// `true_peak_catcher`, or in live mode, it passes everything straight
+    /// through with no delay.
+    pub fn catcher(&self) -> Settings {
+        if !self.true_peak_catcher || self.live {
+            return Settings {
+                bypass: true,
+                ..Settings::default()
+            };
+        }
+        let ceiling_db = self.trim + gain_to_db(self.output_gain);
+        Settings {
+            drive: db_to_gain(-ceiling_db),
+            trim: ceiling_db,
+            lookahead_ms: CATCHER_LOOKAHEAD_MS,
+            release_ms: CATCHER_RELEASE_MS,
+            true_peak: true,
+            stereo_link: 1.0,
+            bypass: self.bypass,
+            ..Settings::default()
+        }
+    }
+
+    pub fn compensation_db(&self) -> f32 {
+        match self.measured_compensation_db {
+            Some(db) if self.compensate => db,
+            _ => compensation_db(self.drive, self.compensate),
+        }
+    }
+
+    /// Output level in dB for a steady input at `input_db`, once the envelope
+    /// has settled. The first value is where the attack envelope alone would
+    /// bring it, anything it leaves over 0 dB is caught by the clip stage,
+    /// which gives the second. Anything else that shapes the static curve has
+    /// to go in here too.
+    pub fn steady_state_transfer(&self, input_db: f32) -> (f32, f32) {
+        let leveled = if self.leveling {
+            input_db
+                + leveler::settled_db(input_db, self.leveling_target_db, self.leveling_range_db)
+        } else {
+            input_db
+        };
+        let driven = leveled + gain_to_db(self.drive);
+        let makeup = self.trim + self.compensation_db() + gain_to_db(self.output_gain);
+
+        let attack = if driven > 0.0 {
+            driven + calc_atk_reduction(driven, 1.0) * self.attack_amt
+        } else {
+            driven
+        };
+        (attack + makeup, driven.min(0.0) + makeup)
+    }
+}
+
+/// Same as the plugin's param defaults.
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            drive: 1.0,
+            trim: 0.0,
+            output_gain: 1.0,
+            lookahead_ms: 0.0,
+            lookahead_accuracy: 1,
+            lookahead_glide: false,
+            lookahead_offset_ms: 0.0,
+            live: false,
+            dc_block: false,
+            leveling: false,
+            leveling_target_db: -18.0,
+            leveling_range_db: 6.0,
+            detector_tilt_db: 0.0,
+            detector_weighting: Weighting::Flat,
+            detector_low_db: 0.0,
+            detector_mid_db: 0.0,
+            detector_mid_hz: 1000.0,
+            detector_high_db: 0.0,
+            detector_listen: false,
+            inverse: false,
+            algorithm: Algorithm::Envelope,
+            attack_amt: 1.0,
+            attack_overshoot: 0.0,
+            hold_ms: 0.0,
+            release_ms: 0.0,
+            hold_level: 1.0,
+            true_hold: false,
+            release_start: 1.0,
+            release_scaling: false,
+            gr_slew_db_per_ms: None,
+            freeze: false,
+            true_peak: false,
+            true_peak_catcher: false,
+            stereo_link: 0.0,
+            width: 1.0,
+            balance_guard_db: None,
+            compensate: false,
+            measured_compensation_db: None,
+            bypass: false,
+            attack: EnvelopeShape::default(),
+            release: EnvelopeShape::default(),
+        }
+    }
+}
+
+/// The simple mode controls, all from 0 to 1. Unless the full set of params is
+/// in use, they stand in for the curve params, the hold and release times and
+/// the attack amount.
+#[derive(Debug, Clone, Copy)]
+pub struct Macros {
+    pub attack_shape: f32,
+    pub release_shape: f32,
+    /// From a 1 s release down to 10 ms, with a tenth of that held first.
+    pub speed: f32,
+    /// How much of a peak the attack takes off, all of it at 1.
+    pub amount: f32,
+}
+
+impl Macros {
+    /// Replaces everything the macros cover in `settings`.
+    pub fn apply(&self, settings: &mut Settings) {
+        settings.attack = EnvelopeShape::from_macro(self.attack_shape);
+        settings.release = EnvelopeShape::from_macro(self.release_shape);
+        settings.release_ms = 1000.0 * 100f32.powf(-self.speed.clamp(0.0, 1.0));
+        settings.hold_ms = settings.release_ms * 0.1;
+        // past 1 the attack takes off more than the peak is over
+        settings.attack_amt = self.amount.clamp(0.0, 1.0);
+    }
+}
+
+/// Same as the plugin's param defaults.
+impl Default for Macros {
+    fn default() -> Self {
+        Self {
+            attack_shape: 0.5,
+            release_shape: 0.5,
+            speed: 0.5,
+            amount: 1.0,
+        }
+    }
+}
+
+/// Settings of clip2zero, the limiter's drive and ceiling without the
+/// lookahead and envelope in between. Same units as [`Settings`].
+#[derive(Debug, Clone, Copy)]
+pub struct ClipSettings {
+    pub drive: f32,
+    pub trim: f32,
+    pub output_gain: f32,
+    pub compensate: bool,
+}
+
+impl ClipSettings {
+    /// Hard clips one sample at 0 dB. Trim and compensation come after the
+    /// ceiling like in the limiter, so they only ever bring it down.
+    pub fn process(&self, sample: f32) -> f32 {
+        let ceiling = (sample * self.drive).clamp(-1.0, 1.0);
+        ceiling
+            * db_to_gain(self.trim + compensation_db(self.drive, self.compensate))
+            * self.output_gain
+    }
+}
+
+impl Default for ClipSettings {
+    fn default() -> Self {
+        Self {
+            drive: 1.0,
+            trim: 0.0,
+            output_gain: 1.0,
+            compensate: false,
+        }
+    }
+}
+
+/// Settings of gate2zero. The gate opens when either channel goes over the
+/// threshold, and closes down to `range_db` once it has stayed under it for
+/// the hold time. Times are in ms.
+#[derive(Debug, Clone, Copy)]
+pub struct GateSettings {
+    pub threshold_db: f32,
+    pub range_db: f32,
+    pub attack_ms: f32,
+    pub hold_ms: f32,
+    pub release_ms: f32,
+    pub attack: EnvelopeShape,
+    pub release: EnvelopeShape,
+}
+
+impl Default for GateSettings {
+    fn default() -> Self {
+        Self {
+            threshold_db: -40.0,
+            range_db: -60.0,
+            attack_ms: 1.0,
+            hold_ms: 50.0,
+            release_ms: 200.0,
+            attack: EnvelopeShape::default(),
+            release: EnvelopeShape::default(),
+        }
+    }
+}
+
+/// The limiter's hold and release state machine turned around: the envelope
+/// is the gain reduction of a closing gate instead of a limiter letting go,
+/// and the attack curve opens it back up.
+pub struct Gate {
+    sample_rate: f32,
+    state: EnvState,
+    // where the release and attack curves start from, in dB
+    release_from: f32,
+    attack: Option<(f32, f32)>,
+    envelope: f32,
+}
+
+impl Gate {
+    /// Starts out closed.
+    pub fn new(sample_rate: f32, settings: &GateSettings) -> Self {
+        Self {
+            sample_rate,
+            state: EnvState::Off,
+            release_from: 0.0,
+            attack: None,
+            envelope: settings.range_db,
+        }
+    }
+
+    /// Gates interleaved samples in place, one block's worth.
+    pub fn process(&mut self, samples: &mut [f32], channels: usize, settings: &GateSettings) {
+        let envelopes = (settings.attack.build(), settings.release.build());
+        for frame in samples.chunks_exact_mut(channels.max(1)) {
+            self.process_frame(frame, settings, &envelopes);
+        }
+    }
+
+    /// One sample of every channel. Returns the gain reduction in dB.
+    pub fn process_frame(
+        &mut self,
+        frame: &mut [f32],
+        settings: &GateSettings,
+        (atk_env, rel_env): &(Envelope, Envelope),
+    ) -> f32 {
+        let attack = settings.attack_ms * 0.001 * self.sample_rate;
+        let hold = settings.hold_ms * 0.001 * self.sample_rate;
+        let release = settings.release_ms * 0.001 * self.sample_rate;
+
+        let level = frame
+            .iter()
+            .map(|sample| gain_to_db(sample.abs()))
+            .fold(-100.0, f32::max);
+
+        if level >= settings.threshold_db {
+            // every sample over the threshold starts the hold over
+            self.state = EnvState::Hold(0.0);
+            if self.envelope < 0.0 && self.attack.is_none() {
+                self.attack = Some((self.envelope, 0.0));
+            }
+        } else {
+            match &mut self.state {
+                EnvState::Hold(elapsed) => {
+                    *elapsed += 1.0;
+                    if *elapsed >= (hold + 1.0) {
+                        self.release_from = self.envelope;
+                        self.attack = None;
+                        self.state = EnvState::Release(0.0);
+                    }
+                }
+                EnvState::Release(elapsed) => {
+                    *elapsed += 1.0;
+                    let t = *elapsed / (release + 1.0);
+                    self.envelope = lerp(self.release_from, settings.range_db, rel_env.process(t));
+                    if *elapsed >= (release + 1.0) {
+                        self.state = EnvState::Off;
+                    }
+                }
+                EnvState::Off => self.envelope = settings.range_db,
+            }
+        }
+
+        if let Some((from, elapsed)) = &mut self.attack {
+            *elapsed += 1.0;
+            let t = *elapsed / (attack + 1.0);
+            if t >= 1.0 {
+                self.envelope = 0.0;
+                self.attack = None;
+            } else {
+                self.envelope = lerp(*from, 0.0, atk_env.process(t));
+            }
+        }
+
+        let gain = db_to_gain(self.envelope);
+        for sample in frame.iter_mut() {
+            *sample *= gain;
+        }
+        self.envelope
+    }
+}
+
+// pulls half the drive back out again, in dB
+fn compensation_db(drive: f32, compensate: bool) -> f32 {
+    if compensate {
+        gain_to_db(drive) / -2.0
+    } else {
+        0.0
+    }
+}
+
+/// What happened to one frame, per channel, for the meters.
+pub(crate) struct FrameReport<'a> {
+    // the driven input coming out of the lookahead, before any reduction
+    pub delayed: &'a [SampleDB],
+    pub unlinked: &'a [f32],
+    pub linked: &'a [f32],
+    pub clips: usize,
+}
+
+/// The limiter itself, everything that runs the same in the plugin and offline.
+pub struct Processor {
+    limiters: LimiterBuffer,
+    channels: usize,
+    sample_rate: f32,
+    lookahead_len: f32,
+    // the delay a gliding lookahead sits at, from `reserve_lookahead`
+    max_lookahead_len: f32,
+    // 0.0 is fully processed, 1.0 fully bypassed
+    bypass_mix: f32,
+    delayed: Vec<SampleDB>,
+    unlinked: Vec<f32>,
+    linked: Vec<f32>,
+    link_groups: Vec<usize>,
+    // the most reduction of each link group in the current frame
+    group_reduction: Vec<f32>,
+    leveler: Leveler,
+    dc_block: DcBlock,
+    weighting: DetectorWeighting,
+    tilt: Tilt,
+    detector_eq: DetectorEq,
+    true_peak: TruePeak,
+    moving_min: MovingMin,
+    peaks: PeakPyramid,
+    // one per channel, so channels with their own windows don't keep
+    // working each other's out again
+    reach: Vec<AttackReach>,
+    history: GrHistory,
+}
+
+impl Processor {
+    /// The lookahead starts out empty, `update_lookahead` sizes it.
+    pub fn new(channels: usize, sample_rate: f32, settings: &Settings) -> Self {
+        let link_groups = link_groups(channels);
+        let groups = link_groups.iter().max().map_or(0, |group| group + 1);
+        let mut peaks = PeakPyramid::new(channels);
+        peaks.reset(0, 0);
+        Self {
+            limiters: LimiterBuffer::new(channels, 0),
+            channels,
+            sample_rate,
+            lookahead_len: 0.0,
+            max_lookahead_len: 0.0,
+            // no fade when a session loads up bypassed
+            bypass_mix: if settings.bypass { 1.0 } else { 0.0 },
+            delayed: Vec::with_capacity(channels),
+            unlinked: Vec::with_capacity(channels),
+            linked: Vec::with_capacity(channels),
+            link_groups,
+            group_reduction: vec![0.0; groups],
+            leveler: Leveler::new(sample_rate),
+            dc_block: DcBlock::new(channels, sample_rate),
+            weighting: DetectorWeighting::new(channels, sample_rate),
+            tilt: Tilt::new(channels, sample_rate),
+            detector_eq: DetectorEq::new(channels, sample_rate),
+            true_peak: TruePeak::new(channels),
+            moving_min: MovingMin::new(channels),
+            peaks,
+            reach: vec![AttackReach::default(); channels],
+            history: GrHistory::new(channels, sample_rate),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        let la_len = self.lookahead_len.ceil() as usize;
+        self.limiters.refill(la_len);
+        self.peaks.reset(
+            la_len,
+            self.max_lookahead_len.max(self.lookahead_len) as usize,
+        );
+        self.reset_envelopes();
+        self.leveler.reset();
+        self.dc_block.reset();
+        self.weighting.reset();
+        self.tilt.reset();
+        self.detector_eq.reset();
+        self.true_peak.reset();
+        self.history.reset();
+    }
+
+    /// Makes room for a lookahead of up to `max_ms`, so changing it later
+    /// doesn't allocate on the audio thread. Also the delay with
+    /// [`Settings::lookahead_glide`].
+    pub fn reserve_lookahead(&mut self, max_ms: f32) {
+        self.max_lookahead_len = (max_ms * 0.001 * self.sample_rate).ceil();
+        // sized for it too, with what's in the lookahead already
+        let capacity = self.max_lookahead_len.max(self.lookahead_len) as usize;
+        for reach in self.reach.iter_mut() {
+            reach.reserve(capacity + 1);
+        }
+        self.peaks.reset(0, capacity);
+        for (channel, buffer) in self.limiters.buffers.iter().enumerate() {
+            for sample in buffer {
+                self.peaks.push(channel, sample.detect_db);
+            }
+        }
+        let max_len = self.max_lookahead_len as usize;
+        for buffer in self.limiters.buffers.iter_mut() {
+            buffer.reserve((max_len + 1).saturating_sub(buffer.len()));
+        }
+    }
+
+    /// Lets go of any gain reduction, but keeps what's in the lookahead.
+    pub fn reset_envelopes(&mut self) {
+        // the same values `LimiterBuffer::new` starts with
+        let limiters = &mut self.limiters;
+        limiters.state.fill(EnvState::Off);
+        limiters.target.fill(0.0);
+        limiters.peak.fill(0.0);
+        limiters.envelope.fill(0.0);
+        limiters.current_peaks.db.fill(0.0);
+        limiters.current_peaks.position.fill(2.0);
+        limiters.current_peaks.lerp_len.fill(1.0);
+        self.moving_min.reset(self.lookahead_len as usize);
+    }
+
+    /// How long the signal is delayed for, in samples.
+    pub fn delay_samples(&self) -> usize {
+        self.lookahead_len as usize
+    }
+
+    /// How much longer the output can go on once the input is silent: what's
+    /// still in the lookahead with a full hold and release after it, or just
+    /// the rest of the envelope when the lookahead is silent already.
+    pub fn tail_samples(&mut self, settings: &Settings) -> u32 {
+        let hold = (settings.hold_ms * 0.001 * self.sample_rate).round();
+        let release = (settings.release_ms * 0.001 * self.sample_rate).round();
+
+        (0..self.channels)
+            .map(|channel| {
+                let release = if settings.release_scaling {
+                    (release * self.history.release_scale(channel)).round()
+                } else {
+                    release
+                };
+                let limiter = self.limiters.get_mut(channel);
+                let delayed = limiter
+                    .buffer
+                    .iter()
+                    .rposition(|sample| sample.dry.abs() > MINUS_INFINITY_GAIN)
+                    .map_or(0.0, |i| (i + 1) as f32);
+                if delayed > 0.0 {
+                    return delayed + hold + release + 2.0;
+                }
+                if settings.algorithm == Algorithm::MovingMin {
+                    // the release is a time constant, five of them is close
+                    // enough to nothing
+                    return if self.moving_min.releasing(channel) {
+                        self.lookahead_len + hold + release * 5.0
+                    } else {
+                        0.0
+                    };
+                }
+                match *limiter.state {
+                    EnvState::Hold(elapsed) => hold + 1.0 - elapsed + release + 1.0,
+                    EnvState::Release(elapsed) => release + 1.0 - elapsed,
+                    EnvState::Off => 0.0,
+                }
+            })
+            .fold(0.0, f32::max)
+            .ceil() as u32
+    }
+
+    /// Resizes the lookahead if it changed, clearing the limiter. Returns the
+    /// latency to report to the host when it did.
+    pub fn update_lookahead(&mut self, settings: &Settings) -> Option<u32> {
+        let mut lookahead =
+            (settings.lookahead_ms + settings.lookahead_offset_ms.abs()) * 0.001 * self.sample_rate;
+        if settings.live {
+            lookahead = 0.0;
+        } else if settings.lookahead_glide {
+            lookahead = lookahead.max(self.max_lookahead_len);
+        }
+        if lookahead.ceil() == self.lookahead_len {
+            return None;
+        }
+
+        self.lookahead_len = lookahead.ceil();
+        self.reset();
+        Some(self.latency_samples())
+    }
+
+    /// The latency to report for the lookahead.
+    pub fn latency_samples(&self) -> u32 {
+        // in bitwig i have to set half the latency samples?
+        // is it like this in other DAWs?
+        // whyyyyyyyy
+        (self.lookahead_len / 2.0).ceil() as u32
+    }
+
+    /// Limits interleaved samples in place, one block's worth. A trailing
+    /// partial frame is left alone.
+    pub fn process(&mut self, samples: &mut [f32], settings: &Settings) {
+        let envelopes = settings.envelopes();
+        for (sample_id, frame) in samples.chunks_exact_mut(self.channels).enumerate() {
+            self.process_frame(frame, settings, &envelopes, sample_id);
+        }
+    }
+
+    /// How many samples ahead of the output the detector looks on
+    /// `channel`, the whole delay unless the lookahead glides or the channel
+    /// is the one offset back. Fractional then, so the window moves smoothly
+    /// instead of a sample at a time.
+    pub(crate) fn lookahead_window(&self, settings: &Settings, channel: usize) -> f32 {
+        let offset = settings.lookahead_offset_ms * 0.001 * self.sample_rate;
+        let longest = if settings.lookahead_glide && !settings.live {
+            (settings.lookahead_ms * 0.001 * self.sample_rate + offset.abs())
+                .min(self.lookahead_len)
+        } else {
+            self.lookahead_len
+        };
+        // the right channel is ahead with a positive offset
+        let behind = if channel % 2 == 1 { -offset } else { offset };
+        (longest - behind.max(0.0)).max(0.0)
+    }
+
+    pub(crate) fn limiter(&mut self, channel: usize) -> Limiter<'_> {
+        self.limiters.get_mut(channel)
+    }
+
+    /// One sample of every channel, replaced with the output. `sample_id` is
+    /// the position in the block, the lookahead accuracy counts on it.
+    pub(crate) fn process_frame(
+        &mut self,
+        frame: &mut [f32],
+        settings: &Settings,
+        (atk_env, rel_env): &(Envelope, Envelope),
+        sample_id: usize,
+    ) -> FrameReport<'_> {
+        let input = settings.drive
+            * self.leveler.process(
+                frame,
+                settings.leveling,
+                settings.leveling_target_db,
+                settings.leveling_range_db,
+            );
+        let atk_amt = settings.attack_amt;
+        let overshoot = settings.attack_overshoot;
+        let hold = settings.hold_ms * 0.001 * self.sample_rate;
+        let hold_level = settings.hold_level;
+        let true_hold = settings.true_hold;
+        let release_start = settings.release_start;
+        let release = settings.release_ms * 0.001 * self.sample_rate;
+        let max_slew = settings
+            .gr_slew_db_per_ms
+            .map(|rate| rate * 1000.0 / self.sample_rate);
+        self.detector_eq.set(EqBands {
+            low_db: settings.detector_low_db,
+            mid_db: settings.detector_mid_db,
+            mid_hz: settings.detector_mid_hz,
+            high_db: settings.detector_high_db,
+        });
+
+        self.delayed.clear();
+        self.unlinked.clear();
+        self.linked.clear();
+
+        let mut clips = 0;
+        self.group_reduction.fill(0.0);
+
+        for (i, sample) in frame.iter().enumerate() {
+            let window = self.lookahead_window(settings, i);
+            // samples nearer the input than the window aren't looked at yet
+            let skip = self.lookahead_len - window;
+            let mut limiter = self.limiters.get_mut(i);
+            let release = if settings.release_scaling {
+                release * self.history.release_scale(i)
+            } else {
+                release
+            };
+
+            // a NaN or an infinity would get stuck in the envelope and turn
+            // everything after it into NaNs, so they come out as silence
+            let (dry, driven) = if (*sample * input).is_finite() {
+                (*sample, *sample * input)
+            } else {
+                (0.0, 0.0)
+            };
+            let driven = self.dc_block.process(i, driven, settings.dc_block);
+            let weighted = self
+                .weighting
+                .process(i, driven, settings.detector_weighting);
+            let tilted = self.tilt.process(i, weighted, settings.detector_tilt_db);
+            let detected = self.detector_eq.process(i, tilted);
+            // a finite sample can still be loud enough that the filters
+            // overflow, and then their state is stuck on it too
+            let (driven, detected) = if driven.is_finite() && detected.is_finite() {
+                (driven, detected)
+            } else {
+                self.dc_block.reset();
+                self.weighting.reset();
+                self.tilt.reset();
+                self.detector_eq.reset();
+                (0.0, 0.0)
+            };
+            let new_sample = SampleDB {
+                sample: driven,
+                db: gain_to_db(driven.abs()),
+                detect: detected,
+                detect_db: gain_to_db(detected.abs()),
+                dry,
+            };
+
+            limiter.buffer.push_back(new_sample);
+            self.peaks.push(i, new_sample.detect_db);
+
+            // raises the samples the estimate is for, the clip stage and
+            // the attack then treat them like any other peak
+            let inter_sample = self.true_peak.process(i, driven);
+            // it could only ever be late without a lookahead
+            if settings.true_peak && !settings.live {
+                let newest = limiter.buffer.len() - 1;
+                // both samples around the peak, or the gain could still
+                // change between them
+                for back in [true_peak::DELAY, true_peak::DELAY + 1] {
+                    let Some(before) = newest
+                        .checked_sub(back)
+                        .and_then(|index| limiter.buffer.get_mut(index))
+                    else {
+                        continue;
+                    };
+                    let raise = gain_to_db(inter_sample) - before.db;
+                    if raise > 0.0 {
+                        before.db += raise;
+                        before.detect_db += raise;
+                        self.peaks.raise(i, back, before.detect_db);
+                    }
+                }
+            }
+            let last_envelope = *limiter.envelope;
+
+            match settings.algorithm {
+                // picks up again right where it was once let go
+                _ if settings.freeze => {}
+                Algorithm::Envelope => {
+                    // do stuff based on envelope state
+                    match &mut limiter.state {
+                        EnvState::Hold(elapsed) => {
+                            if *elapsed == 0.0 {
+                                // the envelope is still at the floor the attack
+                                // reached, the deepest reduction of this peak
+                                if true_hold {
+                                    // past the overshoot, if the attack had one
+                                    *limiter.envelope = limiter.envelope.max(*limiter.peak);
+                                } else {
+                                    *limiter.envelope = *limiter.peak * hold_level;
+                                }
+                                *limiter.target = *limiter.envelope;
+                            }
+                            *elapsed += 1.0;
+                            if *elapsed >= (hold + 1.0) {
+                                if release.round() >= 1.0 {
+                                    *limiter.state = EnvState::Release(0.0);
+                                } else {
+                                    *limiter.state = EnvState::Off;
+                                }
+                            }
+                        }
+                        EnvState::Release(elapsed) => {
+                            if *elapsed == 0.0 {
+                                *limiter.target = *limiter.peak * release_start;
+                                *limiter.envelope = *limiter.target;
+                            }
+                            *elapsed += 1.0;
+                            let t = *elapsed / (release + 1.0);
+
+                            // NOTE: calc_rel_reduction
+                            *limiter.envelope = lerp(*limiter.target, 0.0, rel_env.process(t));
+
+                            if *elapsed >= (release + 1.0) {
+                                *limiter.state = EnvState::Off;
+                            }
+                        }
+                        EnvState::Off => {
+                            if *limiter.envelope != 0.0
+                                || *limiter.target != 0.0
+                                || *limiter.peak != 0.0
+                            {
+                                *limiter.envelope = 0.0;
+                                *limiter.target = 0.0;
+                                *limiter.peak = 0.0;
+                            }
+                        }
+                    }
+
+                    // search buffer for peaks and calc atk env
+                    // or
+                    // calculate atk envelope using the last known peak
+                    let la_acc = settings.lookahead_accuracy;
+                    let mut atk_reduction = 0.0;
+                    if window >= 1.0 && sample_id as i32 % la_acc == 0 {
+                        let mut db = 0.0;
+                        let mut position = 0.0;
+                        let mut curr_reduct = 0.0;
+
+                        let buffer = &limiter.buffer;
+                        let oldest = buffer.len() - 1;
+                        // anything newer hasn't reached the window yet
+                        let newest = ((skip - 1.0).floor() + 1.0).max(0.0) as usize;
+                        if newest <= oldest {
+                            self.reach[i].update(*atk_env, window, skip, overshoot, buffer.len());
+                            self.peaks
+                                .search(i, oldest, newest, &self.reach[i], |back| {
+                                    let sample = &buffer[oldest - back];
+                                    let t = peak_pyramid::progress(
+                                        *atk_env, window, skip, overshoot, back,
+                                    );
+                                    let reduct = calc_atk_reduction(sample.detect_db, t);
+                                    // oldest first, so a tie goes to the newer one
+                                    // like it would newest first
+                                    if sample.detected() && reduct < 0.0 && reduct <= curr_reduct {
+                                        curr_reduct = reduct;
+                                        db = sample.detect_db;
+                                        position = back as f32 - skip;
+                                    }
+                                    -curr_reduct
+                                });
+                        }
+                        if db > 0.0 {
+                            *limiter.current_peak.db = db;
+                            *limiter.current_peak.position = position;
+                            *limiter.current_peak.lerp_len = window;
+                            atk_reduction = curr_reduct * atk_amt;
+                        }
+                    } else if let Some(reduction) = limiter.current_peak.read(*atk_env, overshoot) {
+                        atk_reduction = reduction * atk_amt;
+                    }
+
+                    if atk_reduction < *limiter.envelope {
+                        // the hold and release go by what the peak needs, not by
+                        // how far an overshoot took the envelope past it
+                        let exact = calc_atk_reduction(*limiter.current_peak.db, 1.0) * atk_amt;
+                        *limiter.target = atk_reduction;
+                        *limiter.peak = atk_reduction.max(exact);
+                        *limiter.envelope = atk_reduction;
+                        if hold.round() >= 1.0 {
+                            *limiter.state = EnvState::Hold(0.0);
+                        } else if release.round() >= 1.0 {
+                            *limiter.state = EnvState::Release(0.0);
+                        } else {
+                            *limiter.state = EnvState::Off;
+                        }
+                    }
+                }
+                Algorithm::MovingMin => {
+                    // the sample as far ahead of the output as the window
+                    // reaches
+                    let ahead = limiter.buffer.len() - 1 - skip.round() as usize;
+                    let required = limiter
+                        .buffer
+                        .get(ahead)
+                        .map_or(0.0, |sample| -sample.detect_db.max(0.0) * atk_amt);
+                    *limiter.envelope = self.moving_min.process(
+                        i,
+                        required,
+                        window.round() as usize,
+                        hold,
+                        release,
+                    );
+                }
+            }
+
+            // grab delayed sample from buffer
+            let mut delay = limiter.buffer.pop_front().unwrap();
+
+            // if the sample is still over 0.0 after the envelope is applied,
+            // clip it.
+            if delay.db + *limiter.envelope > 0.0 && settings.freeze {
+                // against the held reduction, which mustn't move
+                clips += 1;
+                let ceiling = db_to_gain(-*limiter.envelope);
+                delay.sample = delay.sample.clamp(-ceiling, ceiling);
+            } else if delay.db + *limiter.envelope > 0.0 {
+                clips += 1;
+                *limiter.target = -1.0 * delay.db;
+                *limiter.peak = *limiter.target;
+                *limiter.envelope = *limiter.target;
+                if hold.round() >= 1.0 {
+                    *limiter.state = EnvState::Hold(0.0);
+                } else if release.round() >= 1.0 {
+                    *limiter.state = EnvState::Release(0.0);
+                } else {
+                    *limiter.state = EnvState::Off;
+                }
+            }
+
+            // the state machine keeps its targets, only the envelope that's
+            // applied is held back. If that leaves the sample over, it's
+            // clipped, the ceiling holds either way.
+            if let Some(max_slew) = max_slew {
+                *limiter.envelope = limiter
+                    .envelope
+                    .clamp(last_envelope - max_slew, last_envelope + max_slew);
+                if delay.db + *limiter.envelope > 0.0 {
+                    let ceiling = db_to_gain(-*limiter.envelope);
+                    delay.sample = delay.sample.clamp(-ceiling, ceiling);
+                }
+            }
+
+            let group = &mut self.group_reduction[self.link_groups[i]];
+            *group = f32::min(*group, *limiter.envelope);
+
+            self.history.add(i, *limiter.envelope);
+            self.delayed.push(delay);
+            self.unlinked.push(*limiter.envelope);
+        }
+        self.history.end_frame();
+
+        let compensation = settings.compensation_db();
+
+        // bypassing fades over to the delayed dry signal instead of letting the
+        // host cut the plugin out, so the latency it reports stays the same
+        let bypass_target = if settings.bypass { 1.0 } else { 0.0 };
+        let bypass_step = (BYPASS_FADE_MS * 0.001 * self.sample_rate).max(1.0).recip();
+        self.bypass_mix = if bypass_target > self.bypass_mix {
+            (self.bypass_mix + bypass_step).min(bypass_target)
+        } else {
+            (self.bypass_mix - bypass_step).max(bypass_target)
+        };
+
+        // what trim, compensation and output gain make of 0 dB
+        let ceiling = db_to_gain(settings.trim + compensation) * settings.output_gain;
+
+        for (i, sample_pre) in self.delayed.iter().enumerate() {
+            let most_reduction = self.group_reduction[self.link_groups[i]];
+            let mut reduce = lerp(self.unlinked[i], most_reduction, settings.stereo_link);
+            if let Some(guard) = settings.balance_guard_db {
+                reduce = reduce.min(most_reduction + guard);
+            }
+            let processed = if settings.detector_listen {
+                sample_pre.detect
+            } else if settings.inverse {
+                let boost = (-reduce).min(INVERSE_MAX_BOOST_DB);
+                (sample_pre.sample
+                    * db_to_gain(boost + settings.trim + compensation)
+                    * settings.output_gain)
+                    .clamp(-ceiling, ceiling)
+            } else {
+                sample_pre.sample
+                    * db_to_gain(reduce + settings.trim + compensation)
+                    * settings.output_gain
+            };
+            frame[i] = processed;
+            self.linked.push(reduce);
+        }
+
+        // in M/S, only for stereo, the pairs of other layouts aren't L/R
+        if settings.width != 1.0 {
+            if let [left, right] = &mut frame[..] {
+                let mid = (*left + *right) * 0.5;
+                let side = (*left - *right) * 0.5 * settings.width;
+                // listening to the detector, samples can be near enough the
+                // float limit that widening overflows, those stay as they are
+                if (mid + side).is_finite() && (mid - side).is_finite() {
+                    *left = mid + side;
+                    *right = mid - side;
+                }
+                // more side than the limiter saw can go over the ceiling
+                // again, it's clipped back to it
+                if settings.width > 1.0 && !settings.detector_listen {
+                    for sample in [left, right] {
+                        if sample.abs() > ceiling {
+                            clips += 1;
+                            *sample = sample.clamp(-ceiling, ceiling);
+                        }
+                    }
+                }
+            }
+        }
+
+        for (output, sample_pre) in frame.iter_mut().zip(self.delayed.iter()) {
+            *output = lerp(*output, sample_pre.dry, self.bypass_mix);
+        }
+
+        FrameReport {
+            delayed: &self.delayed,
+            unlinked: &self.unlinked,
+            linked: &self.linked,
+            clips,
+        }
+    }
+}
+
+/// What [`render`] produced, one `Vec` per channel. The output is still
+/// delayed by the lookahead, `delay` samples.
+#[derive(Debug, Clone)]
+pub struct Render {
+    pub output: Vec<Vec<f32>>,
+    /// The gain reduction in dB, after the stereo link.
+    pub reduction: Vec<Vec<f32>>,
+    pub delay: usize,
+}
+
+/// Runs `input`, one slice per channel, through a fresh limiter with fixed
+/// settings, `block_size` samples at a time like a host would. For tests and
+/// comparing builds, the same input always renders the same.
+pub fn render(
+    input: &[&[f32]],
+    sample_rate: f32,
+    settings: &Settings,
+    block_size: usize,
+) -> Render {
+    let channels = input.len();
+    let len = input.iter().map(|channel| channel.len()).min().unwrap_or(0);
+    let block_size = block_size.max(1);
+
+    let mut processor = Processor::new(channels, sample_rate, settings);
+    processor.update_lookahead(settings);
+    let envelopes = settings.envelopes();
+
+    let mut output = vec![Vec::with_capacity(len); channels];
+    let mut reduction = vec![Vec::with_capacity(len); channels];
+    let mut frame = vec![0.0; channels];
+    for start in (0..len).step_by(block_size) {
+        for sample in start..(start + block_size).min(len) {
+            for (value, channel) in frame.iter_mut().zip(input) {
+                *value = channel[sample];
+            }
+            let report = processor.process_frame(&mut frame, settings, &envelopes, sample - start);
+            for (trace, linked) in reduction.iter_mut().zip(report.linked) {
+                trace.push(*linked);
+            }
+            for (trace, value) in output.iter_mut().zip(&frame) {
+                trace.push(*value);
+            }
+        }
+    }
+
+    Render {
+        output,
+        reduction,
+        delay: processor.delay_samples(),
+    }
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+// the share of the attack at its end the overshoot relaxes over
+const OVERSHOOT_RELAX: f32 = 0.25;
+
+// how much of its reduction the attack is taking off at `progress`, past 1
+// with an overshoot until it comes back over the end
+fn attack_progress(ease: impl Ease, progress: f32, overshoot: f32) -> f32 {
+    if overshoot <= 0.0 {
+        return ease.process(progress);
+    }
+    let split = 1.0 - OVERSHOOT_RELAX;
+    if progress < split {
+        ease.process(progress / split) * (1.0 + overshoot)
+    } else {
+        lerp(1.0 + overshoot, 1.0, (progress - split) / OVERSHOOT_RELAX)
+    }
+}
+
+fn calc_atk_reduction(db: f32, t: f32) -> f32 {
+    lerp(0.0, -1.0 * db, t)
+}
+
+// what nih-plug's `util` treats as silence, -100 dB
+const MINUS_INFINITY_GAIN: f32 = 1e-5;
+
+pub fn gain_to_db(gain: f32) -> f32 {
+    gain.max(MINUS_INFINITY_GAIN).ln() * (20.0 / std::f32::consts::LN_10)
+}
+
+pub fn db_to_gain(db: f32) -> f32 {
+    (db * (std::f32::consts::LN_10 / 20.0)).exp()
+}
@@ -0,0 +1,41 @@
+//! A gentle high pass on the audio before anything else sees it, so an
+//! offset neither biases the detector nor eats into the headroom under the
+//! ceiling. Unlike the detector's filters this one is heard.
+
+use std::f32::consts::TAU;
+
+const CUTOFF_HZ: f32 = 5.0;
+
+/// One first order high pass per channel, the last input and output of each.
+#[derive(Debug, Clone)]
+pub(crate) struct DcBlock {
+    state: Vec<(f32, f32)>,
+    coef: f32,
+}
+
+impl DcBlock {
+    pub fn new(channels: usize, sample_rate: f32) -> Self {
+        Self {
+            state: vec![(0.0, 0.0); channels],
+            coef: (-TAU * CUTOFF_HZ / sample_rate).exp(),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.state.fill((0.0, 0.0));
+    }
+
+    /// Runs every sample through, even when off, so the filter has settled
+    /// by the time it's switched on.
+    pub fn process(&mut self, channel: usize, sample: f32, enabled: bool) -> f32 {
+        let (last_in, last_out) = &mut self.state[channel];
+        let out = sample - *last_in + self.coef * *last_out;
+        *last_in = sample;
+        *last_out = out;
+        if enabled {
+            out
+        } else {
+            sample
+        }
+    }
+}
@@ -0,0 +1,163 @@
+//! A low shelf, a peak and a high shelf on the detector's copy of the signal,
+//! after the tilt, for shaping what sets the limiter off without splitting
+//! the audio into bands. The audio itself never goes through it.
+
+use std::f32::consts::{FRAC_1_SQRT_2, TAU};
+
+use super::db_to_gain;
+
+const LOW_SHELF_HZ: f32 = 120.0;
+const HIGH_SHELF_HZ: f32 = 6000.0;
+const PEAK_Q: f32 = 1.0;
+
+/// The EQ's gains in dB and where the peak sits.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(crate) struct EqBands {
+    pub low_db: f32,
+    pub mid_db: f32,
+    pub mid_hz: f32,
+    pub high_db: f32,
+}
+
+/// A biquad's coefficients, normalized by a0. Also the weighting's.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Coefs {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+}
+
+impl Coefs {
+    pub(super) fn new(b0: f32, b1: f32, b2: f32, a0: f32, a1: f32, a2: f32) -> Self {
+        Self {
+            b0: b0 / a0,
+            b1: b1 / a0,
+            b2: b2 / a0,
+            a1: a1 / a0,
+            a2: a2 / a0,
+        }
+    }
+
+    // RBJ cookbook shelves and peak
+    fn shelf(high: bool, hz: f32, db: f32, sample_rate: f32) -> Self {
+        let a = db_to_gain(db * 0.5);
+        let w = TAU * hz.min(sample_rate * 0.45) / sample_rate;
+        let (sin, cos) = w.sin_cos();
+        let alpha = sin * FRAC_1_SQRT_2;
+        let root = 2.0 * a.sqrt() * alpha;
+        // the high shelf is the low one mirrored
+        let sign = if high { -1.0 } else { 1.0 };
+        Self::new(
+            a * ((a + 1.0) - sign * (a - 1.0) * cos + root),
+            sign * 2.0 * a * ((a - 1.0) - sign * (a + 1.0) * cos),
+            a * ((a + 1.0) - sign * (a - 1.0) * cos - root),
+            (a + 1.0) + sign * (a - 1.0) * cos + root,
+            -sign * 2.0 * ((a - 1.0) + sign * (a + 1.0) * cos),
+            (a + 1.0) + sign * (a - 1.0) * cos - root,
+        )
+    }
+
+    fn peak(hz: f32, db: f32, sample_rate: f32) -> Self {
+        let a = db_to_gain(db * 0.5);
+        let w = TAU * hz.min(sample_rate * 0.45) / sample_rate;
+        let (sin, cos) = w.sin_cos();
+        let alpha = sin / (2.0 * PEAK_Q);
+        Self::new(
+            1.0 + alpha * a,
+            -2.0 * cos,
+            1.0 - alpha * a,
+            1.0 + alpha / a,
+            -2.0 * cos,
+            1.0 - alpha / a,
+        )
+    }
+
+    /// How much the filter passes at `hz`.
+    pub(super) fn magnitude(&self, hz: f32, sample_rate: f32) -> f32 {
+        let w = TAU * hz / sample_rate;
+        let (sin, cos) = w.sin_cos();
+        let (sin2, cos2) = (2.0 * w).sin_cos();
+        // both sides at z = e^jw
+        let num = (
+            self.b0 + self.b1 * cos + self.b2 * cos2,
+            self.b1 * sin + self.b2 * sin2,
+        );
+        let den = (
+            1.0 + self.a1 * cos + self.a2 * cos2,
+            self.a1 * sin + self.a2 * sin2,
+        );
+        (num.0.hypot(num.1)) / den.0.hypot(den.1)
+    }
+
+    pub(super) fn scaled(self, gain: f32) -> Self {
+        Self {
+            b0: self.b0 * gain,
+            b1: self.b1 * gain,
+            b2: self.b2 * gain,
+            ..self
+        }
+    }
+
+    /// Transposed direct form II, `state` is the filter's two delays.
+    pub(super) fn process(&self, state: &mut [f32; 2], sample: f32) -> f32 {
+        let out = self.b0 * sample + state[0];
+        state[0] = self.b1 * sample - self.a1 * out + state[1];
+        state[1] = self.b2 * sample - self.a2 * out;
+        out
+    }
+}
+
+/// The three bands for every channel, the coefficients only worked out
+/// again when the settings change.
+#[derive(Debug, Clone)]
+pub(crate) struct DetectorEq {
+    sample_rate: f32,
+    bands: EqBands,
+    coefs: [Coefs; 3],
+    state: Vec<[[f32; 2]; 3]>,
+}
+
+impl DetectorEq {
+    pub fn new(channels: usize, sample_rate: f32) -> Self {
+        let mut eq = Self {
+            sample_rate,
+            bands: EqBands::default(),
+            coefs: [Coefs::new(1.0, 0.0, 0.0, 1.0, 0.0, 0.0); 3],
+            state: vec![[[0.0; 2]; 3]; channels],
+        };
+        eq.set(EqBands {
+            mid_hz: 1000.0,
+            ..EqBands::default()
+        });
+        eq
+    }
+
+    pub fn reset(&mut self) {
+        self.state.fill([[0.0; 2]; 3]);
+    }
+
+    pub fn set(&mut self, bands: EqBands) {
+        if bands == self.bands {
+            return;
+        }
+        self.bands = bands;
+        self.coefs = [
+            Coefs::shelf(false, LOW_SHELF_HZ, bands.low_db, self.sample_rate),
+            Coefs::peak(bands.mid_hz, bands.mid_db, self.sample_rate),
+            Coefs::shelf(true, HIGH_SHELF_HZ, bands.high_db, self.sample_rate),
+        ];
+    }
+
+    /// Runs every sample through, even when flat, like the tilt.
+    pub fn process(&mut self, channel: usize, sample: f32) -> f32 {
+        let state = &mut self.state[channel];
+        self.coefs
+            .iter()
+            .zip(state.iter_mut())
+            .fold(sample, |sample, (coefs, state)| {
+                coefs.process(state, sample)
+            })
+    }
+}
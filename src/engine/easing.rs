@@ -6,7 +6,7 @@ pub trait Ease {
     fn process(&self, x: f32) -> f32;
 }
 
-#[derive(Default, Debug, Clone, Copy)]
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
 pub struct Linear;
 
 impl Ease for Linear {
@@ -15,7 +15,7 @@ impl Ease for Linear {
     }
 }
 
-#[derive(Default, Debug, Clone, Copy)]
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
 pub struct LinearBlend<T: Ease> {
     curve: T,
     linearity: f32,
@@ -33,7 +33,7 @@ impl<T: Ease> Ease for LinearBlend<T> {
     }
 }
 
-#[derive(Default, Debug, Clone, Copy)]
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
 pub struct SCurve<T: Ease> {
     ease_in: EaseIn,
     ease_out: EaseOut,
@@ -94,7 +94,7 @@ impl<T: Ease> Ease for SCurve<T> {
     }
 }
 
-#[derive(Default, Debug, Clone, Copy)]
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
 pub struct EaseOut {
     polarity: f32,
     power: f32,
@@ -123,7 +123,7 @@ impl Ease for EaseOut {
     }
 }
 
-#[derive(Default, Debug, Clone, Copy)]
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
 pub struct EaseIn {
     polarity: f32,
     power: f32,
@@ -0,0 +1,77 @@
+//! How deep the limiter has been reducing lately, for scaling the release.
+
+// the history is kept in 10 ms chunks, a second's worth of them
+const CHUNKS: usize = 100;
+const CHUNK_SECONDS: f32 = 0.01;
+// the average reduction the release time is set for, less releases faster
+// and more slower, in proportion
+const REFERENCE_DB: f32 = 3.0;
+const MIN_SCALE: f32 = 0.5;
+const MAX_SCALE: f32 = 4.0;
+
+/// The average reduction of every channel over the last second.
+#[derive(Debug, Clone)]
+pub(crate) struct GrHistory {
+    channels: usize,
+    // the reduction summed over each chunk, `CHUNKS` per channel
+    chunks: Vec<f32>,
+    // the chunk being filled, per channel
+    current: Vec<f32>,
+    // the sum of each channel's finished chunks
+    totals: Vec<f32>,
+    chunk: usize,
+    filled: usize,
+    chunk_len: usize,
+}
+
+impl GrHistory {
+    pub fn new(channels: usize, sample_rate: f32) -> Self {
+        Self {
+            channels,
+            chunks: vec![0.0; channels * CHUNKS],
+            current: vec![0.0; channels],
+            totals: vec![0.0; channels],
+            chunk: 0,
+            filled: 0,
+            chunk_len: ((sample_rate * CHUNK_SECONDS) as usize).max(1),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.chunks.fill(0.0);
+        self.current.fill(0.0);
+        self.totals.fill(0.0);
+        self.chunk = 0;
+        self.filled = 0;
+    }
+
+    /// The channel's envelope for this frame, in dB.
+    pub fn add(&mut self, channel: usize, envelope: f32) {
+        self.current[channel] -= envelope;
+    }
+
+    /// After every channel of a frame has been added.
+    pub fn end_frame(&mut self) {
+        self.filled += 1;
+        if self.filled < self.chunk_len {
+            return;
+        }
+        self.filled = 0;
+
+        for channel in 0..self.channels {
+            let chunks = &mut self.chunks[channel * CHUNKS..(channel + 1) * CHUNKS];
+            chunks[self.chunk] = self.current[channel];
+            // summed over again instead of kept running, so rounding
+            // errors can't pile up
+            self.totals[channel] = chunks.iter().sum();
+        }
+        self.current.fill(0.0);
+        self.chunk = (self.chunk + 1) % CHUNKS;
+    }
+
+    /// What to multiply the channel's release time by.
+    pub fn release_scale(&self, channel: usize) -> f32 {
+        let depth = self.totals[channel] / (CHUNKS * self.chunk_len) as f32;
+        (depth / REFERENCE_DB).clamp(MIN_SCALE, MAX_SCALE)
+    }
+}
@@ -0,0 +1,76 @@
+//! A slow gain rider in front of the limiter, so uneven material comes in at
+//! a steadier level and the limiter only has to deal with peaks.
+
+use super::{db_to_gain, gain_to_db};
+
+// how long the level it follows averages over
+const LEVEL_SECONDS: f32 = 3.0;
+// how long the gain takes to move most of the way to a new level
+const GAIN_SECONDS: f32 = 5.0;
+// quieter than this is a pause, not a quiet passage, so the gain stays put
+// instead of riding the noise floor up
+const GATE_DB: f32 = -50.0;
+
+/// Follows the mean square level of all channels together, so the stereo
+/// image doesn't move, and turns it towards the target.
+#[derive(Debug, Clone)]
+pub(crate) struct Leveler {
+    mean_square: f32,
+    gain_db: f32,
+    level_coef: f32,
+    gain_coef: f32,
+}
+
+impl Leveler {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            mean_square: 0.0,
+            gain_db: 0.0,
+            level_coef: one_pole(LEVEL_SECONDS, sample_rate),
+            gain_coef: one_pole(GAIN_SECONDS, sample_rate),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.mean_square = 0.0;
+        self.gain_db = 0.0;
+    }
+
+    /// The gain for one frame of input. Switched off it heads back to unity
+    /// just as slowly, so turning it off mid-song doesn't jump.
+    pub fn process(&mut self, frame: &[f32], on: bool, target_db: f32, range_db: f32) -> f32 {
+        let square = frame
+            .iter()
+            .filter(|sample| sample.is_finite())
+            .map(|sample| sample * sample)
+            .sum::<f32>()
+            / frame.len() as f32;
+        self.mean_square += (square - self.mean_square) * self.level_coef;
+
+        let level_db = gain_to_db(self.mean_square.sqrt());
+        let wanted = if !on {
+            0.0
+        } else if level_db < GATE_DB {
+            self.gain_db
+        } else {
+            (target_db - level_db).clamp(-range_db, range_db)
+        };
+        self.gain_db += (wanted - self.gain_db) * self.gain_coef;
+
+        db_to_gain(self.gain_db)
+    }
+}
+
+/// Where the gain settles for a steady input at `input_db`, taken as its
+/// RMS level.
+pub(crate) fn settled_db(input_db: f32, target_db: f32, range_db: f32) -> f32 {
+    if input_db < GATE_DB {
+        return 0.0;
+    }
+    (target_db - input_db).clamp(-range_db, range_db)
+}
+
+// the share of the distance a one-pole filter covers per sample
+fn one_pole(seconds: f32, sample_rate: f32) -> f32 {
+    1.0 - (-1.0 / (seconds * sample_rate)).exp()
+}
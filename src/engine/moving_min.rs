@@ -0,0 +1,112 @@
+//! The classic lookahead limiter, as an alternative to the envelope's state
+//! machine: the deepest reduction anything in the window needs, held and
+//! released, then averaged over the window so it ramps down ahead of the
+//! peak. Every sample in the window pulls the gain, which reads very
+//! differently on dense material than reacting peak by peak.
+
+use std::collections::VecDeque;
+
+/// Each channel's running minimum and average, in dB of reduction.
+#[derive(Debug, Clone)]
+pub(crate) struct MovingMin {
+    channels: Vec<Channel>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Channel {
+    now: u64,
+    // rising from the front, each with when it came in, so the front is the
+    // minimum of the window
+    minima: VecDeque<(u64, f32)>,
+    released: f32,
+    hold_left: f32,
+    // what the average is over, and its sum
+    recent: VecDeque<f32>,
+    sum: f64,
+}
+
+impl MovingMin {
+    pub fn new(channels: usize) -> Self {
+        Self {
+            channels: vec![Channel::default(); channels],
+        }
+    }
+
+    /// Starts over, with room for a window of up to `len` samples. Reuses
+    /// the buffers when they're already big enough.
+    pub fn reset(&mut self, len: usize) {
+        for channel in self.channels.iter_mut() {
+            channel.now = 0;
+            channel.minima.clear();
+            channel.minima.reserve(len + 2);
+            channel.released = 0.0;
+            channel.hold_left = 0.0;
+            channel.recent.clear();
+            channel.recent.reserve(len + 1);
+            channel.sum = 0.0;
+        }
+    }
+
+    /// Takes the reduction the sample `window` samples ahead of the output
+    /// needs, gives the one for the output. `hold` and `release` are in
+    /// samples, the release a time constant.
+    pub fn process(
+        &mut self,
+        channel: usize,
+        required: f32,
+        window: usize,
+        hold: f32,
+        release: f32,
+    ) -> f32 {
+        let channel = &mut self.channels[channel];
+        channel.now += 1;
+
+        // the window plus the sample it starts from, so every one of the
+        // averaged values has seen the output's own
+        while channel
+            .minima
+            .back()
+            .is_some_and(|(_, reduction)| *reduction >= required)
+        {
+            channel.minima.pop_back();
+        }
+        channel.minima.push_back((channel.now, required));
+        while channel
+            .minima
+            .front()
+            .is_some_and(|(time, _)| time + (window as u64) < channel.now)
+        {
+            channel.minima.pop_front();
+        }
+        let held = channel
+            .minima
+            .front()
+            .map_or(0.0, |(_, reduction)| *reduction);
+
+        if held <= channel.released {
+            channel.released = held;
+            channel.hold_left = hold;
+        } else if channel.hold_left >= 1.0 {
+            channel.hold_left -= 1.0;
+        } else if release < 1.0 {
+            channel.released = held;
+        } else {
+            channel.released += (held - channel.released) * (1.0 - (-release.recip()).exp());
+        }
+
+        let len = window.max(1);
+        channel.recent.push_back(channel.released);
+        channel.sum += channel.released as f64;
+        while channel.recent.len() > len {
+            channel.sum -= channel.recent.pop_front().unwrap_or(0.0) as f64;
+        }
+        // a window that just grew counts what it hasn't seen as no reduction
+        (channel.sum / len as f64) as f32
+    }
+
+    /// Still letting go of a reduction.
+    pub fn releasing(&self, channel: usize) -> bool {
+        let channel = &self.channels[channel];
+        channel.released < -0.01 || channel.sum < -0.01
+    }
+}
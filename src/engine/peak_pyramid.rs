@@ -0,0 +1,218 @@
+//! The loudest the detector saw over blocks of the lookahead, over blocks of
+//! those blocks and so on, so the attack's search can skip whole stretches
+//! that can't hold the peak it's looking for. What it costs then depends on
+//! how many peaks could matter, not on how long the lookahead is.
+//!
+//! A block can only beat the deepest reduction found so far if its loudest
+//! sample, as far into the attack as anything in the block gets, would. The
+//! search goes by exactly the reduction the limiter works out, so it finds
+//! the same peak a sample by sample search would.
+//!
+//! Samples are counted from the first one after a reset, so each level is a
+//! ring of blocks by that count. A block is only ever an upper bound, it
+//! keeps the samples that already left the lookahead, which just makes the
+//! search look closer than it had to.
+
+use super::{attack_progress, Envelope};
+
+const FANOUT: u64 = 16;
+
+/// Every channel's levels, the first over blocks of `FANOUT` samples.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PeakPyramid {
+    channels: Vec<Levels>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Levels {
+    // how many samples came in, the newest is one less
+    pushed: u64,
+    levels: Vec<Vec<f32>>,
+}
+
+impl PeakPyramid {
+    pub fn new(channels: usize) -> Self {
+        Self {
+            channels: vec![Levels::default(); channels],
+        }
+    }
+
+    /// Starts over with `len` silent samples already in, and room for up to
+    /// `capacity` at once. Reuses the levels when they're already big enough.
+    pub fn reset(&mut self, len: usize, capacity: usize) {
+        let capacity = capacity.max(len) as u64 + 1;
+        for channel in self.channels.iter_mut() {
+            channel.pushed = len as u64;
+            let mut size = FANOUT;
+            let mut level = 0;
+            loop {
+                // every block the lookahead can reach into, and one spare
+                let blocks = (capacity / size + 2) as usize;
+                match channel.levels.get_mut(level) {
+                    Some(ring) if ring.len() >= blocks => ring.fill(f32::NEG_INFINITY),
+                    Some(ring) => *ring = vec![f32::NEG_INFINITY; blocks],
+                    None => channel.levels.push(vec![f32::NEG_INFINITY; blocks]),
+                }
+                level += 1;
+                if size >= capacity {
+                    break;
+                }
+                size *= FANOUT;
+            }
+            channel.levels.truncate(level);
+        }
+    }
+
+    /// A new sample at `db`, the newest from now on.
+    pub fn push(&mut self, channel: usize, db: f32) {
+        let channel = &mut self.channels[channel];
+        let index = channel.pushed;
+        channel.pushed += 1;
+        let mut size = FANOUT;
+        for ring in channel.levels.iter_mut() {
+            let len = ring.len() as u64;
+            let block = &mut ring[(index / size % len) as usize];
+            // a block's first sample, whatever the ring held there is stale
+            if index.is_multiple_of(size) {
+                *block = db;
+            } else {
+                *block = block.max(db);
+            }
+            size *= FANOUT;
+        }
+    }
+
+    /// The sample `back` from the newest went up to `db`.
+    pub fn raise(&mut self, channel: usize, back: usize, db: f32) {
+        let channel = &mut self.channels[channel];
+        let Some(index) = channel.pushed.checked_sub(back as u64 + 1) else {
+            return;
+        };
+        let mut size = FANOUT;
+        for ring in channel.levels.iter_mut() {
+            let len = ring.len() as u64;
+            let block = &mut ring[(index / size % len) as usize];
+            *block = block.max(db);
+            size *= FANOUT;
+        }
+    }
+
+    /// Calls `visit` with how far back each sample between `oldest` and
+    /// `newest` back is, oldest first, except where a whole block's loudest
+    /// times the `reach` of its oldest sample is under the floor. `visit`
+    /// gives the floor from then on, which starts at 0.
+    pub fn search(
+        &self,
+        channel: usize,
+        oldest: usize,
+        newest: usize,
+        reach: &AttackReach,
+        mut visit: impl FnMut(usize) -> f32,
+    ) {
+        let channel = &self.channels[channel];
+        let Some(last) = channel.pushed.checked_sub(1) else {
+            return;
+        };
+        let (Some(from), Some(to)) = (
+            last.checked_sub(oldest as u64),
+            last.checked_sub(newest as u64),
+        ) else {
+            return;
+        };
+        let top = channel.levels.len() - 1;
+        let size = FANOUT.pow(top as u32 + 1);
+        let mut floor = 0.0;
+        for block in from / size..=to / size {
+            channel.search(top, block, (from, to), reach, &mut floor, &mut visit);
+        }
+    }
+}
+
+impl Levels {
+    fn search(
+        &self,
+        level: usize,
+        block: u64,
+        (from, to): (u64, u64),
+        reach: &AttackReach,
+        floor: &mut f32,
+        visit: &mut impl FnMut(usize) -> f32,
+    ) {
+        let last = self.pushed - 1;
+        let size = FANOUT.pow(level as u32 + 1);
+        let start = (block * size).max(from);
+        let end = (block * size + size - 1).min(to);
+
+        let ring = &self.levels[level];
+        let loudest = ring[(block % ring.len() as u64) as usize];
+        if loudest * reach.up_to((last - start) as usize) < *floor {
+            return;
+        }
+
+        if level == 0 {
+            for index in start..=end {
+                *floor = visit((last - index) as usize);
+            }
+            return;
+        }
+
+        let child_size = size / FANOUT;
+        for child in start / child_size..=end / child_size {
+            self.search(level - 1, child, (from, to), reach, floor, visit);
+        }
+    }
+}
+
+/// How far into the attack the samples get, the furthest of any up to each
+/// distance back from the newest. Only worked out again when the attack
+/// curve or the window changes, which a gliding lookahead does every sample.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct AttackReach {
+    key: Option<(Envelope, f32, f32, f32)>,
+    reach: Vec<f32>,
+}
+
+impl AttackReach {
+    /// Room for a lookahead of `len` samples without allocating.
+    pub fn reserve(&mut self, len: usize) {
+        self.reach
+            .reserve((len + 1).saturating_sub(self.reach.len()));
+    }
+
+    /// Works it out for the newest `len` samples, unless nothing it depends
+    /// on changed.
+    pub fn update(&mut self, attack: Envelope, window: f32, skip: f32, overshoot: f32, len: usize) {
+        let key = Some((attack, window, skip, overshoot));
+        if key == self.key && self.reach.len() == len {
+            return;
+        }
+        self.key = key;
+        self.reach.clear();
+        let mut furthest = 0.0f32;
+        for back in 0..len {
+            furthest = furthest.max(progress(attack, window, skip, overshoot, back));
+            self.reach.push(furthest);
+        }
+    }
+
+    pub fn up_to(&self, back: usize) -> f32 {
+        self.reach[back]
+    }
+}
+
+/// How far into the attack a sample `back` from the newest is, 0 before the
+/// window starts. One partway into the window starts out barely reducing, so
+/// the window's edge doesn't step.
+pub(super) fn progress(
+    attack: Envelope,
+    window: f32,
+    skip: f32,
+    overshoot: f32,
+    back: usize,
+) -> f32 {
+    let offset = back as f32 - skip;
+    if offset <= -1.0 {
+        return 0.0;
+    }
+    attack_progress(attack, (offset + 1.0) / (window + 1.0), overshoot)
+}
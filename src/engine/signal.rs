@@ -0,0 +1,128 @@
+//! Reproducible test material: the same parameters always give the same
+//! samples, so tests, benchmarks and the plugin's test tone can all share
+//! it. One channel at a time, times are in samples.
+
+use std::f32::consts::TAU;
+
+/// A steady sine.
+pub fn sine(sample_rate: f32, frequency: f32, amplitude: f32, len: usize) -> Vec<f32> {
+    (0..len)
+        .map(|i| amplitude * (TAU * frequency * i as f32 / sample_rate).sin())
+        .collect()
+}
+
+/// A sine from `start` for `burst_len` samples, silence around it. The phase
+/// runs from the start of the signal, not the start of the burst.
+pub fn sine_burst(
+    sample_rate: f32,
+    frequency: f32,
+    amplitude: f32,
+    start: usize,
+    burst_len: usize,
+    len: usize,
+) -> Vec<f32> {
+    let mut signal = sine(sample_rate, frequency, amplitude, len);
+    for (i, sample) in signal.iter_mut().enumerate() {
+        if !(start..start + burst_len).contains(&i) {
+            *sample = 0.0;
+        }
+    }
+    signal
+}
+
+/// A square wave, starting on the positive half.
+pub fn square(sample_rate: f32, frequency: f32, amplitude: f32, len: usize) -> Vec<f32> {
+    (0..len)
+        .map(|i| {
+            let phase = (i as f32 * frequency / sample_rate).fract();
+            if phase < 0.5 {
+                amplitude
+            } else {
+                -amplitude
+            }
+        })
+        .collect()
+}
+
+/// Single samples at `amplitude` every `period` samples, the first one at
+/// `start`. A period longer than the signal gives a single impulse.
+pub fn impulse_train(start: usize, period: usize, amplitude: f32, len: usize) -> Vec<f32> {
+    let mut signal = vec![0.0; len];
+    for i in (start..len).step_by(period.max(1)) {
+        signal[i] = amplitude;
+    }
+    signal
+}
+
+/// Pink noise that stays within about `amplitude`, the same for the same `seed`.
+pub fn pink_noise(seed: u64, amplitude: f32, len: usize) -> Vec<f32> {
+    let mut noise = PinkNoise::new(seed);
+    (0..len).map(|_| noise.next(amplitude)).collect()
+}
+
+/// [`pink_noise`] a sample at a time, for as long as it's asked for.
+#[derive(Debug, Clone)]
+pub struct PinkNoise {
+    state: u64,
+    filter: [f32; 3],
+}
+
+impl PinkNoise {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            // xorshift, zero would get stuck
+            state: seed.max(1),
+            filter: [0.0; 3],
+        }
+    }
+
+    pub fn next(&mut self, amplitude: f32) -> f32 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        let white = (self.state >> 40) as f32 / (1u64 << 24) as f32 * 2.0 - 1.0;
+
+        // Paul Kellet's economy filter, good to about 1 dB above 40 Hz
+        let [b0, b1, b2] = &mut self.filter;
+        *b0 = 0.99765 * *b0 + white * 0.0990460;
+        *b1 = 0.96300 * *b1 + white * 0.2965164;
+        *b2 = 0.57000 * *b2 + white * 1.0526913;
+        // the filter has a lot of gain, this brings its peaks back to 1
+        (*b0 + *b1 + *b2 + white * 0.1848) * 0.11 * amplitude
+    }
+}
+
+/// A sine a sample at a time, for as long as it's asked for. The frequency
+/// may change between samples without the phase jumping.
+#[derive(Debug, Clone, Default)]
+pub struct Oscillator {
+    // in cycles, kept under 1 so it doesn't lose precision over hours
+    phase: f32,
+}
+
+impl Oscillator {
+    pub fn next(&mut self, sample_rate: f32, frequency: f32, amplitude: f32) -> f32 {
+        let sample = amplitude * (TAU * self.phase).sin();
+        self.phase = (self.phase + frequency / sample_rate).fract();
+        sample
+    }
+}
+
+/// An exponential sweep from `from` to `to` Hz, the same time for every octave.
+pub fn sweep(sample_rate: f32, from: f32, to: f32, amplitude: f32, len: usize) -> Vec<f32> {
+    // the phase is summed up in f64, it drifts audibly in f32 over long sweeps
+    let duration = len as f64 / sample_rate as f64;
+    let (from, to) = (from as f64, to.max(f32::EPSILON) as f64);
+    let rate = (to / from).ln();
+    (0..len)
+        .map(|i| {
+            let t = i as f64 / sample_rate as f64;
+            let phase = if rate.abs() < f64::EPSILON {
+                from * t
+            } else {
+                from * duration / rate * ((t / duration * rate).exp() - 1.0)
+            };
+            amplitude * (std::f64::consts::TAU * phase).sin() as f32
+        })
+        .collect()
+}
@@ -0,0 +1,44 @@
+//! A tilt on the detector's copy of the signal: highs up and lows down, or
+//! the other way around, pivoting around the crossover. The audio itself
+//! never goes through it.
+
+use std::f32::consts::TAU;
+
+use super::db_to_gain;
+
+const PIVOT_HZ: f32 = 650.0;
+
+/// One first order crossover per channel, the two sides weighted against
+/// each other. Each end shelves by up to the tilt, with a slope of up to
+/// 6 dB/oct in between.
+#[derive(Debug, Clone)]
+pub(crate) struct Tilt {
+    lowpass: Vec<f32>,
+    coef: f32,
+}
+
+impl Tilt {
+    pub fn new(channels: usize, sample_rate: f32) -> Self {
+        Self {
+            lowpass: vec![0.0; channels],
+            coef: 1.0 - (-TAU * PIVOT_HZ / sample_rate).exp(),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.lowpass.fill(0.0);
+    }
+
+    /// Runs every sample through, even with no tilt, so turning it on
+    /// doesn't start the filter from silence.
+    pub fn process(&mut self, channel: usize, sample: f32, tilt_db: f32) -> f32 {
+        let lowpass = &mut self.lowpass[channel];
+        *lowpass += (sample - *lowpass) * self.coef;
+        if tilt_db == 0.0 {
+            return sample;
+        }
+
+        let highpass = sample - *lowpass;
+        *lowpass * db_to_gain(-tilt_db) + highpass * db_to_gain(tilt_db)
+    }
+}
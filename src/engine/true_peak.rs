@@ -0,0 +1,76 @@
+//! Inter-sample peaks, from the signal upsampled 4x like a true peak meter
+//! does. Each estimate needs a few samples after the pair it falls between,
+//! so it comes out `DELAY` samples late and goes onto the sample that far
+//! back in the lookahead.
+
+use std::f32::consts::PI;
+
+// samples around the pair the peak is looked for between, as many per phase
+// as BS.1770's true peak meter
+const TAPS: usize = 12;
+const PHASES: usize = 4;
+/// How far back the sample the estimate is for sits, 0 being the newest.
+pub(super) const DELAY: usize = TAPS / 2 - 1;
+
+/// The history of every channel and a Hann-windowed sinc for each of the
+/// three points between two samples.
+#[derive(Debug, Clone)]
+pub(crate) struct TruePeak {
+    kernels: [[f32; TAPS]; PHASES - 1],
+    history: Vec<[f32; TAPS]>,
+}
+
+impl TruePeak {
+    pub fn new(channels: usize) -> Self {
+        let mut kernels = [[0.0; TAPS]; PHASES - 1];
+        for (phase, kernel) in kernels.iter_mut().enumerate() {
+            let offset = (phase + 1) as f32 / PHASES as f32;
+            for (tap, coef) in kernel.iter_mut().enumerate() {
+                // from the oldest sample of the history to the newest
+                let x = offset + (TAPS / 2 - 1) as f32 - tap as f32;
+                let window = 0.5 * (1.0 + (PI * x / (TAPS / 2) as f32).cos());
+                *coef = sinc(x) * window;
+            }
+            // no gain at DC
+            let sum: f32 = kernel.iter().sum();
+            kernel.iter_mut().for_each(|coef| *coef /= sum);
+        }
+
+        Self {
+            kernels,
+            history: vec![[0.0; TAPS]; channels],
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.history.fill([0.0; TAPS]);
+    }
+
+    /// Takes the newest sample, gives the loudest point between the two
+    /// samples `DELAY` and `DELAY + 1` back.
+    pub fn process(&mut self, channel: usize, sample: f32) -> f32 {
+        let history = &mut self.history[channel];
+        history.copy_within(1.., 0);
+        history[TAPS - 1] = sample;
+
+        self.kernels
+            .iter()
+            .map(|kernel| {
+                kernel
+                    .iter()
+                    .zip(history.iter())
+                    .map(|(coef, sample)| coef * sample)
+                    .sum::<f32>()
+                    .abs()
+            })
+            .fold(0.0, f32::max)
+    }
+}
+
+fn sinc(x: f32) -> f32 {
+    if x == 0.0 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
@@ -0,0 +1,141 @@
+//! Perceptual weighting on the detector's copy of the signal, first in line
+//! before the tilt and the EQ, so the limiter reacts to how loud peaks sound
+//! rather than to the raw samples. The audio itself never goes through it.
+
+use std::f32::consts::PI;
+use std::f64::consts::PI as PI_F64;
+
+use super::detector_eq::Coefs;
+
+/// What the detector hears through.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Weighting {
+    #[default]
+    Flat,
+    /// IEC 61672 A-weighting, 0 dB at 1 kHz.
+    A,
+    /// The BS.1770 pre-filter loudness is measured through.
+    K,
+}
+
+// the A curve's poles, in Hz
+const A_POLES: [f32; 4] = [20.598_997, 107.652_65, 737.862_2, 12_194.217];
+
+/// Both curves for every channel. Only the selected one runs, the one
+/// switched to starts from silence.
+#[derive(Debug, Clone)]
+pub(crate) struct DetectorWeighting {
+    a: [Coefs; 3],
+    k: [Coefs; 2],
+    a_state: Vec<[[f32; 2]; 3]>,
+    k_state: Vec<[[f32; 2]; 2]>,
+    selected: Weighting,
+}
+
+impl DetectorWeighting {
+    pub fn new(channels: usize, sample_rate: f32) -> Self {
+        Self {
+            a: a_weighting(sample_rate),
+            k: k_weighting(sample_rate as f64).map(|([b0, b1, b2], [a1, a2])| {
+                Coefs::new(b0 as f32, b1 as f32, b2 as f32, 1.0, a1 as f32, a2 as f32)
+            }),
+            a_state: vec![[[0.0; 2]; 3]; channels],
+            k_state: vec![[[0.0; 2]; 2]; channels],
+            selected: Weighting::Flat,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.a_state.fill([[0.0; 2]; 3]);
+        self.k_state.fill([[0.0; 2]; 2]);
+    }
+
+    pub fn process(&mut self, channel: usize, sample: f32, weighting: Weighting) -> f32 {
+        // what the other curve held is from whenever it last ran
+        if weighting != self.selected {
+            self.selected = weighting;
+            self.reset();
+        }
+        match weighting {
+            Weighting::Flat => sample,
+            Weighting::A => cascade(&self.a, &mut self.a_state[channel], sample),
+            Weighting::K => cascade(&self.k, &mut self.k_state[channel], sample),
+        }
+    }
+}
+
+fn cascade(coefs: &[Coefs], state: &mut [[f32; 2]], sample: f32) -> f32 {
+    coefs
+        .iter()
+        .zip(state.iter_mut())
+        .fold(sample, |sample, (coefs, state)| {
+            coefs.process(state, sample)
+        })
+}
+
+// an analog second order section, highest power of s first, through the
+// bilinear transform
+fn bilinear(b: [f32; 3], a: [f32; 3], sample_rate: f32) -> Coefs {
+    let k = 2.0 * sample_rate;
+    let k2 = k * k;
+    Coefs::new(
+        b[0] * k2 + b[1] * k + b[2],
+        2.0 * (b[2] - b[0] * k2),
+        b[0] * k2 - b[1] * k + b[2],
+        a[0] * k2 + a[1] * k + a[2],
+        2.0 * (a[2] - a[0] * k2),
+        a[0] * k2 - a[1] * k + a[2],
+    )
+}
+
+fn a_weighting(sample_rate: f32) -> [Coefs; 3] {
+    // prewarped, or the top pole comes out too low at 44.1 kHz. Below
+    // Nyquist like the detector EQ's corners, at 22.05 and 24 kHz the top
+    // pole is past it and the filter would blow up.
+    let [w1, w2, w3, w4] = A_POLES.map(|hz| {
+        let hz = hz.min(sample_rate * 0.45);
+        2.0 * sample_rate * (PI * hz / sample_rate).tan()
+    });
+    let sections = [
+        // two zeros at DC for the double pole at the bottom
+        bilinear([1.0, 0.0, 0.0], [1.0, 2.0 * w1, w1 * w1], sample_rate),
+        bilinear([1.0, 0.0, 0.0], [1.0, w2 + w3, w2 * w3], sample_rate),
+        bilinear([0.0, 0.0, w4 * w4], [1.0, 2.0 * w4, w4 * w4], sample_rate),
+    ];
+    let gain: f32 = sections
+        .iter()
+        .map(|section| section.magnitude(1000.0, sample_rate))
+        .product();
+    let [first, second, third] = sections;
+    [first.scaled(gain.recip()), second, third]
+}
+
+/// The two stage pre-filter from BS.1770, a high shelf and a high pass, as
+/// biquads normalized to `a0`: `([b0, b1, b2], [a1, a2])`. The loudness
+/// meter runs it in f64, the detector in f32.
+pub(crate) fn k_weighting(sample_rate: f64) -> [([f64; 3], [f64; 2]); 2] {
+    let (f0, gain_db, q) = (1681.974450955533, 3.999843853973347, 0.7071752369554196);
+    let k = (PI_F64 * f0 / sample_rate).tan();
+    let vh = 10f64.powf(gain_db / 20.0);
+    let vb = vh.powf(0.4996667741545416);
+    let a0 = 1.0 + k / q + k * k;
+    let shelf = (
+        [
+            (vh + vb * k / q + k * k) / a0,
+            2.0 * (k * k - vh) / a0,
+            (vh - vb * k / q + k * k) / a0,
+        ],
+        [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+    );
+
+    let (f0, q) = (38.13547087602444, 0.5003270373238773);
+    let k = (PI_F64 * f0 / sample_rate).tan();
+    let a0 = 1.0 + k / q + k * k;
+    // BS.1770 leaves the zeros unnormalized
+    let high_pass = (
+        [1.0, -2.0, 1.0],
+        [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+    );
+
+    [shelf, high_pass]
+}
@@ -0,0 +1,293 @@
+use nih_plug::prelude::*;
+use nih_plug_vizia::ViziaState;
+use std::sync::Arc;
+
+use crate::engine::{Envelope, EnvelopeShape, Gate, GateSettings};
+use crate::{metadata, power_param};
+
+mod editor;
+
+/// A gate built from the limiter's envelope: it opens along the attack curve,
+/// and after the hold closes along the release curve.
+pub struct Gate2zero {
+    params: Arc<Gate2zeroParams>,
+    gate: Gate,
+    channels: usize,
+    sample_rate: f32,
+}
+
+#[derive(Params)]
+struct Gate2zeroParams {
+    #[persist = "editor-state"]
+    editor_state: Arc<ViziaState>,
+
+    #[id = "threshold"]
+    pub threshold: FloatParam,
+
+    #[id = "range"]
+    pub range: FloatParam,
+
+    #[id = "attack"]
+    pub attack: FloatParam,
+
+    #[id = "hold"]
+    pub hold: FloatParam,
+
+    #[id = "release"]
+    pub release: FloatParam,
+
+    #[nested(id_prefix = "atk", group = "Attack Curve")]
+    pub attack_curve: CurveParams,
+
+    #[nested(id_prefix = "rel", group = "Release Curve")]
+    pub release_curve: CurveParams,
+}
+
+// the shape of one of the curves, the same knobs the limiter has minus the
+// smoothing curve's own shape
+#[derive(Params)]
+struct CurveParams {
+    #[id = "linearity"]
+    pub linearity: FloatParam,
+
+    #[id = "s_center"]
+    pub center: FloatParam,
+
+    #[id = "polarity_in"]
+    pub polarity_in: FloatParam,
+
+    #[id = "polarity_out"]
+    pub polarity_out: FloatParam,
+
+    #[id = "power_in"]
+    pub power_in: FloatParam,
+
+    #[id = "power_out"]
+    pub power_out: FloatParam,
+
+    #[id = "smooth_amt"]
+    pub smooth_amt: FloatParam,
+}
+
+impl CurveParams {
+    fn new(name: &str) -> Self {
+        Self {
+            linearity: FloatParam::new(
+                format!("{name} Linearity"),
+                1.0,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_unit("%")
+            .with_value_to_string(formatters::v2s_f32_percentage(0)),
+            center: FloatParam::new(
+                format!("{name} S Center"),
+                0.5,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            ),
+            polarity_in: FloatParam::new(
+                format!("{name} Polarity In"),
+                1.0,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            ),
+            polarity_out: FloatParam::new(
+                format!("{name} Polarity Out"),
+                1.0,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            ),
+            power_in: power_param(format!("{name} Power In"), 2.0),
+            power_out: power_param(format!("{name} Power Out"), 2.0),
+            smooth_amt: FloatParam::new(
+                format!("{name} Smooth Amount"),
+                0.0,
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            ),
+        }
+    }
+
+    fn shape(&self) -> EnvelopeShape {
+        EnvelopeShape {
+            linearity: self.linearity.value(),
+            center: self.center.value(),
+            smooth_amount: self.smooth_amt.value(),
+            polarity_in: self.polarity_in.value(),
+            polarity_out: self.polarity_out.value(),
+            power_in: self.power_in.value(),
+            power_out: self.power_out.value(),
+            ..EnvelopeShape::default()
+        }
+    }
+}
+
+impl Gate2zeroParams {
+    fn settings(&self) -> GateSettings {
+        GateSettings {
+            threshold_db: self.threshold.value(),
+            range_db: self.range.value(),
+            attack_ms: self.attack.value(),
+            hold_ms: self.hold.value(),
+            release_ms: self.release.value(),
+            attack: self.attack_curve.shape(),
+            release: self.release_curve.shape(),
+        }
+    }
+}
+
+impl Default for Gate2zero {
+    fn default() -> Self {
+        let params = Arc::new(Gate2zeroParams::default());
+        let gate = Gate::new(44100.0, &params.settings());
+        Self {
+            params,
+            gate,
+            channels: 2,
+            sample_rate: 44100.0,
+        }
+    }
+}
+
+impl Default for Gate2zeroParams {
+    fn default() -> Self {
+        Self {
+            editor_state: editor::default_state(),
+
+            threshold: FloatParam::new(
+                "Threshold",
+                -40.0,
+                FloatRange::Linear {
+                    min: -80.0,
+                    max: 0.0,
+                },
+            )
+            .with_unit(" dB")
+            .with_value_to_string(formatters::v2s_f32_rounded(1)),
+
+            range: FloatParam::new(
+                "Range",
+                -60.0,
+                FloatRange::Linear {
+                    min: -100.0,
+                    max: 0.0,
+                },
+            )
+            .with_unit(" dB")
+            .with_value_to_string(formatters::v2s_f32_rounded(1)),
+
+            attack: FloatParam::new(
+                "Attack",
+                1.0,
+                FloatRange::Skewed {
+                    min: 0.0,
+                    max: 100.0,
+                    factor: 0.375,
+                },
+            )
+            .with_unit(" ms")
+            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+
+            hold: FloatParam::new(
+                "Hold",
+                50.0,
+                FloatRange::Skewed {
+                    min: 0.0,
+                    max: 1000.0,
+                    factor: 0.375,
+                },
+            )
+            .with_unit(" ms")
+            .with_value_to_string(formatters::v2s_f32_rounded(1)),
+
+            release: FloatParam::new(
+                "Release",
+                200.0,
+                FloatRange::Skewed {
+                    min: 0.0,
+                    max: 3000.0,
+                    factor: 0.25,
+                },
+            )
+            .with_unit(" ms")
+            .with_value_to_string(formatters::v2s_f32_rounded(0)),
+
+            attack_curve: CurveParams::new("Attack"),
+            release_curve: CurveParams::new("Release"),
+        }
+    }
+}
+
+impl Plugin for Gate2zero {
+    const NAME: &'static str = metadata::GATE2ZERO.name;
+    const VENDOR: &'static str = metadata::VENDOR;
+    const URL: &'static str = metadata::URL;
+    const EMAIL: &'static str = metadata::EMAIL;
+
+    const VERSION: &'static str = metadata::VERSION;
+
+    const AUDIO_IO_LAYOUTS: &'static [AudioIOLayout] = &[AudioIOLayout {
+        main_input_channels: NonZeroU32::new(2),
+        main_output_channels: NonZeroU32::new(2),
+
+        aux_input_ports: &[],
+        aux_output_ports: &[],
+
+        names: PortNames::const_default(),
+    }];
+
+    const MIDI_INPUT: MidiConfig = MidiConfig::None;
+    const MIDI_OUTPUT: MidiConfig = MidiConfig::None;
+
+    const SAMPLE_ACCURATE_AUTOMATION: bool = true;
+
+    type SysExMessage = ();
+    type BackgroundTask = ();
+
+    fn params(&self) -> Arc<dyn Params> {
+        self.params.clone()
+    }
+
+    fn editor(&mut self, _async_executor: AsyncExecutor<Self>) -> Option<Box<dyn Editor>> {
+        editor::create(self.params.clone(), self.params.editor_state.clone())
+    }
+
+    fn initialize(
+        &mut self,
+        audio_io_layout: &AudioIOLayout,
+        buffer_config: &BufferConfig,
+        _context: &mut impl InitContext<Self>,
+    ) -> bool {
+        self.channels = audio_io_layout.main_input_channels.unwrap().get() as usize;
+        self.sample_rate = buffer_config.sample_rate;
+        self.reset();
+        true
+    }
+
+    fn reset(&mut self) {
+        self.gate = Gate::new(self.sample_rate, &self.params.settings());
+    }
+
+    fn process(
+        &mut self,
+        buffer: &mut Buffer,
+        _aux: &mut AuxiliaryBuffers,
+        _context: &mut impl ProcessContext<Self>,
+    ) -> ProcessStatus {
+        let settings = self.params.settings();
+        let envelopes: (Envelope, Envelope) = (settings.attack.build(), settings.release.build());
+
+        for mut channel_samples in buffer.iter_samples() {
+            let mut frame = [0.0; 2];
+            for (i, sample) in channel_samples.iter_mut().take(2).enumerate() {
+                frame[i] = *sample;
+            }
+            let channels = self.channels.min(2);
+            self.gate
+                .process_frame(&mut frame[..channels], &settings, &envelopes);
+            for (i, sample) in channel_samples.iter_mut().take(2).enumerate() {
+                *sample = frame[i];
+            }
+        }
+
+        ProcessStatus::Normal
+    }
+}
+
+metadata::impl_formats!(Gate2zero, metadata::GATE2ZERO);
@@ -0,0 +1,55 @@
+use nih_plug::prelude::Editor;
+use nih_plug_vizia::vizia::prelude::*;
+use nih_plug_vizia::widgets::*;
+use nih_plug_vizia::{assets, create_vizia_editor, ViziaState, ViziaTheming};
+use std::sync::Arc;
+
+use super::Gate2zeroParams;
+
+#[derive(Lens)]
+struct Data {
+    params: Arc<Gate2zeroParams>,
+}
+
+impl Model for Data {}
+
+pub(super) fn default_state() -> Arc<ViziaState> {
+    ViziaState::new(|| (400, 560))
+}
+
+// nih-plug's generic list of every param, the curves are too many knobs for a
+// hand laid out page
+pub(super) fn create(
+    params: Arc<Gate2zeroParams>,
+    editor_state: Arc<ViziaState>,
+) -> Option<Box<dyn Editor>> {
+    create_vizia_editor(editor_state, ViziaTheming::Custom, move |cx, _| {
+        assets::register_noto_sans_light(cx);
+        assets::register_noto_sans_thin(cx);
+
+        Data {
+            params: params.clone(),
+        }
+        .build(cx);
+
+        VStack::new(cx, |cx| {
+            Label::new(cx, "Gate2Zero")
+                .font_family(vec![FamilyOwned::Name(String::from(assets::NOTO_SANS))])
+                .font_weight(FontWeightKeyword::Thin)
+                .font_size(30.0)
+                .height(Pixels(50.0))
+                .child_top(Stretch(1.0))
+                .child_bottom(Pixels(0.0));
+
+            ScrollView::new(cx, 0.0, 0.0, false, true, |cx| {
+                GenericUi::new(cx, Data::params).child_right(Pixels(10.0));
+            })
+            .width(Percentage(100.0));
+        })
+        .row_between(Pixels(0.0))
+        .child_left(Stretch(1.0))
+        .child_right(Stretch(1.0));
+
+        ResizeHandle::new(cx);
+    })
+}
@@ -1,45 +1,113 @@
 use atomic_float::AtomicF32;
 use core::f32;
 use nih_plug::prelude::*;
+use nih_plug::wrapper::state::{ParamValue, PluginState};
 use nih_plug_vizia::ViziaState;
-use std::sync::atomic::Ordering;
-use std::{collections::VecDeque, sync::Arc};
-
-mod easing;
+#[cfg(feature = "osc")]
+use std::net::Ipv4Addr;
+use std::sync::atomic::{AtomicU32, AtomicU8, AtomicUsize, Ordering};
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::{Arc, Mutex, RwLock},
+};
+
+mod automation;
+mod clip2zero;
+mod compensation;
+mod defaults;
+#[cfg(feature = "diagnostics")]
+mod diagnostics;
 mod editor;
-
-use easing::{Ease, EaseIn, EaseOut, Linear, LinearBlend, SCurve};
+pub mod engine;
+mod gate2zero;
+mod loudness;
+mod macro_knobs;
+mod material;
+mod metadata;
+mod midi;
+#[cfg(feature = "osc")]
+mod osc;
+mod telemetry;
+
+use automation::SettingsSmoother;
+use compensation::{CompensationAnalyzer, CompensationCapture, CompensationSnapshot};
+use defaults::{user_default, UserDefaults};
+use engine::signal::{Oscillator, PinkNoise};
+use engine::{
+    Algorithm, CurrentPeakSingleMut, EnvState, EnvelopeShape, Macros, SampleDB, Weighting,
+    ATTACK_MORPHS, RELEASE_MORPHS,
+};
+pub use engine::{Processor, Settings};
+use loudness::{LoudnessAnalyzer, LoudnessCapture, LoudnessSnapshot};
+use macro_knobs::{MacroControl, MacroMap, MacroTable, MACRO_KNOBS};
+use material::{MaterialAnalyzer, MaterialSnapshot};
+use midi::{CcTable, GrOut, MidiControl, MidiMap, NO_CC};
+use telemetry::{MeterFrame, MeterQueue};
 
 struct Limit2zero {
     params: Arc<Limit2zeroParams>,
-    lookahead_len: f32,
     sample_rate: f32,
-    channels: usize,
-    limiters: LimiterBuffer,
+    processor: Processor,
+    // the true peak catcher after it, passing straight through while it's off
+    catcher: Processor,
     gui_msg_timer: usize,
-    gui_pre_gain: [Arc<AtomicF32>; 2],
-    gui_post_gain: [Arc<AtomicF32>; 2],
-    gui_reduction: [Arc<AtomicF32>; 2],
-}
-
-#[derive(Debug, Default, Clone, Copy)]
-struct SampleDB {
-    sample: f32,
-    db: f32,
+    gui_meters: Arc<MeterQueue>,
+    // what's gathered for the next frame in `gui_meters`
+    meter_frame: MeterFrame,
+    gui_scope_peak: [Arc<AtomicF32>; 2],
+    gui_scope_gr: [Arc<AtomicF32>; 2],
+    gui_clip_count: Arc<AtomicUsize>,
+    gui_latency: Arc<AtomicU32>,
+    gui_sample_rate: Arc<AtomicF32>,
+    gui_lookahead: Arc<LookaheadSnapshot>,
+    gui_progress: Arc<EnvelopeProgress>,
+    gui_trigger: Arc<TriggerSnapshot>,
+    trigger: TriggerCapture,
+    gui_delta: Arc<DeltaSnapshot>,
+    smoother: SettingsSmoother,
+    tone_sine: Oscillator,
+    tone_noise: PinkNoise,
+    loudness: Arc<LoudnessSnapshot>,
+    loudness_capture: LoudnessCapture,
+    compensation: Arc<CompensationSnapshot>,
+    compensation_capture: CompensationCapture,
+    material: Arc<MaterialSnapshot>,
+    material_capture: LoudnessCapture,
+    // one sample of every channel, sized in `initialize`
+    frame: Vec<f32>,
+    midi: MidiControl,
+    macro_control: MacroControl,
+    gr_out: GrOut,
+    // the transport state of the last block
+    was_playing: bool,
+    #[cfg(feature = "osc")]
+    osc: Option<osc::OscServer>,
+    #[cfg(feature = "diagnostics")]
+    diagnostics: Arc<diagnostics::Diagnostics>,
+    #[cfg(feature = "diagnostics")]
+    diagnostics_capture: diagnostics::DiagnosticsCapture,
 }
 
-impl SampleDB {
-    fn peak(&self) -> bool {
-        self.db > 0.0
-    }
-}
-
-#[derive(Default, Debug, PartialEq, Clone, Copy)]
-enum EnvState {
-    Hold(f32),
-    Release(f32),
-    #[default]
-    Off,
+/// Work that's too heavy for `process()`, run on nih-plug's background thread.
+enum Task {
+    ResetLoudness {
+        channels: usize,
+        sample_rate: f32,
+    },
+    AnalyzeLoudness,
+    ResetCompensation {
+        channels: usize,
+        sample_rate: f32,
+        db: f32,
+    },
+    AnalyzeCompensation,
+    ResetMaterial {
+        channels: usize,
+        sample_rate: f32,
+    },
+    AnalyzeMaterial,
+    #[cfg(feature = "diagnostics")]
+    LogDiagnostics,
 }
 
 #[derive(Params)]
@@ -47,264 +115,503 @@ struct Limit2zeroParams {
     #[persist = "editor-state"]
     editor_state: Arc<ViziaState>,
 
-    #[id = "drive"]
-    pub drive: FloatParam,
-
-    #[id = "trim"]
-    pub trim: FloatParam,
-
-    #[id = "lookahead"]
-    pub lookahead: FloatParam,
-
-    #[id = "lookahead_accuracy"]
-    pub lookahead_accuracy: IntParam,
-
-    #[id = "attack_amt"]
-    pub attack_amt: FloatParam,
-
-    #[id = "atk_env_linearity"]
-    pub atk_env_linearity: FloatParam,
-
-    #[id = "atk_env_s_center"]
-    pub atk_env_center: FloatParam,
-
-    #[id = "atk_env_polarity_in"]
-    pub atk_env_polarity_in: FloatParam,
-
-    #[id = "atk_env_polarity_out"]
-    pub atk_env_polarity_out: FloatParam,
-
-    #[id = "atk_env_power_in"]
-    pub atk_env_power_in: FloatParam,
+    #[persist = "theme"]
+    theme: Arc<RwLock<editor::ThemeKind>>,
 
-    #[id = "atk_env_power_out"]
-    pub atk_env_power_out: FloatParam,
+    #[persist = "language"]
+    language: Arc<RwLock<editor::Language>>,
 
-    #[id = "atk_smooth_amt"]
-    pub atk_smooth_amt: FloatParam,
-
-    #[id = "atk_env_smooth_polarity_in"]
-    pub atk_env_sm_polarity_in: FloatParam,
-
-    #[id = "atk_env_smooth_polarity_out"]
-    pub atk_env_sm_polarity_out: FloatParam,
+    #[persist = "gr-view"]
+    gr_view: Arc<RwLock<editor::GRViewPrefs>>,
 
-    #[id = "atk_env_smooth_power_in"]
-    pub atk_env_sm_power_in: FloatParam,
+    #[persist = "low-power"]
+    low_power: Arc<RwLock<bool>>,
 
-    #[id = "atk_env_smooth_power_out"]
-    pub atk_env_sm_power_out: FloatParam,
+    #[persist = "locked-params"]
+    locked_params: Arc<RwLock<HashSet<String>>>,
 
-    #[id = "hold"]
-    pub hold: FloatParam,
+    #[persist = "midi-map"]
+    midi_map: Arc<RwLock<MidiMap>>,
 
-    #[id = "release"]
-    pub release: FloatParam,
+    // the param ID the next MIDI CC gets mapped to, set from the editor
+    midi_learn: Arc<RwLock<Option<String>>>,
 
-    #[id = "release_amt"]
-    pub release_amt: FloatParam,
+    // the CC that came in for it, the editor does the mapping
+    midi_learned: Arc<AtomicU8>,
 
-    #[id = "rel_linearity"]
-    pub rel_env_linearity: FloatParam,
+    // `midi_map` as the audio thread reads it
+    midi_table: Arc<RwLock<CcTable>>,
 
-    #[id = "rel_env_s_center"]
-    pub rel_env_center: FloatParam,
+    #[persist = "macro-map"]
+    macro_map: Arc<RwLock<MacroMap>>,
 
-    #[id = "rel_env_polarity_in"]
-    pub rel_env_polarity_in: FloatParam,
+    // `macro_map` as the audio thread reads it
+    macro_table: Arc<RwLock<MacroTable>>,
 
-    #[id = "rel_env_polarity_out"]
-    pub rel_env_polarity_out: FloatParam,
+    #[nested(group = "Simple")]
+    pub simple: SimpleParams,
 
-    #[id = "rel_env_power_in"]
-    pub rel_env_power_in: FloatParam,
+    #[nested(group = "Input")]
+    pub input: InputParams,
 
-    #[id = "rel_env_power_out"]
-    pub rel_env_power_out: FloatParam,
+    #[nested(group = "Attack Envelope")]
+    pub attack: AttackParams,
 
-    #[id = "rel_smooth_amt"]
-    pub rel_smooth_amt: FloatParam,
+    #[nested(group = "Attack Smoothing")]
+    pub attack_smoothing: AttackSmoothingParams,
 
-    #[id = "rel_env_smooth_polarity_in"]
-    pub rel_env_sm_polarity_in: FloatParam,
+    #[nested(group = "Hold/Release")]
+    pub hold_release: HoldReleaseParams,
 
-    #[id = "rel_env_smooth_polarity_out"]
-    pub rel_env_sm_polarity_out: FloatParam,
+    #[nested(group = "Release Envelope")]
+    pub release: ReleaseParams,
 
-    #[id = "rel_env_smooth_power_in"]
-    pub rel_env_sm_power_in: FloatParam,
+    #[nested(group = "Output")]
+    pub output: OutputParams,
 
-    #[id = "rel_env_smooth_power_out"]
-    pub rel_env_sm_power_out: FloatParam,
+    #[nested(group = "Macros")]
+    pub macros: MacroParams,
 
-    #[id = "stereo_link"]
-    pub stereo_link: FloatParam,
+    #[nested(group = "Test Tone")]
+    pub test_tone: TestToneParams,
 
-    #[id = "compensate"]
-    pub compensate: BoolParam,
+    #[id = "bypass"]
+    pub bypass: BoolParam,
 }
 
 impl Default for Limit2zero {
     fn default() -> Self {
+        let loudness = Arc::new(LoudnessSnapshot::default());
+        let compensation = Arc::new(CompensationSnapshot::default());
+        let material = Arc::new(MaterialSnapshot::default());
         Self {
             params: Arc::new(Limit2zeroParams::default()),
             sample_rate: 44100.0,
-            channels: 2,
-            lookahead_len: 0.0,
-            limiters: LimiterBuffer::new(2, 256),
+            processor: Processor::new(2, 44100.0, &Settings::default()),
+            catcher: Processor::new(2, 44100.0, &Settings::default().catcher()),
             gui_msg_timer: 0,
-            gui_pre_gain: [
-                Arc::new(AtomicF32::default()),
-                Arc::new(AtomicF32::default()),
-            ],
-            gui_post_gain: [
+            gui_meters: Arc::new(MeterQueue::default()),
+            meter_frame: MeterFrame::default(),
+            gui_scope_peak: [
                 Arc::new(AtomicF32::default()),
                 Arc::new(AtomicF32::default()),
             ],
-            gui_reduction: [
+            gui_scope_gr: [
                 Arc::new(AtomicF32::default()),
                 Arc::new(AtomicF32::default()),
             ],
+            gui_clip_count: Arc::new(AtomicUsize::new(0)),
+            gui_latency: Arc::new(AtomicU32::new(0)),
+            gui_sample_rate: Arc::new(AtomicF32::new(44100.0)),
+            gui_lookahead: Arc::new(LookaheadSnapshot::default()),
+            gui_progress: Arc::new(EnvelopeProgress::default()),
+            gui_trigger: Arc::new(TriggerSnapshot::default()),
+            trigger: TriggerCapture::new(44100.0),
+            gui_delta: Arc::new(DeltaSnapshot::default()),
+            smoother: SettingsSmoother::default(),
+            tone_sine: Oscillator::default(),
+            tone_noise: PinkNoise::new(1),
+            loudness_capture: LoudnessCapture::new(2, 44100.0, &loudness),
+            loudness,
+            compensation_capture: CompensationCapture::new(2, 44100.0, &compensation),
+            compensation,
+            material_capture: material.capture(2, 44100.0),
+            material,
+            frame: vec![0.0; 2],
+            midi: MidiControl::default(),
+            macro_control: MacroControl::default(),
+            gr_out: GrOut::new(44100.0),
+            was_playing: false,
+            #[cfg(feature = "osc")]
+            osc: None,
+            #[cfg(feature = "diagnostics")]
+            diagnostics: Arc::new(diagnostics::Diagnostics::default()),
+            #[cfg(feature = "diagnostics")]
+            diagnostics_capture: diagnostics::DiagnosticsCapture::new(2),
         }
     }
 }
 
-struct LimiterBuffer {
-    channels: usize,
-    buffers: Vec<VecDeque<SampleDB>>,
-    state: Vec<EnvState>,
-    target: Vec<f32>,
-    hold: Vec<f32>,
-    envelope: Vec<f32>,
-    current_peaks: CurrentPeaks,
-}
+pub(crate) const LOOKAHEAD_BINS: usize = 64;
 
-struct CurrentPeaks {
-    db: Vec<f32>,
-    position: Vec<f32>,
-    lerp_len: Vec<f32>,
+/// Downsampled copy of the lookahead window for the editor, oldest samples
+/// first with the loudest sample of each bin. The tracked peak is how far
+/// along the window the peak the attack is currently heading for sits, or
+/// negative when nothing is being tracked.
+pub(crate) struct LookaheadSnapshot {
+    pub bins: [[AtomicF32; LOOKAHEAD_BINS]; 2],
+    pub tracked_peak: [AtomicF32; 2],
 }
 
-struct CurrentPeakSingleMut<'a> {
-    db: &'a mut f32,
-    position: &'a mut f32,
-    lerp_len: &'a mut f32,
+impl Default for LookaheadSnapshot {
+    fn default() -> Self {
+        Self {
+            bins: std::array::from_fn(|_| std::array::from_fn(|_| AtomicF32::new(-100.0))),
+            tracked_peak: std::array::from_fn(|_| AtomicF32::new(-1.0)),
+        }
+    }
 }
 
-impl CurrentPeaks {
-    fn get_mut(&mut self, channel: usize) -> CurrentPeakSingleMut<'_> {
-        if channel >= self.db.len() {
-            panic!("outta bounds");
+impl LookaheadSnapshot {
+    // `window` is how many of the oldest samples the detector looks at
+    fn store(
+        &self,
+        channel: usize,
+        buffer: &VecDeque<SampleDB>,
+        window: f32,
+        peak: &CurrentPeakSingleMut,
+    ) {
+        let len = (window.ceil() as usize).min(buffer.len());
+        for (bin, atomic) in self.bins[channel].iter().enumerate() {
+            let (start, end) = (bin * len / LOOKAHEAD_BINS, (bin + 1) * len / LOOKAHEAD_BINS);
+            let db = buffer
+                .range(start..end)
+                .map(|sample| sample.db)
+                .fold(-100.0, f32::max);
+            atomic.store(db, Ordering::Relaxed);
         }
-        CurrentPeakSingleMut {
-            db: self.db.get_mut(channel).unwrap(),
-            position: self.position.get_mut(channel).unwrap(),
-            lerp_len: self.lerp_len.get_mut(channel).unwrap(),
+
+        // the peak's position counts backwards from the newest sample
+        let tracked = if *peak.db > 0.0 && *peak.position <= *peak.lerp_len && len > 0 {
+            (1.0 - *peak.position / len as f32).clamp(0.0, 1.0)
+        } else {
+            -1.0
+        };
+        self.tracked_peak[channel].store(tracked, Ordering::Relaxed);
+    }
+}
+
+/// How far along the attack and release curves each channel's envelope
+/// currently is, negative when that part of the envelope isn't running.
+pub(crate) struct EnvelopeProgress {
+    pub attack: [AtomicF32; 2],
+    pub release: [AtomicF32; 2],
+}
+
+impl Default for EnvelopeProgress {
+    fn default() -> Self {
+        Self {
+            attack: std::array::from_fn(|_| AtomicF32::new(-1.0)),
+            release: std::array::from_fn(|_| AtomicF32::new(-1.0)),
         }
     }
 }
 
-impl<'a> CurrentPeakSingleMut<'a> {
-    fn read(&mut self, ease: impl Ease) -> Option<f32> {
-        *self.position += 1.0;
-        let progress = (*self.position + 1.0) / (*self.lerp_len + 1.0);
-        if progress > 1.0 {
-            *self.position -= 1.0;
-            return None;
+impl EnvelopeProgress {
+    fn store(&self, channel: usize, peak: &CurrentPeakSingleMut, state: &EnvState, release: f32) {
+        // same progress `CurrentPeakSingleMut::read` feeds the attack curve
+        let attack = if *peak.db > 0.0 && *peak.position <= *peak.lerp_len {
+            ((*peak.position + 1.0) / (*peak.lerp_len + 1.0)).clamp(0.0, 1.0)
+        } else {
+            -1.0
+        };
+        let release = match state {
+            EnvState::Release(elapsed) => (elapsed / (release + 1.0)).clamp(0.0, 1.0),
+            _ => -1.0,
+        };
+
+        self.attack[channel].store(attack, Ordering::Relaxed);
+        self.release[channel].store(release, Ordering::Relaxed);
+    }
+}
+
+pub(crate) const TRIGGER_POINTS: usize = 256;
+// how much of the triggered scope comes before and after the sample that fired it
+pub(crate) const TRIGGER_PRE_MS: f32 = 2.0;
+pub(crate) const TRIGGER_POST_MS: f32 = 8.0;
+
+/// The last capture of the triggered scope, the delayed input and the output
+/// of both channels around a sample that went over the ceiling. `captures`
+/// counts up once a capture has been written out completely.
+pub(crate) struct TriggerSnapshot {
+    pub input: [[AtomicF32; TRIGGER_POINTS]; 2],
+    pub output: [[AtomicF32; TRIGGER_POINTS]; 2],
+    pub captures: AtomicU32,
+}
+
+impl Default for TriggerSnapshot {
+    fn default() -> Self {
+        Self {
+            input: std::array::from_fn(|_| std::array::from_fn(|_| AtomicF32::new(0.0))),
+            output: std::array::from_fn(|_| std::array::from_fn(|_| AtomicF32::new(0.0))),
+            captures: AtomicU32::new(0),
         }
-        Some(calc_atk_reduction(*self.db, ease.process(progress)))
     }
 }
 
-struct Limiter<'a> {
-    buffer: &'a mut VecDeque<SampleDB>,
-    state: &'a mut EnvState,
-    target: &'a mut f32,
-    hold: &'a mut f32,
-    envelope: &'a mut f32,
-    current_peak: CurrentPeakSingleMut<'a>,
+// audio thread side of the triggered scope. keeps the last few ms around until
+// a peak comes along, then records until the window is full and hands it over
+struct TriggerCapture {
+    // input and output of each channel, oldest first
+    samples: VecDeque<[[f32; 2]; 2]>,
+    pre_len: usize,
+    post_len: usize,
+    remaining: Option<usize>,
 }
 
-impl LimiterBuffer {
-    fn new(channels: usize, sample_len: usize) -> Self {
-        let mut rv = LimiterBuffer {
-            channels,
-            buffers: vec![VecDeque::with_capacity(sample_len); channels],
-            state: vec![EnvState::Off; channels],
-            target: vec![0.0; channels],
-            hold: vec![0.0; channels],
-            envelope: vec![0.0; channels],
-            current_peaks: CurrentPeaks {
-                db: vec![0.0; channels],
-                position: vec![2.0; channels],
-                lerp_len: vec![1.0; channels],
-            },
-        };
+impl TriggerCapture {
+    fn new(sample_rate: f32) -> Self {
+        let pre_len = (TRIGGER_PRE_MS * 0.001 * sample_rate).ceil() as usize;
+        let post_len = (TRIGGER_POST_MS * 0.001 * sample_rate).ceil() as usize;
+        Self {
+            samples: VecDeque::with_capacity(pre_len + post_len + 1),
+            pre_len,
+            post_len,
+            remaining: None,
+        }
+    }
 
-        for b in rv.buffers.iter_mut() {
-            for _ in 0..sample_len {
-                b.push_back(SampleDB {
-                    sample: 0.0,
-                    db: -100.0,
-                });
+    fn push(&mut self, frame: [[f32; 2]; 2], peak: bool, snapshot: &TriggerSnapshot) {
+        self.samples.push_back(frame);
+
+        match self.remaining {
+            Some(remaining) if remaining > 1 => self.remaining = Some(remaining - 1),
+            Some(_) => {
+                self.publish(snapshot);
+                self.remaining = None;
+                // whatever's left over is the next capture's lead-in
+                while self.samples.len() > self.pre_len {
+                    self.samples.pop_front();
+                }
+            }
+            None if peak => self.remaining = Some(self.post_len.max(1)),
+            None => {
+                while self.samples.len() > self.pre_len {
+                    self.samples.pop_front();
+                }
             }
         }
+    }
 
-        rv
+    fn publish(&self, snapshot: &TriggerSnapshot) {
+        let len = self.samples.len();
+        for point in 0..TRIGGER_POINTS {
+            let [left, right] = self.samples[point * len / TRIGGER_POINTS];
+            for (channel, [input, output]) in [left, right].into_iter().enumerate() {
+                snapshot.input[channel][point].store(input, Ordering::Relaxed);
+                snapshot.output[channel][point].store(output, Ordering::Relaxed);
+            }
+        }
+        snapshot.captures.fetch_add(1, Ordering::Release);
     }
+}
+
+pub(crate) const DELTA_LEN: usize = 4096;
+
+/// The last `DELTA_LEN` samples of what the limiter took out, the channels
+/// summed, for the editor's spectrum. A ring, `written` counts every sample
+/// that went in so the newest sits just before it.
+pub(crate) struct DeltaSnapshot {
+    pub samples: [AtomicF32; DELTA_LEN],
+    pub written: AtomicU32,
+}
 
-    fn get_mut(&'_ mut self, channel: usize) -> Limiter<'_> {
-        let channel = channel.clamp(0, self.channels - 1);
-        Limiter {
-            buffer: self.buffers.get_mut(channel).unwrap(),
-            state: self.state.get_mut(channel).unwrap(),
-            target: self.target.get_mut(channel).unwrap(),
-            hold: self.hold.get_mut(channel).unwrap(),
-            envelope: self.envelope.get_mut(channel).unwrap(),
-            current_peak: self.current_peaks.get_mut(channel),
+impl Default for DeltaSnapshot {
+    fn default() -> Self {
+        Self {
+            samples: std::array::from_fn(|_| AtomicF32::new(0.0)),
+            written: AtomicU32::new(0),
         }
     }
 }
 
+impl DeltaSnapshot {
+    // only ever called from the audio thread
+    fn push(&self, sample: f32) {
+        let written = self.written.load(Ordering::Relaxed);
+        self.samples[written as usize % DELTA_LEN].store(sample, Ordering::Relaxed);
+        self.written
+            .store(written.wrapping_add(1), Ordering::Release);
+    }
+}
+
 impl Default for Limit2zeroParams {
     fn default() -> Self {
+        let defaults = UserDefaults::get();
         Self {
             editor_state: editor::default_state(),
+            theme: Arc::new(RwLock::new(defaults.theme.unwrap_or_default())),
+            language: Arc::new(RwLock::new(defaults.language.unwrap_or_default())),
+            gr_view: Arc::new(RwLock::new(defaults.gr_view.unwrap_or_default())),
+            low_power: Arc::new(RwLock::new(defaults.low_power.unwrap_or(false))),
+            locked_params: Arc::new(RwLock::new(HashSet::new())),
+            midi_map: Arc::new(RwLock::new(MidiMap::default())),
+            midi_learn: Arc::new(RwLock::new(None)),
+            midi_learned: Arc::new(AtomicU8::new(NO_CC)),
+            midi_table: Arc::new(RwLock::new(CcTable::default())),
+            macro_map: Arc::new(RwLock::new(MacroMap::default())),
+            macro_table: Arc::new(RwLock::new(MacroTable::default())),
+
+            simple: SimpleParams::default(),
+            input: InputParams::default(),
+            attack: AttackParams::default(),
+            attack_smoothing: AttackSmoothingParams::default(),
+            hold_release: HoldReleaseParams::default(),
+            release: ReleaseParams::default(),
+            output: OutputParams::default(),
+            macros: MacroParams::default(),
+            test_tone: TestToneParams::default(),
+
+            bypass: BoolParam::new("Bypass", false).make_bypass(),
+        }
+    }
+}
 
-            drive: FloatParam::new(
-                "Drive",
-                util::db_to_gain(0.0),
-                FloatRange::Skewed {
-                    min: util::db_to_gain(0.0),
-                    max: util::db_to_gain(60.0),
-                    factor: FloatRange::gain_skew_factor(0.0, 60.0),
-                },
-            )
-            .with_smoother(SmoothingStyle::Logarithmic(50.0))
-            .with_unit("dB")
-            .with_value_to_string(formatters::v2s_f32_gain_to_db(2))
-            .with_string_to_value(formatters::s2v_f32_gain_to_db()),
+// `engine::Weighting` as the host sees it, the engine stays free of nih-plug
+#[derive(Enum, Debug, Clone, Copy, PartialEq)]
+enum DetectorWeighting {
+    #[id = "flat"]
+    Flat,
+    #[id = "a"]
+    #[name = "A-weight"]
+    A,
+    #[id = "k"]
+    #[name = "K-weight"]
+    K,
+}
 
-            trim: FloatParam::new(
-                "Trim",
-                0.0,
-                FloatRange::Linear {
-                    min: -1.0,
-                    max: 0.0,
-                },
-            )
-            .with_unit("db")
-            .with_value_to_string(formatters::v2s_f32_rounded(2)),
+impl From<DetectorWeighting> for Weighting {
+    fn from(weighting: DetectorWeighting) -> Self {
+        match weighting {
+            DetectorWeighting::Flat => Weighting::Flat,
+            DetectorWeighting::A => Weighting::A,
+            DetectorWeighting::K => Weighting::K,
+        }
+    }
+}
+
+// same for `engine::Algorithm`
+#[derive(Enum, Debug, Clone, Copy, PartialEq)]
+enum GainAlgorithm {
+    #[id = "envelope"]
+    Envelope,
+    #[id = "moving_min"]
+    #[name = "Moving Min"]
+    MovingMin,
+}
+
+impl From<GainAlgorithm> for Algorithm {
+    fn from(algorithm: GainAlgorithm) -> Self {
+        match algorithm {
+            GainAlgorithm::Envelope => Algorithm::Envelope,
+            GainAlgorithm::MovingMin => Algorithm::MovingMin,
+        }
+    }
+}
+
+#[derive(Enum, Debug, Clone, Copy, PartialEq)]
+enum ToneSignal {
+    #[id = "sine"]
+    Sine,
+    #[id = "pink_noise"]
+    #[name = "Pink Noise"]
+    PinkNoise,
+}
+
+// one of the detector EQ's three gains
+fn detector_eq_param(name: &str, id: &str) -> FloatParam {
+    FloatParam::new(
+        name,
+        user_default(id).unwrap_or(0.0),
+        FloatRange::Linear {
+            min: -12.0,
+            max: 12.0,
+        },
+    )
+    .with_unit("dB")
+    .with_value_to_string(formatters::v2s_f32_rounded(1))
+}
+
+// the top of the balance guard's range, where it's off
+const BALANCE_GUARD_OFF: f32 = 12.0;
+// same for the GR slew limit, in dB/ms
+const GR_SLEW_OFF: f32 = 20.0;
+
+#[derive(Params)]
+struct InputParams {
+    #[id = "drive"]
+    pub drive: FloatParam,
+
+    #[id = "lookahead"]
+    pub lookahead: FloatParam,
+
+    #[id = "lookahead_accuracy"]
+    pub lookahead_accuracy: IntParam,
+
+    #[id = "lookahead_glide"]
+    pub lookahead_glide: BoolParam,
+
+    #[id = "lookahead_offset"]
+    pub lookahead_offset: FloatParam,
+
+    #[id = "live"]
+    pub live: BoolParam,
+
+    #[id = "dc_block"]
+    pub dc_block: BoolParam,
+
+    #[id = "algorithm"]
+    pub algorithm: EnumParam<GainAlgorithm>,
+
+    #[id = "detector_weighting"]
+    pub detector_weighting: EnumParam<DetectorWeighting>,
+
+    #[id = "detector_tilt"]
+    pub detector_tilt: FloatParam,
+
+    #[id = "detector_low"]
+    pub detector_low: FloatParam,
+
+    #[id = "detector_mid"]
+    pub detector_mid: FloatParam,
+
+    #[id = "detector_mid_freq"]
+    pub detector_mid_freq: FloatParam,
+
+    #[id = "detector_high"]
+    pub detector_high: FloatParam,
+
+    #[id = "detector_listen"]
+    pub detector_listen: BoolParam,
+
+    #[id = "leveling"]
+    pub leveling: BoolParam,
+
+    #[id = "leveling_target"]
+    pub leveling_target: FloatParam,
+
+    #[id = "leveling_range"]
+    pub leveling_range: FloatParam,
+
+    #[id = "stereo_link"]
+    pub stereo_link: FloatParam,
+
+    #[id = "balance_guard"]
+    pub balance_guard: FloatParam,
+
+    #[id = "reset_on_play"]
+    pub reset_on_play: BoolParam,
+
+    #[id = "automation_smoothing"]
+    pub automation_smoothing: FloatParam,
+}
+
+impl Default for InputParams {
+    fn default() -> Self {
+        Self {
+            drive: drive_param(user_default("drive").unwrap_or(util::db_to_gain(0.0))),
 
             lookahead: FloatParam::new(
                 "Lookahead",
-                0.0,
+                user_default("lookahead").unwrap_or(0.0),
                 FloatRange::Skewed {
                     min: 0.0,
                     max: 50.0,
                     factor: 0.5,
                 },
             )
+            // only followed while it glides, otherwise every change resets
+            .with_smoother(SmoothingStyle::Linear(50.0))
             .with_value_to_string(Arc::new(move |value| {
                 if value < 1.01 {
                     format!("{} samples", (value * 48.0).ceil() as usize)
@@ -315,7 +622,7 @@ impl Default for Limit2zeroParams {
 
             lookahead_accuracy: IntParam::new(
                 "Lookahead Accuracy",
-                1,
+                user_default("lookahead_accuracy").map_or(1, |value| value.round() as i32),
                 IntRange::Linear { min: 1, max: 16 },
             )
             .with_value_to_string(Arc::new(move |value| match value {
@@ -324,9 +631,192 @@ impl Default for Limit2zeroParams {
                 _ => format!("every {} samples", value),
             })),
 
+            lookahead_glide: BoolParam::new(
+                "Glide Lookahead",
+                user_default("lookahead_glide").map_or(false, |value| value >= 0.5),
+            ),
+
+            // positive looks further ahead on the right
+            lookahead_offset: FloatParam::new(
+                "Lookahead Offset",
+                user_default("lookahead_offset").unwrap_or(0.0),
+                FloatRange::Linear {
+                    min: -5.0,
+                    max: 5.0,
+                },
+            )
+            .with_value_to_string(Arc::new(move |value| format!("{:+.2}ms", value))),
+
+            live: BoolParam::new(
+                "Live",
+                user_default("live").map_or(false, |value| value >= 0.5),
+            ),
+
+            dc_block: BoolParam::new(
+                "DC Block",
+                user_default("dc_block").map_or(false, |value| value >= 0.5),
+            ),
+
+            algorithm: EnumParam::new(
+                "Algorithm",
+                user_default("algorithm").map_or(GainAlgorithm::Envelope, |value| {
+                    GainAlgorithm::from_index(value.round() as usize)
+                }),
+            ),
+
+            detector_weighting: EnumParam::new(
+                "Detector Weighting",
+                user_default("detector_weighting").map_or(DetectorWeighting::Flat, |value| {
+                    DetectorWeighting::from_index(value.round() as usize)
+                }),
+            ),
+
+            detector_tilt: FloatParam::new(
+                "Detector Tilt",
+                user_default("detector_tilt").unwrap_or(0.0),
+                FloatRange::Linear {
+                    min: -6.0,
+                    max: 6.0,
+                },
+            )
+            .with_unit("dB")
+            .with_value_to_string(formatters::v2s_f32_rounded(1)),
+
+            detector_low: detector_eq_param("Detector Low", "detector_low"),
+            detector_mid: detector_eq_param("Detector Mid", "detector_mid"),
+
+            detector_mid_freq: FloatParam::new(
+                "Detector Mid Freq",
+                user_default("detector_mid_freq").unwrap_or(1000.0),
+                FloatRange::Skewed {
+                    min: 200.0,
+                    max: 8000.0,
+                    factor: FloatRange::skew_factor(-2.0),
+                },
+            )
+            .with_value_to_string(formatters::v2s_f32_hz_then_khz(1))
+            .with_string_to_value(formatters::s2v_f32_hz_then_khz()),
+
+            detector_high: detector_eq_param("Detector High", "detector_high"),
+
+            detector_listen: BoolParam::new(
+                "Detector Listen",
+                user_default("detector_listen").map_or(false, |value| value >= 0.5),
+            ),
+
+            leveling: BoolParam::new(
+                "Leveling",
+                user_default("leveling").map_or(false, |value| value >= 0.5),
+            ),
+
+            // an RMS level, not a peak one
+            leveling_target: FloatParam::new(
+                "Leveling Target",
+                user_default("leveling_target").unwrap_or(-18.0),
+                FloatRange::Linear {
+                    min: -36.0,
+                    max: 0.0,
+                },
+            )
+            .with_unit("dB")
+            .with_value_to_string(formatters::v2s_f32_rounded(1)),
+
+            leveling_range: FloatParam::new(
+                "Leveling Range",
+                user_default("leveling_range").unwrap_or(6.0),
+                FloatRange::Linear {
+                    min: 0.0,
+                    max: 12.0,
+                },
+            )
+            .with_unit("dB")
+            .with_value_to_string(formatters::v2s_f32_rounded(1)),
+
+            stereo_link: FloatParam::new(
+                "Stereo Link",
+                user_default("stereo_link").unwrap_or(0.0),
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_unit("%")
+            .with_value_to_string(formatters::v2s_f32_percentage(0)),
+
+            // all the way up is off
+            balance_guard: FloatParam::new(
+                "Balance Guard",
+                user_default("balance_guard").unwrap_or(BALANCE_GUARD_OFF),
+                FloatRange::Linear {
+                    min: 0.0,
+                    max: BALANCE_GUARD_OFF,
+                },
+            )
+            .with_value_to_string(Arc::new(move |value| {
+                if value >= BALANCE_GUARD_OFF {
+                    "off".to_string()
+                } else {
+                    format!("{:.1}dB", value)
+                }
+            })),
+
+            reset_on_play: BoolParam::new(
+                "Reset On Play",
+                user_default("reset_on_play").map_or(false, |value| value >= 0.5),
+            ),
+
+            // how long drive, the gains, stereo link and the curves take to
+            // follow automation, see `SettingsSmoother`
+            automation_smoothing: FloatParam::new(
+                "Automation Smoothing",
+                user_default("automation_smoothing").unwrap_or(50.0),
+                FloatRange::Skewed {
+                    min: 0.0,
+                    max: 500.0,
+                    factor: 0.5,
+                },
+            )
+            .with_value_to_string(Arc::new(move |value| format!("{:.0}ms", value))),
+        }
+    }
+}
+
+#[derive(Params)]
+struct AttackParams {
+    #[id = "attack_amt"]
+    pub attack_amt: FloatParam,
+
+    #[id = "attack_bite"]
+    pub attack_bite: FloatParam,
+
+    #[id = "atk_env_linearity"]
+    pub atk_env_linearity: FloatParam,
+
+    #[id = "atk_env_s_center"]
+    pub atk_env_center: FloatParam,
+
+    #[id = "atk_env_polarity_in"]
+    pub atk_env_polarity_in: FloatParam,
+
+    #[id = "atk_env_polarity_out"]
+    pub atk_env_polarity_out: FloatParam,
+
+    #[id = "atk_env_power_in"]
+    pub atk_env_power_in: FloatParam,
+
+    #[id = "atk_env_power_out"]
+    pub atk_env_power_out: FloatParam,
+
+    #[id = "atk_morph"]
+    pub atk_morph: FloatParam,
+
+    #[id = "atk_morph_on"]
+    pub atk_morph_on: BoolParam,
+}
+
+impl Default for AttackParams {
+    fn default() -> Self {
+        Self {
             attack_amt: FloatParam::new(
                 "Attack Amount",
-                1.0,
+                user_default("attack_amt").unwrap_or(1.0),
                 FloatRange::Linear { min: 0.0, max: 5.0 },
             )
             .with_value_to_string(Arc::new(move |value| {
@@ -349,155 +839,157 @@ impl Default for Limit2zeroParams {
                 }
             })),
 
+            attack_bite: FloatParam::new(
+                "Attack Bite",
+                user_default("attack_bite").unwrap_or(0.0),
+                FloatRange::Linear { min: 0.0, max: 0.5 },
+            )
+            .with_unit("%")
+            .with_value_to_string(formatters::v2s_f32_percentage(0)),
+
             atk_env_linearity: FloatParam::new(
                 "Attack Linearity",
-                1.0,
+                user_default("atk_env_linearity").unwrap_or(1.0),
                 FloatRange::Linear { min: 0.0, max: 1.0 },
             )
             .with_unit("%")
             .with_value_to_string(formatters::v2s_f32_percentage(0)),
 
+            atk_env_center: FloatParam::new(
+                "Atk S Center",
+                user_default("atk_env_s_center").unwrap_or(0.5),
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            ),
+
             atk_env_polarity_in: FloatParam::new(
                 "Attack Polarity In",
-                1.0,
+                user_default("atk_env_polarity_in").unwrap_or(1.0),
                 FloatRange::Linear { min: 0.0, max: 1.0 },
             ),
+
             atk_env_polarity_out: FloatParam::new(
                 "Attack Polarity Out",
-                1.0,
+                user_default("atk_env_polarity_out").unwrap_or(1.0),
                 FloatRange::Linear { min: 0.0, max: 1.0 },
             ),
 
-            atk_env_power_in: FloatParam::new(
+            atk_env_power_in: power_param(
                 "Attack Power In",
-                2.0,
-                FloatRange::Skewed {
-                    min: 16_f32.recip(),
-                    max: 16.0,
-                    factor: 0.25,
-                },
-            )
-            .with_value_to_string(Arc::new(move |value| {
-                let one_over_value = value.recip();
-                if one_over_value.round() > 1.0 {
-                    if one_over_value >= 10.0 {
-                        format!("1/{:.0}", one_over_value)
-                    } else {
-                        format!("1/{:.1}", one_over_value)
-                    }
-                } else {
-                    if value >= 10.0 {
-                        format!("{:.0}", value)
-                    } else {
-                        format!("{:.1}", value)
-                    }
-                }
-            })),
+                user_default("atk_env_power_in").unwrap_or(2.0),
+            ),
 
-            atk_env_power_out: FloatParam::new(
+            atk_env_power_out: power_param(
                 "Attack Power Out",
-                2.0,
-                FloatRange::Skewed {
-                    min: 16_f32.recip(),
-                    max: 16.0,
-                    factor: 0.25,
-                },
+                user_default("atk_env_power_out").unwrap_or(2.0),
+            ),
+
+            // stands in for all the curve params, smoothing included, while
+            // it's on
+            atk_morph: FloatParam::new(
+                "Attack Morph",
+                user_default("atk_morph").unwrap_or(0.5),
+                FloatRange::Linear { min: 0.0, max: 1.0 },
             )
             .with_value_to_string(Arc::new(move |value| {
-                let one_over_value = value.recip();
-                if one_over_value.round() > 1.0 {
-                    if one_over_value >= 10.0 {
-                        format!("1/{:.0}", one_over_value)
-                    } else {
-                        format!("1/{:.1}", one_over_value)
-                    }
-                } else {
-                    if value >= 10.0 {
-                        format!("{:.0}", value)
-                    } else {
-                        format!("{:.1}", value)
-                    }
-                }
+                EnvelopeShape::morph_name(&ATTACK_MORPHS, value).to_string()
             })),
 
-            atk_env_center: FloatParam::new(
-                "Atk S Center",
-                0.5,
-                FloatRange::Linear { min: 0.0, max: 1.0 },
+            atk_morph_on: BoolParam::new(
+                "Use Attack Morph",
+                user_default("atk_morph_on").map_or(false, |value| value >= 0.5),
             ),
+        }
+    }
+}
 
+#[derive(Params)]
+struct AttackSmoothingParams {
+    #[id = "atk_smooth_amt"]
+    pub atk_smooth_amt: FloatParam,
+
+    #[id = "atk_env_smooth_polarity_in"]
+    pub atk_env_sm_polarity_in: FloatParam,
+
+    #[id = "atk_env_smooth_polarity_out"]
+    pub atk_env_sm_polarity_out: FloatParam,
+
+    #[id = "atk_env_smooth_power_in"]
+    pub atk_env_sm_power_in: FloatParam,
+
+    #[id = "atk_env_smooth_power_out"]
+    pub atk_env_sm_power_out: FloatParam,
+}
+
+impl Default for AttackSmoothingParams {
+    fn default() -> Self {
+        Self {
             atk_smooth_amt: FloatParam::new(
                 "Attack Smooth Amount",
-                0.0,
+                user_default("atk_smooth_amt").unwrap_or(0.0),
                 FloatRange::Linear { min: 0.0, max: 1.0 },
             ),
 
             atk_env_sm_polarity_in: FloatParam::new(
                 "Attack Smooth Polarity In",
-                1.0,
+                user_default("atk_env_smooth_polarity_in").unwrap_or(1.0),
                 FloatRange::Linear { min: 0.0, max: 1.0 },
             ),
+
             atk_env_sm_polarity_out: FloatParam::new(
                 "Attack Smooth Polarity Out",
-                1.0,
+                user_default("atk_env_smooth_polarity_out").unwrap_or(1.0),
                 FloatRange::Linear { min: 0.0, max: 1.0 },
             ),
 
-            atk_env_sm_power_in: FloatParam::new(
+            atk_env_sm_power_in: power_param(
                 "Attack Smooth Power In",
-                2.0,
-                FloatRange::Skewed {
-                    min: 16_f32.recip(),
-                    max: 16.0,
-                    factor: 0.25,
-                },
-            )
-            .with_value_to_string(Arc::new(move |value| {
-                let one_over_value = value.recip();
-                if one_over_value.round() > 1.0 {
-                    if one_over_value >= 10.0 {
-                        format!("1/{:.0}", one_over_value)
-                    } else {
-                        format!("1/{:.1}", one_over_value)
-                    }
-                } else {
-                    if value >= 10.0 {
-                        format!("{:.0}", value)
-                    } else {
-                        format!("{:.1}", value)
-                    }
-                }
-            })),
+                user_default("atk_env_smooth_power_in").unwrap_or(2.0),
+            ),
 
-            atk_env_sm_power_out: FloatParam::new(
+            atk_env_sm_power_out: power_param(
                 "Attack Smooth Power Out",
-                2.0,
-                FloatRange::Skewed {
-                    min: 16_f32.recip(),
-                    max: 16.0,
-                    factor: 0.25,
-                },
-            )
-            .with_value_to_string(Arc::new(move |value| {
-                let one_over_value = value.recip();
-                if one_over_value.round() > 1.0 {
-                    if one_over_value >= 10.0 {
-                        format!("1/{:.0}", one_over_value)
-                    } else {
-                        format!("1/{:.1}", one_over_value)
-                    }
-                } else {
-                    if value >= 10.0 {
-                        format!("{:.0}", value)
-                    } else {
-                        format!("{:.1}", value)
-                    }
-                }
-            })),
+                user_default("atk_env_smooth_power_out").unwrap_or(2.0),
+            ),
+        }
+    }
+}
+
+#[derive(Params)]
+struct HoldReleaseParams {
+    #[id = "hold"]
+    pub hold: FloatParam,
+
+    #[id = "release"]
+    pub release: FloatParam,
+
+    #[id = "hold_level"]
+    pub hold_level: FloatParam,
+
+    #[id = "true_hold"]
+    pub true_hold: BoolParam,
+
+    #[id = "release_start"]
+    pub release_start: FloatParam,
+
+    #[id = "release_scaling"]
+    pub release_scaling: BoolParam,
 
+    #[id = "gr_slew"]
+    pub gr_slew: FloatParam,
+
+    #[id = "auto_release"]
+    pub auto_release: BoolParam,
+
+    #[id = "freeze"]
+    pub freeze: BoolParam,
+}
+
+impl Default for HoldReleaseParams {
+    fn default() -> Self {
+        Self {
             hold: FloatParam::new(
                 "Hold",
-                0.0,
+                user_default("hold").unwrap_or(0.0),
                 FloatRange::Skewed {
                     min: 0.0,
                     max: 1000.,
@@ -519,17 +1011,9 @@ impl Default for Limit2zeroParams {
                 }
             })),
 
-            release_amt: FloatParam::new(
-                "Hold Amount",
-                1.0,
-                FloatRange::Linear { min: 0.0, max: 1.0 },
-            )
-            .with_unit("%")
-            .with_value_to_string(formatters::v2s_f32_percentage(0)),
-
             release: FloatParam::new(
                 "Release",
-                0.0,
+                user_default("release").unwrap_or(0.0),
                 FloatRange::Skewed {
                     min: 0.0,
                     max: 3000.,
@@ -551,261 +1035,654 @@ impl Default for Limit2zeroParams {
                 }
             })),
 
-            rel_env_linearity: FloatParam::new(
-                "Release Linearity",
-                1.0,
+            // both are shares of the reduction the attack went down to
+            hold_level: FloatParam::new(
+                "Hold Level",
+                user_default("hold_level").unwrap_or(1.0),
                 FloatRange::Linear { min: 0.0, max: 1.0 },
             )
             .with_unit("%")
             .with_value_to_string(formatters::v2s_f32_percentage(0)),
 
-            rel_env_polarity_in: FloatParam::new(
-                "Release Polarity In",
-                1.0,
-                FloatRange::Linear { min: 0.0, max: 1.0 },
+            true_hold: BoolParam::new(
+                "True Hold",
+                user_default("true_hold").map_or(false, |value| value >= 0.5),
             ),
-            rel_env_polarity_out: FloatParam::new(
-                "Release Polarity Out",
-                1.0,
+
+            release_start: FloatParam::new(
+                "Release Start",
+                user_default("release_start").unwrap_or(1.0),
                 FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_unit("%")
+            .with_value_to_string(formatters::v2s_f32_percentage(0)),
+
+            release_scaling: BoolParam::new(
+                "Scale Release",
+                user_default("release_scaling").map_or(false, |value| value >= 0.5),
             ),
 
-            rel_env_power_in: FloatParam::new(
-                "Release Power In",
-                2.0,
-                FloatRange::Skewed {
-                    min: 16_f32.recip(),
-                    max: 16.0,
-                    factor: 0.25,
-                },
-            )
-            .with_value_to_string(Arc::new(move |value| {
-                let one_over_value = value.recip();
-                if one_over_value.round() > 1.0 {
-                    if one_over_value >= 10.0 {
-                        format!("1/{:.0}", one_over_value)
-                    } else {
-                        format!("1/{:.1}", one_over_value)
-                    }
-                } else {
-                    if value >= 10.0 {
-                        format!("{:.0}", value)
-                    } else {
-                        format!("{:.1}", value)
-                    }
-                }
-            })),
+            auto_release: BoolParam::new(
+                "Auto Release",
+                user_default("auto_release").map_or(false, |value| value >= 0.5),
+            ),
 
-            rel_env_power_out: FloatParam::new(
-                "Release Power Out",
-                2.0,
+            // a momentary control, so it never starts out held
+            freeze: BoolParam::new("Freeze GR", false),
+
+            gr_slew: FloatParam::new(
+                "GR Slew Limit",
+                user_default("gr_slew").unwrap_or(GR_SLEW_OFF),
                 FloatRange::Skewed {
-                    min: 16_f32.recip(),
-                    max: 16.0,
-                    factor: 0.25,
+                    min: 0.05,
+                    max: GR_SLEW_OFF,
+                    factor: 0.3,
                 },
             )
             .with_value_to_string(Arc::new(move |value| {
-                let one_over_value = value.recip();
-                if one_over_value.round() > 1.0 {
-                    if one_over_value >= 10.0 {
-                        format!("1/{:.0}", one_over_value)
-                    } else {
-                        format!("1/{:.1}", one_over_value)
-                    }
+                if value >= GR_SLEW_OFF {
+                    "off".to_string()
                 } else {
-                    if value >= 10.0 {
-                        format!("{:.0}", value)
-                    } else {
-                        format!("{:.1}", value)
-                    }
+                    format!("{:.2}dB/ms", value)
                 }
             })),
+        }
+    }
+}
+
+#[derive(Params)]
+struct ReleaseParams {
+    #[id = "rel_linearity"]
+    pub rel_env_linearity: FloatParam,
+
+    #[id = "rel_env_s_center"]
+    pub rel_env_center: FloatParam,
+
+    #[id = "rel_env_polarity_in"]
+    pub rel_env_polarity_in: FloatParam,
+
+    #[id = "rel_env_polarity_out"]
+    pub rel_env_polarity_out: FloatParam,
+
+    #[id = "rel_env_power_in"]
+    pub rel_env_power_in: FloatParam,
+
+    #[id = "rel_env_power_out"]
+    pub rel_env_power_out: FloatParam,
+
+    #[id = "rel_smooth_amt"]
+    pub rel_smooth_amt: FloatParam,
+
+    #[id = "rel_env_smooth_polarity_in"]
+    pub rel_env_sm_polarity_in: FloatParam,
+
+    #[id = "rel_env_smooth_polarity_out"]
+    pub rel_env_sm_polarity_out: FloatParam,
+
+    #[id = "rel_env_smooth_power_in"]
+    pub rel_env_sm_power_in: FloatParam,
+
+    #[id = "rel_env_smooth_power_out"]
+    pub rel_env_sm_power_out: FloatParam,
+
+    #[id = "rel_morph"]
+    pub rel_morph: FloatParam,
+
+    #[id = "rel_morph_on"]
+    pub rel_morph_on: BoolParam,
+}
+
+impl Default for ReleaseParams {
+    fn default() -> Self {
+        Self {
+            rel_env_linearity: FloatParam::new(
+                "Release Linearity",
+                user_default("rel_linearity").unwrap_or(1.0),
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_unit("%")
+            .with_value_to_string(formatters::v2s_f32_percentage(0)),
 
             rel_env_center: FloatParam::new(
                 "rel S Center",
-                0.5,
+                user_default("rel_env_s_center").unwrap_or(0.5),
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            ),
+
+            rel_env_polarity_in: FloatParam::new(
+                "Release Polarity In",
+                user_default("rel_env_polarity_in").unwrap_or(1.0),
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            ),
+
+            rel_env_polarity_out: FloatParam::new(
+                "Release Polarity Out",
+                user_default("rel_env_polarity_out").unwrap_or(1.0),
                 FloatRange::Linear { min: 0.0, max: 1.0 },
             ),
 
+            rel_env_power_in: power_param(
+                "Release Power In",
+                user_default("rel_env_power_in").unwrap_or(2.0),
+            ),
+
+            rel_env_power_out: power_param(
+                "Release Power Out",
+                user_default("rel_env_power_out").unwrap_or(2.0),
+            ),
+
             rel_smooth_amt: FloatParam::new(
                 "Release Smooth Amount",
-                0.0,
+                user_default("rel_smooth_amt").unwrap_or(0.0),
                 FloatRange::Linear { min: 0.0, max: 1.0 },
             ),
 
             rel_env_sm_polarity_in: FloatParam::new(
                 "Release Polarity In",
-                1.0,
+                user_default("rel_env_smooth_polarity_in").unwrap_or(1.0),
                 FloatRange::Linear { min: 0.0, max: 1.0 },
             ),
+
             rel_env_sm_polarity_out: FloatParam::new(
                 "Release Polarity Out",
-                1.0,
+                user_default("rel_env_smooth_polarity_out").unwrap_or(1.0),
                 FloatRange::Linear { min: 0.0, max: 1.0 },
             ),
 
-            rel_env_sm_power_in: FloatParam::new(
+            rel_env_sm_power_in: power_param(
                 "Release Smooth Power In",
-                2.0,
-                FloatRange::Skewed {
-                    min: 16_f32.recip(),
-                    max: 16.0,
-                    factor: 0.25,
-                },
-            )
-            .with_value_to_string(Arc::new(move |value| {
-                let one_over_value = value.recip();
-                if one_over_value.round() > 1.0 {
-                    if one_over_value >= 10.0 {
-                        format!("1/{:.0}", one_over_value)
-                    } else {
-                        format!("1/{:.1}", one_over_value)
-                    }
-                } else {
-                    if value >= 10.0 {
-                        format!("{:.0}", value)
-                    } else {
-                        format!("{:.1}", value)
-                    }
-                }
-            })),
+                user_default("rel_env_smooth_power_in").unwrap_or(2.0),
+            ),
 
-            rel_env_sm_power_out: FloatParam::new(
+            rel_env_sm_power_out: power_param(
                 "Release Smooth Power Out",
-                2.0,
-                FloatRange::Skewed {
-                    min: 16_f32.recip(),
-                    max: 16.0,
-                    factor: 0.25,
-                },
+                user_default("rel_env_smooth_power_out").unwrap_or(2.0),
+            ),
+
+            // stands in for all the curve params above while it's on
+            rel_morph: FloatParam::new(
+                "Release Morph",
+                user_default("rel_morph").unwrap_or(0.0),
+                FloatRange::Linear { min: 0.0, max: 1.0 },
             )
             .with_value_to_string(Arc::new(move |value| {
-                let one_over_value = value.recip();
-                if one_over_value.round() > 1.0 {
-                    if one_over_value >= 10.0 {
-                        format!("1/{:.0}", one_over_value)
-                    } else {
-                        format!("1/{:.1}", one_over_value)
-                    }
-                } else {
-                    if value >= 10.0 {
-                        format!("{:.0}", value)
-                    } else {
-                        format!("{:.1}", value)
-                    }
-                }
+                EnvelopeShape::morph_name(&RELEASE_MORPHS, value).to_string()
             })),
 
-            stereo_link: FloatParam::new(
-                "Stereo Link",
-                0.0,
-                FloatRange::Linear { min: 0.0, max: 1.0 },
+            rel_morph_on: BoolParam::new(
+                "Use Release Morph",
+                user_default("rel_morph_on").map_or(false, |value| value >= 0.5),
+            ),
+        }
+    }
+}
+
+#[derive(Params)]
+struct OutputParams {
+    #[id = "trim"]
+    pub trim: FloatParam,
+
+    #[id = "output_gain"]
+    pub output_gain: FloatParam,
+
+    #[id = "compensate"]
+    pub compensate: BoolParam,
+
+    #[id = "compensate_loudness"]
+    pub compensate_loudness: BoolParam,
+
+    /// For comparing only: the output follows the input's loudness, up or
+    /// down, in place of trim, compensation and output gain.
+    #[id = "gain_match"]
+    pub gain_match: BoolParam,
+
+    #[id = "true_peak"]
+    pub true_peak: BoolParam,
+
+    #[id = "true_peak_catcher"]
+    pub true_peak_catcher: BoolParam,
+
+    #[id = "inverse"]
+    pub inverse: BoolParam,
+
+    #[id = "width"]
+    pub width: FloatParam,
+
+    #[id = "gr_midi_out"]
+    pub gr_midi_out: BoolParam,
+
+    #[id = "gr_midi_cc"]
+    pub gr_midi_cc: IntParam,
+}
+
+impl Default for OutputParams {
+    fn default() -> Self {
+        Self {
+            trim: trim_param(user_default("trim").unwrap_or(0.0)),
+
+            output_gain: output_gain_param(
+                user_default("output_gain").unwrap_or(util::db_to_gain(0.0)),
+            ),
+
+            compensate: BoolParam::new(
+                "Gain Compensation",
+                user_default("compensate").map_or(false, |value| value >= 0.5),
+            ),
+
+            compensate_loudness: BoolParam::new(
+                "Match Loudness",
+                user_default("compensate_loudness").map_or(false, |value| value >= 0.5),
+            ),
+
+            gain_match: BoolParam::new("Gain Match", false),
+
+            true_peak: BoolParam::new(
+                "True Peak",
+                user_default("true_peak").map_or(false, |value| value >= 0.5),
+            ),
+
+            true_peak_catcher: BoolParam::new(
+                "True Peak Catcher",
+                user_default("true_peak_catcher").map_or(false, |value| value >= 0.5),
+            ),
+
+            inverse: BoolParam::new(
+                "Inverse",
+                user_default("inverse").map_or(false, |value| value >= 0.5),
+            ),
+
+            width: FloatParam::new(
+                "Width",
+                user_default("width").unwrap_or(1.0),
+                FloatRange::Linear { min: 0.0, max: 2.0 },
             )
             .with_unit("%")
-            .with_value_to_string(formatters::v2s_f32_percentage(0)),
+            .with_value_to_string(formatters::v2s_f32_percentage(0))
+            .with_string_to_value(formatters::s2v_f32_percentage()),
 
-            compensate: BoolParam::new("Gain Compensation", false),
+            gr_midi_out: BoolParam::new(
+                "GR to MIDI",
+                user_default("gr_midi_out").map_or(false, |value| value >= 0.5),
+            ),
+
+            // 20 is the first of the CCs the MIDI spec leaves undefined
+            gr_midi_cc: IntParam::new(
+                "GR MIDI CC",
+                user_default("gr_midi_cc").map_or(20, |value| value.round() as i32),
+                IntRange::Linear { min: 0, max: 119 },
+            ),
         }
     }
 }
 
-fn build_envelope(
-    linearity: f32,
-    center: f32,
-    smooth_amount: f32,
-    pol_i: f32,
-    pol_o: f32,
-    pow_i: f32,
-    pow_o: f32,
-    sm_pol_i: f32,
-    sm_pol_o: f32,
-    sm_pow_i: f32,
-    sm_pow_o: f32,
-) -> LinearBlend<SCurve<SCurve<Linear>>> {
-    let linear_smoothing_factor = (1.0 - smooth_amount) * linearity.powi(2);
-    LinearBlend::new(
-        SCurve::new(
-            EaseIn::new(pol_i, pow_i),
-            EaseOut::new(pol_o, pow_o),
-            center,
-            smooth_amount + linear_smoothing_factor,
-            SCurve::new(
-                EaseIn::new(sm_pol_i, sm_pow_i),
-                EaseOut::new(sm_pol_o, sm_pow_o),
-                0.5,
-                0.25 * linearity.powi(2),
-                Linear,
+/// The four macros of simple mode, see [`engine::Macros`]. Switching off
+/// `advanced` hands control over to them. It starts on, so a new instance
+/// sounds like the detailed params' defaults.
+#[derive(Params)]
+struct SimpleParams {
+    #[id = "simple_attack_shape"]
+    pub attack_shape: FloatParam,
+
+    #[id = "simple_release_shape"]
+    pub release_shape: FloatParam,
+
+    #[id = "simple_speed"]
+    pub speed: FloatParam,
+
+    #[id = "simple_amount"]
+    pub amount: FloatParam,
+
+    #[id = "advanced"]
+    pub advanced: BoolParam,
+}
+
+impl Default for SimpleParams {
+    fn default() -> Self {
+        let defaults = Macros::default();
+        let macro_param = |name: &str, id: &str, default: f32| {
+            FloatParam::new(
+                name,
+                user_default(id).unwrap_or(default),
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_unit("%")
+            .with_value_to_string(formatters::v2s_f32_percentage(0))
+            .with_string_to_value(formatters::s2v_f32_percentage())
+        };
+
+        Self {
+            attack_shape: macro_param("Attack Shape", "simple_attack_shape", defaults.attack_shape),
+            release_shape: macro_param(
+                "Release Shape",
+                "simple_release_shape",
+                defaults.release_shape,
             ),
-        ),
-        linearity,
-    )
+            speed: macro_param("Speed", "simple_speed", defaults.speed),
+            amount: macro_param("Amount", "simple_amount", defaults.amount),
+
+            advanced: BoolParam::new(
+                "Advanced",
+                user_default("advanced").map_or(true, |value| value >= 0.5),
+            ),
+        }
+    }
 }
 
-#[derive(Default, Debug, Clone, Copy)]
-struct EditorMsg {
-    pre: [f32; 2],
-    post: [f32; 2],
-    gr: [f32; 2],
+/// Knobs that drive whatever params are assigned to them, see
+/// [`macro_knobs::MacroMap`]. Turning one does nothing until then.
+#[derive(Params)]
+struct MacroParams {
+    #[id = "macro_1"]
+    pub macro_1: FloatParam,
+
+    #[id = "macro_2"]
+    pub macro_2: FloatParam,
+
+    #[id = "macro_3"]
+    pub macro_3: FloatParam,
+
+    #[id = "macro_4"]
+    pub macro_4: FloatParam,
 }
 
-impl EditorMsg {
-    fn add_pre(&mut self, db: f32, channel: usize) {
-        if channel >= 2 {
-            panic!("outta bounds")
+impl Default for MacroParams {
+    fn default() -> Self {
+        let macro_param = |name: &str, id: &str| {
+            FloatParam::new(
+                name,
+                user_default(id).unwrap_or(0.0),
+                FloatRange::Linear { min: 0.0, max: 1.0 },
+            )
+            .with_unit("%")
+            .with_value_to_string(formatters::v2s_f32_percentage(0))
+            .with_string_to_value(formatters::s2v_f32_percentage())
+        };
+
+        Self {
+            macro_1: macro_param("Macro 1", "macro_1"),
+            macro_2: macro_param("Macro 2", "macro_2"),
+            macro_3: macro_param("Macro 3", "macro_3"),
+            macro_4: macro_param("Macro 4", "macro_4"),
         }
-        self.pre[channel] = self.pre[channel].max(db);
     }
-    fn add_post(&mut self, db: f32, channel: usize) {
-        if channel >= 2 {
-            panic!("outta bounds")
-        }
-        self.post[channel] = self.post[channel].max(db);
+}
+
+impl MacroParams {
+    fn knobs(&self) -> [&FloatParam; MACRO_KNOBS] {
+        [&self.macro_1, &self.macro_2, &self.macro_3, &self.macro_4]
     }
-    fn add_gr(&mut self, db: f32, channel: usize) {
-        if channel >= 2 {
-            panic!("outta bounds")
+}
+
+/// A calibration signal that takes the place of the input while it's on,
+/// going through drive and the limiter like anything else coming in. The
+/// level is the sine's peak, the noise stays within about it.
+#[derive(Params)]
+struct TestToneParams {
+    #[id = "test_tone"]
+    pub enabled: BoolParam,
+
+    #[id = "test_tone_signal"]
+    pub signal: EnumParam<ToneSignal>,
+
+    #[id = "test_tone_freq"]
+    pub frequency: FloatParam,
+
+    #[id = "test_tone_level"]
+    pub level: FloatParam,
+}
+
+impl Default for TestToneParams {
+    fn default() -> Self {
+        Self {
+            // never on when a session loads, it replaces the program
+            enabled: BoolParam::new("Test Tone", false),
+
+            signal: EnumParam::new("Test Tone Signal", ToneSignal::Sine),
+
+            frequency: FloatParam::new(
+                "Test Tone Freq",
+                1000.0,
+                FloatRange::Skewed {
+                    min: 20.0,
+                    max: 20000.0,
+                    factor: FloatRange::skew_factor(-2.0),
+                },
+            )
+            .with_value_to_string(formatters::v2s_f32_hz_then_khz(1))
+            .with_string_to_value(formatters::s2v_f32_hz_then_khz()),
+
+            level: FloatParam::new(
+                "Test Tone Level",
+                -18.0,
+                FloatRange::Linear {
+                    min: -60.0,
+                    max: 0.0,
+                },
+            )
+            .with_unit("dBFS")
+            .with_value_to_string(formatters::v2s_f32_rounded(1)),
         }
-        self.gr[channel] = self.gr[channel].max(db);
     }
 }
 
-impl Plugin for Limit2zero {
-    const NAME: &'static str = "limit2zero";
-    const VENDOR: &'static str = "Adamina Barx";
-    const URL: &'static str = env!("CARGO_PKG_HOMEPAGE");
-    const EMAIL: &'static str = "adaminabarx@gmail.com";
+// the drive and output stages, shared with clip2zero
+pub(crate) fn drive_param(default: f32) -> FloatParam {
+    FloatParam::new(
+        "Drive",
+        default,
+        FloatRange::Skewed {
+            min: util::db_to_gain(0.0),
+            max: util::db_to_gain(60.0),
+            factor: FloatRange::gain_skew_factor(0.0, 60.0),
+        },
+    )
+    .with_smoother(SmoothingStyle::Logarithmic(50.0))
+    .with_unit("dB")
+    .with_value_to_string(formatters::v2s_f32_gain_to_db(2))
+    .with_string_to_value(formatters::s2v_f32_gain_to_db())
+}
+
+pub(crate) fn trim_param(default: f32) -> FloatParam {
+    FloatParam::new(
+        "Trim",
+        default,
+        FloatRange::Linear {
+            min: -1.0,
+            max: 0.0,
+        },
+    )
+    .with_unit("db")
+    .with_value_to_string(formatters::v2s_f32_rounded(2))
+}
+
+pub(crate) fn output_gain_param(default: f32) -> FloatParam {
+    FloatParam::new(
+        "Output Gain",
+        default,
+        FloatRange::Skewed {
+            min: util::db_to_gain(-24.0),
+            max: util::db_to_gain(24.0),
+            factor: FloatRange::gain_skew_factor(-24.0, 24.0),
+        },
+    )
+    .with_smoother(SmoothingStyle::Logarithmic(50.0))
+    .with_unit("dB")
+    .with_value_to_string(formatters::v2s_f32_gain_to_db(2))
+    .with_string_to_value(formatters::s2v_f32_gain_to_db())
+}
+
+// the curves' power params, shared with gate2zero
+pub(crate) fn power_param(name: impl Into<String>, default: f32) -> FloatParam {
+    FloatParam::new(
+        name,
+        default,
+        FloatRange::Skewed {
+            min: 16_f32.recip(),
+            max: 16.0,
+            factor: 0.25,
+        },
+    )
+    .with_string_to_value(s2v_power())
+    .with_value_to_string(Arc::new(move |value| {
+        let one_over_value = value.recip();
+        if one_over_value.round() > 1.0 {
+            if one_over_value >= 10.0 {
+                format!("1/{:.0}", one_over_value)
+            } else {
+                format!("1/{:.1}", one_over_value)
+            }
+        } else {
+            if value >= 10.0 {
+                format!("{:.0}", value)
+            } else {
+                format!("{:.1}", value)
+            }
+        }
+    }))
+}
 
-    const VERSION: &'static str = env!("CARGO_PKG_VERSION");
+// the power params display values below one as fractions, so accept them
+// back in the same form
+fn s2v_power() -> Arc<dyn Fn(&str) -> Option<f32> + Send + Sync> {
+    Arc::new(|string| {
+        let string = string.trim();
+        match string.split_once('/') {
+            Some((numerator, denominator)) => {
+                let numerator: f32 = numerator.trim().parse().ok()?;
+                let denominator: f32 = denominator.trim().parse().ok()?;
+                (denominator != 0.0).then(|| numerator / denominator)
+            }
+            None => string.parse().ok(),
+        }
+    })
+}
 
-    const AUDIO_IO_LAYOUTS: &'static [AudioIOLayout] = &[AudioIOLayout {
-        main_input_channels: NonZeroU32::new(2),
-        main_output_channels: NonZeroU32::new(2),
+const fn layout(channels: u32) -> AudioIOLayout {
+    AudioIOLayout {
+        main_input_channels: NonZeroU32::new(channels),
+        main_output_channels: NonZeroU32::new(channels),
 
         aux_input_ports: &[],
         aux_output_ports: &[],
 
         names: PortNames::const_default(),
-    }];
-
-    const MIDI_INPUT: MidiConfig = MidiConfig::None;
-    const MIDI_OUTPUT: MidiConfig = MidiConfig::None;
+    }
+}
 
+impl Plugin for Limit2zero {
+    const NAME: &'static str = metadata::LIMIT2ZERO.name;
+    const VENDOR: &'static str = metadata::VENDOR;
+    const URL: &'static str = metadata::URL;
+    const EMAIL: &'static str = metadata::EMAIL;
+
+    const VERSION: &'static str = metadata::VERSION;
+
+    // stereo first, that's the one hosts pick when they don't ask
+    const AUDIO_IO_LAYOUTS: &'static [AudioIOLayout] = &[
+        layout(2),
+        layout(1),
+        layout(3),
+        layout(4),
+        layout(5),
+        layout(6),
+        layout(7),
+        layout(8),
+    ];
+
+    const MIDI_INPUT: MidiConfig = MidiConfig::MidiCCs;
+    const MIDI_OUTPUT: MidiConfig = MidiConfig::MidiCCs;
+
+    // nih-plug splits the buffer at every param change, so a param read once
+    // per call already lands on the right sample. The smoothed ones are still
+    // read per sample so the steps don't click.
     const SAMPLE_ACCURATE_AUTOMATION: bool = true;
 
     type SysExMessage = ();
-    type BackgroundTask = ();
+    type BackgroundTask = Task;
 
     fn params(&self) -> Arc<dyn Params> {
         self.params.clone()
     }
 
+    // sessions and presets from before simple mode keep using their detailed
+    // params instead of switching over to the macros
+    fn filter_state(state: &mut PluginState) {
+        if !state.params.contains_key("advanced") {
+            state
+                .params
+                .insert(String::from("advanced"), ParamValue::Bool(true));
+        }
+
+        // the old Hold Amount set both through a square root
+        if let Some(ParamValue::F32(amount)) = state.params.remove("release_amt") {
+            let level = amount.max(0.0).sqrt();
+            for id in ["hold_level", "release_start"] {
+                state
+                    .params
+                    .entry(String::from(id))
+                    .or_insert(ParamValue::F32(level));
+            }
+        }
+    }
+
+    fn task_executor(&mut self) -> TaskExecutor<Self> {
+        let loudness = self.loudness.clone();
+        let analyzer = Mutex::new(LoudnessAnalyzer::default());
+        let compensation = self.compensation.clone();
+        let compensation_analyzer = Mutex::new(CompensationAnalyzer::default());
+        let material = self.material.clone();
+        let material_analyzer = Mutex::new(MaterialAnalyzer::default());
+        #[cfg(feature = "diagnostics")]
+        let diagnostics = self.diagnostics.clone();
+        Box::new(move |task| match task {
+            Task::ResetLoudness {
+                channels,
+                sample_rate,
+            } => analyzer
+                .lock()
+                .unwrap()
+                .reset(channels, sample_rate, &loudness),
+            Task::AnalyzeLoudness => analyzer.lock().unwrap().analyze(&loudness),
+            Task::ResetCompensation {
+                channels,
+                sample_rate,
+                db,
+            } => compensation_analyzer.lock().unwrap().reset(
+                channels,
+                sample_rate,
+                db,
+                &compensation,
+            ),
+            Task::AnalyzeCompensation => {
+                compensation_analyzer.lock().unwrap().analyze(&compensation)
+            }
+            Task::ResetMaterial {
+                channels,
+                sample_rate,
+            } => material_analyzer
+                .lock()
+                .unwrap()
+                .reset(channels, sample_rate, &material),
+            Task::AnalyzeMaterial => material_analyzer.lock().unwrap().analyze(&material),
+            #[cfg(feature = "diagnostics")]
+            Task::LogDiagnostics => diagnostics.log(),
+        })
+    }
+
     fn editor(&mut self, _async_executor: AsyncExecutor<Self>) -> Option<Box<dyn Editor>> {
         editor::create(
             self.params.clone(),
-            self.gui_pre_gain.clone(),
-            self.gui_post_gain.clone(),
-            self.gui_pre_gain.clone(),
+            self.gui_meters.clone(),
+            self.gui_scope_peak.clone(),
+            self.gui_scope_gr.clone(),
+            self.gui_clip_count.clone(),
+            self.gui_latency.clone(),
+            self.gui_sample_rate.clone(),
+            self.gui_lookahead.clone(),
+            self.gui_progress.clone(),
+            self.gui_trigger.clone(),
+            self.gui_delta.clone(),
+            self.loudness.clone(),
             self.params.editor_state.clone(),
         )
     }
@@ -814,21 +1691,86 @@ impl Plugin for Limit2zero {
         &mut self,
         audio_io_layout: &AudioIOLayout,
         buffer_config: &BufferConfig,
-        _context: &mut impl InitContext<Self>,
+        context: &mut impl InitContext<Self>,
     ) -> bool {
         let channels = audio_io_layout.main_input_channels.unwrap().get() as usize;
-        let lookahead_len =
-            (self.params.lookahead.value() * 0.001 * buffer_config.sample_rate).ceil() as usize;
         self.sample_rate = buffer_config.sample_rate;
-        self.channels = channels;
-        self.limiters = LimiterBuffer::new(channels, lookahead_len);
+        self.gui_sample_rate
+            .store(buffer_config.sample_rate, Ordering::Relaxed);
+        let settings = Settings::from_params(&self.params);
+        self.processor = Processor::new(channels, buffer_config.sample_rate, &settings);
+        // process() runs frame by frame, so nothing in it scales with the
+        // host's buffer size. What it does hold is sized for the channels and
+        // the longest lookahead here instead.
+        let max_lookahead = self.params.input.lookahead.preview_plain(1.0)
+            + self.params.input.lookahead_offset.preview_plain(1.0);
+        self.processor.reserve_lookahead(max_lookahead);
+        self.catcher = Processor::new(channels, buffer_config.sample_rate, &settings.catcher());
+        self.catcher.reserve_lookahead(engine::CATCHER_LOOKAHEAD_MS);
+        self.frame = vec![0.0; channels];
+        self.midi.reserve(&self.params);
+        self.macro_control.reserve(&self.params);
+        // a session that was just loaded brings its own mappings
+        CcTable::publish(&self.params);
+        MacroTable::publish(&self.params);
+        self.gr_out = GrOut::new(buffer_config.sample_rate);
+        #[cfg(feature = "diagnostics")]
+        {
+            self.diagnostics_capture = diagnostics::DiagnosticsCapture::new(channels);
+        }
+        // the lookahead is in ms, so a new sample rate changes the latency
+        // even when the param didn't move. Reported now rather than on the
+        // first block so the host's compensation is right from the start.
+        self.processor.update_lookahead(&settings);
+        self.catcher.update_lookahead(&settings.catcher());
+        let latency = self.latency_samples();
+        context.set_latency_samples(latency);
+        self.gui_latency.store(latency, Ordering::Relaxed);
+        self.trigger = TriggerCapture::new(buffer_config.sample_rate);
+        self.loudness_capture =
+            LoudnessCapture::new(channels, buffer_config.sample_rate, &self.loudness);
+        context.execute(Task::ResetLoudness {
+            channels,
+            sample_rate: buffer_config.sample_rate,
+        });
+        self.compensation_capture =
+            CompensationCapture::new(channels, buffer_config.sample_rate, &self.compensation);
+        // until there's a measurement it's the usual one from drive
+        let drive_compensation = Settings {
+            compensate: true,
+            measured_compensation_db: None,
+            ..settings
+        };
+        context.execute(Task::ResetCompensation {
+            channels,
+            sample_rate: buffer_config.sample_rate,
+            db: drive_compensation.compensation_db(),
+        });
+        self.material_capture = self.material.capture(channels, buffer_config.sample_rate);
+        context.execute(Task::ResetMaterial {
+            channels,
+            sample_rate: buffer_config.sample_rate,
+        });
+
+        // initialize runs again on every reconfigure, the port stays bound
+        #[cfg(feature = "osc")]
+        if self.osc.is_none() {
+            let defaults = UserDefaults::get();
+            let address = defaults.osc_address.unwrap_or(Ipv4Addr::LOCALHOST.into());
+            let port = defaults.osc_port.unwrap_or(9000);
+            self.osc = osc::OscServer::start(self.params.clone(), address, port);
+        }
 
         true
     }
 
     fn reset(&mut self) {
-        let la_len = self.lookahead_len.ceil() as usize;
-        self.limiters = LimiterBuffer::new(self.channels, la_len);
+        self.processor.reset();
+        self.catcher.reset();
+        self.smoother.reset();
+        self.loudness_capture.reset();
+        self.compensation_capture.reset();
+        self.material_capture.reset();
     }
 
     fn process(
@@ -837,276 +1779,414 @@ impl Plugin for Limit2zero {
         _aux: &mut AuxiliaryBuffers,
         context: &mut impl ProcessContext<Self>,
     ) -> ProcessStatus {
-        let atk_env = build_envelope(
-            self.params.atk_env_linearity.value(),
-            self.params.atk_env_center.value(),
-            self.params.atk_smooth_amt.value(),
-            self.params.atk_env_polarity_in.value(),
-            self.params.atk_env_polarity_out.value(),
-            self.params.atk_env_power_in.value(),
-            self.params.atk_env_power_out.value(),
-            self.params.atk_env_sm_polarity_in.value(),
-            self.params.atk_env_sm_polarity_out.value(),
-            self.params.atk_env_sm_power_in.value(),
-            self.params.atk_env_sm_power_out.value(),
-        );
-        let rel_env = build_envelope(
-            self.params.rel_env_linearity.value(),
-            self.params.rel_env_center.value(),
-            self.params.rel_smooth_amt.value(),
-            self.params.rel_env_polarity_in.value(),
-            self.params.rel_env_polarity_out.value(),
-            self.params.rel_env_power_in.value(),
-            self.params.rel_env_power_out.value(),
-            self.params.rel_env_sm_polarity_in.value(),
-            self.params.rel_env_sm_polarity_out.value(),
-            self.params.rel_env_sm_power_in.value(),
-            self.params.rel_env_sm_power_out.value(),
-        );
-
-        let (input, trim) = (self.params.drive.value(), self.params.trim.value());
-
-        let (lookahead, atk_amt) = (
-            self.params.lookahead.value() * 0.001 * self.sample_rate,
-            self.params.attack_amt.value(),
-        );
-
-        let (hold, release_amt) = (
-            self.params.hold.value() * 0.001 * self.sample_rate,
-            self.params.release_amt.value(),
-        );
-
-        let release = self.params.release.value() * 0.001 * self.sample_rate;
-
-        let stereo_link = self.params.stereo_link.value();
-
-        if lookahead.ceil() != self.lookahead_len {
-            // in bitwig i have to set half the latency samples?
-            // is it like this in other DAWs?
-            // whyyyyyyyy
-            context.set_latency_samples((lookahead / 2.0).ceil() as u32);
-            self.lookahead_len = lookahead.ceil();
-            self.reset();
+        #[cfg(feature = "osc")]
+        if let Some(osc) = &self.osc {
+            osc.drain(|param, value| self.midi.set(param, value));
         }
-
-        struct Samples {
-            samples: Vec<SampleDB>,
-            reductions: Vec<f32>,
-        }
-
-        impl Samples {
-            fn add(&mut self, sample: SampleDB, reduction: f32) {
-                self.samples.push(sample);
-                self.reductions.push(reduction);
+        let mut next_event = context.next_event();
+        // `targets` are the params as they are, `settings` where the
+        // smoother has got to
+        let (mut targets, mut controls) = self.settings();
+        let mut settings = targets;
+        let mut envelopes = settings.envelopes();
+
+        // a bounce shouldn't start out with the reduction a preview run left
+        // behind, the lookahead is still filled by the pre-roll
+        let playing = context.transport().playing;
+        if playing && !self.was_playing {
+            if controls.reset_on_play {
+                self.processor.reset_envelopes();
+                self.catcher.reset_envelopes();
             }
+            // the overs counter covers the current playback
+            self.gui_clip_count.store(0, Ordering::Relaxed);
+        }
+        self.was_playing = playing;
+
+        // fixed timing, only the ceiling and the switch follow the params
+        let catcher_settings = settings.catcher();
+        let catcher_envelopes = catcher_settings.envelopes();
+        // both, neither may be skipped
+        let main_changed = self.processor.update_lookahead(&settings).is_some();
+        let catcher_changed = self.catcher.update_lookahead(&catcher_settings).is_some();
+        if main_changed || catcher_changed {
+            let latency = self.latency_samples();
+            context.set_latency_samples(latency);
+            self.gui_latency.store(latency, Ordering::Relaxed);
         }
 
+        let editor_open = self.params.editor_state.is_open();
         let buffer_samples = buffer.samples();
         let raw_buffer = buffer.as_slice();
+        let channels = raw_buffer.len().min(self.frame.len());
 
         for sample_id in 0..buffer_samples {
-            let mut rv_samples = Samples {
-                samples: Vec::with_capacity(raw_buffer.len()),
-                reductions: Vec::with_capacity(raw_buffer.len()),
-            };
-
-            let mut most_reduction = 0.0;
-
-            let channel_samples: Vec<_> = raw_buffer
-                .iter_mut()
-                .enumerate()
-                .map(|(i, channel)| (i, channel.get_mut(sample_id).unwrap()))
-                .collect();
-
-            for (i, sample) in channel_samples {
-                let mut limiter = self.limiters.get_mut(i);
-
-                let new_sample = SampleDB {
-                    sample: *sample * input,
-                    db: util::gain_to_db_fast(sample.abs() * input),
-                };
-
-                limiter.buffer.push_back(new_sample);
-
-                // do stuff based on envelope state
-                match &mut limiter.state {
-                    EnvState::Hold(elapsed) => {
-                        if *elapsed == 0.0 {
-                            *limiter.target = *limiter.hold;
-                            *limiter.envelope = *limiter.hold;
-                        }
-                        *elapsed += 1.0;
-                        if *elapsed >= (hold + 1.0) {
-                            if release.round() >= 1.0 {
-                                *limiter.state = EnvState::Release(0.0);
-                            } else {
-                                *limiter.state = EnvState::Off;
-                            }
-                        }
-                    }
-                    EnvState::Release(elapsed) => {
-                        if *elapsed == 0.0 {
-                            *limiter.target = *limiter.hold;
-                            *limiter.envelope = *limiter.hold;
-                        }
-                        *elapsed += 1.0;
-                        let t = *elapsed / (release + 1.0);
-
-                        // NOTE: calc_rel_reduction
-                        *limiter.envelope = lerp(*limiter.target, 0.0, rel_env.process(t));
-
-                        if *elapsed >= (release + 1.0) {
-                            *limiter.state = EnvState::Off;
-                        }
-                    }
-                    EnvState::Off => {
-                        if *limiter.envelope != 0.0
-                            || *limiter.target != 0.0
-                            || *limiter.hold != 0.0
-                        {
-                            *limiter.envelope = 0.0;
-                            *limiter.target = 0.0;
-                            *limiter.hold = 0.0;
-                        }
-                    }
+            // the host only splits the buffer for its own automation, MIDI
+            // CCs are handled at their sample here
+            let mut received = false;
+            while let Some(event) = next_event {
+                if event.timing() > sample_id as u32 {
+                    break;
                 }
-
-                // search buffer for peaks and calc atk env
-                // or
-                // calculate atk envelope using the last known peak
-                let la_acc = self.params.lookahead_accuracy.value();
-                let mut atk_reduction = 0.0;
-                if self.lookahead_len >= 1.0 && sample_id as i32 % la_acc == 0 {
-                    let mut db = 0.0;
-                    let mut position = 0.0;
-                    let mut curr_reduct = 0.0;
-
-                    for (i, sample) in limiter
-                        .buffer
-                        .iter()
-                        .rev()
-                        .enumerate()
-                        .filter(|x| x.1.peak())
-                    {
-                        let t = atk_env.process((i + 1) as f32 / (self.lookahead_len + 1.0));
-                        let reduct = calc_atk_reduction(sample.db, t);
-                        if reduct < curr_reduct {
-                            curr_reduct = reduct;
-                            db = sample.db;
-                            position = i as f32;
-                        }
-                    }
-                    if db > 0.0 {
-                        *limiter.current_peak.db = db;
-                        *limiter.current_peak.position = position;
-                        *limiter.current_peak.lerp_len = self.lookahead_len;
-                        atk_reduction = curr_reduct * atk_amt;
-                    }
-                } else if let Some(reduction) = limiter.current_peak.read(atk_env) {
-                    atk_reduction = reduction * atk_amt;
+                if let NoteEvent::MidiCC { cc, value, .. } = event {
+                    self.midi.receive(&self.params, cc, value);
+                    received = true;
                 }
+                next_event = context.next_event();
+            }
+            if received {
+                (targets, controls) = self.settings();
+            }
 
-                if atk_reduction < *limiter.envelope {
-                    *limiter.target = atk_reduction;
-                    *limiter.hold = atk_reduction * release_amt.sqrt();
-                    *limiter.envelope = atk_reduction;
-                    if hold.round() >= 1.0 {
-                        *limiter.state = EnvState::Hold(0.0);
-                    } else if release.round() >= 1.0 {
-                        *limiter.state = EnvState::Release(0.0);
-                    } else {
-                        *limiter.state = EnvState::Off;
-                    }
-                }
+            // the lookahead's smoother keeps running under an override, so
+            // there's no jump once it's dropped
+            let lookahead = self.params.input.lookahead.smoothed.next();
+            settings = targets;
+            if controls.lookahead && settings.lookahead_glide {
+                settings.lookahead_ms = lookahead;
+            }
+            if self.smoother.apply(&mut settings, controls.smoothing_steps) || received {
+                envelopes = settings.envelopes();
+            }
 
-                // grab delayed sample from buffer
-                let delay = limiter.buffer.pop_front().unwrap();
-
-                // if the sample is still over 0.0 after the envelope is applied,
-                // clip it.
-                if delay.db + *limiter.envelope > 0.0 {
-                    *limiter.target = -1.0 * delay.db;
-                    *limiter.hold = *limiter.target * release_amt.sqrt();
-                    *limiter.envelope = *limiter.target;
-                    if hold.round() >= 1.0 {
-                        *limiter.state = EnvState::Hold(0.0);
-                    } else if release.round() >= 1.0 {
-                        *limiter.state = EnvState::Release(0.0);
-                    } else {
-                        *limiter.state = EnvState::Off;
-                    }
-                }
+            let frame = &mut self.frame[..channels];
+            for (value, channel) in frame.iter_mut().zip(raw_buffer.iter()) {
+                *value = channel[sample_id];
+            }
+            // the same on every channel, in place of the input
+            if let Some((signal, frequency, amplitude)) = controls.tone {
+                frame.fill(match signal {
+                    ToneSignal::Sine => self.tone_sine.next(self.sample_rate, frequency, amplitude),
+                    ToneSignal::PinkNoise => self.tone_noise.next(amplitude),
+                });
+            }
+
+            // the program material as it comes in, before drive
+            if self.loudness_capture.push(frame, &self.loudness) {
+                context.execute_background(Task::AnalyzeLoudness);
+            }
 
-                most_reduction = f32::min(most_reduction, *limiter.envelope);
+            if controls.auto_release && self.material.push(&mut self.material_capture, frame) {
+                context.execute_background(Task::AnalyzeMaterial);
+            }
 
-                rv_samples.add(delay, *limiter.envelope);
+            let report = self
+                .processor
+                .process_frame(frame, &settings, &envelopes, sample_id);
+
+            // needs the measurement whether or not Match Loudness is on
+            if (controls.gain_match || (settings.compensate && controls.compensate_loudness))
+                && self
+                    .compensation_capture
+                    .push(report.delayed, report.linked, &self.compensation)
+            {
+                context.execute_background(Task::AnalyzeCompensation);
             }
 
-            let compensation = if self.params.compensate.value() {
-                util::gain_to_db_fast(input) / -2.0
-            } else {
-                0.0
-            };
+            // after the limiter's own meters, which stay about the limiter
+            self.catcher
+                .process_frame(frame, &catcher_settings, &catcher_envelopes, sample_id);
+
+            if let Some(cc) = controls.gr_midi_cc {
+                let reduction = report.linked.iter().fold(0.0, f32::min);
+                if let Some(value) = self.gr_out.push(reduction) {
+                    context.send_event(NoteEvent::MidiCC {
+                        timing: sample_id as u32,
+                        channel: 0,
+                        cc,
+                        value,
+                    });
+                }
+            }
+
+            if report.clips > 0 {
+                self.gui_clip_count
+                    .fetch_add(report.clips, Ordering::Relaxed);
+            }
+            #[cfg(feature = "diagnostics")]
+            let frame_clips = report.clips;
+            #[cfg(feature = "diagnostics")]
+            let frame_gr = report.linked.iter().fold(0.0, f32::min);
+
+            let mut trigger_frame = [[0.0; 2]; 2];
+            let mut trigger_peak = false;
+            let mut delta = 0.0;
 
-            for (i, sample_pre) in rv_samples.samples.iter().enumerate() {
-                let channel = raw_buffer.get_mut(i).unwrap();
-                let reduce = rv_samples.reductions.get(i).unwrap();
-                let reduce = lerp(*reduce, most_reduction, stereo_link);
+            for (i, output) in frame.iter().enumerate() {
+                raw_buffer[i][sample_id] = *output;
 
-                *channel.get_mut(sample_id).unwrap() =
-                    sample_pre.sample * util::db_to_gain_fast(reduce + trim + compensation);
+                let sample_pre = report.delayed[i];
+                let (unlinked, reduce) = (report.unlinked[i], report.linked[i]);
                 let post_db = sample_pre.db + reduce;
+                // past stereo, the even channels go on the left meters and
+                // the odd ones on the right, each showing the loudest of them
+                let meter = i % 2;
 
-                if self.params.editor_state.is_open() {
-                    // send editor highest samples 120 times every second
-                    let editor_msg_freq = self.sample_rate.ceil() as usize / 120;
-                    let mut editor_msg = EditorMsg::default();
+                if i < 2 {
+                    trigger_frame[i] = [sample_pre.sample, *output];
+                }
+                trigger_peak |= sample_pre.peak();
+                // at the level it went into the limiter, before trim
+                delta += sample_pre.sample * (1.0 - util::db_to_gain(reduce));
 
-                    editor_msg.add_pre(sample_pre.db, i);
-                    editor_msg.add_post(post_db, i);
-                    editor_msg.add_gr(reduce, i);
+                #[cfg(feature = "osc")]
+                if let Some(osc) = &self.osc {
+                    osc.store_reduction(meter, reduce);
+                }
 
-                    if self.gui_msg_timer % editor_msg_freq == 0 {
-                        self.gui_pre_gain[i].fetch_max(editor_msg.pre[i], Ordering::Relaxed);
-                        self.gui_post_gain[i].fetch_max(editor_msg.post[i], Ordering::Relaxed);
-                        self.gui_reduction[i].fetch_max(editor_msg.gr[i], Ordering::Relaxed);
-                        self.gui_msg_timer = 0;
-                    }
-                    self.gui_msg_timer += 1;
-                } else if self.gui_msg_timer != 0 {
+                if editor_open {
+                    // the scope wants every sample, not just the ones the timer lands on
+                    self.gui_scope_peak[meter].fetch_max(output.abs(), Ordering::Relaxed);
+                    self.gui_scope_gr[meter].fetch_min(reduce, Ordering::Relaxed);
+                    self.meter_frame
+                        .add(meter, sample_pre.db, post_db, reduce, unlinked);
+                }
+            }
+
+            if editor_open {
+                self.trigger
+                    .push(trigger_frame, trigger_peak, &self.gui_trigger);
+                self.gui_delta.push(delta / channels.max(1) as f32);
+
+                // send the editor the meters 120 times every second
+                let editor_msg_freq = (self.sample_rate.ceil() as usize / 120).max(1);
+                self.gui_msg_timer += 1;
+                if self.gui_msg_timer >= editor_msg_freq {
                     self.gui_msg_timer = 0;
+                    // with the editor behind, this frame goes out with the next one
+                    if self.gui_meters.push(self.meter_frame) {
+                        self.meter_frame = MeterFrame::default();
+                    } else {
+                        #[cfg(feature = "diagnostics")]
+                        self.diagnostics.meter_overrun();
+                    }
                 }
+            } else if self.gui_msg_timer != 0 {
+                self.gui_msg_timer = 0;
+                self.meter_frame = MeterFrame::default();
+            }
+
+            #[cfg(feature = "diagnostics")]
+            self.diagnostics_capture
+                .frame(&mut self.processor, frame_clips, frame_gr);
+        }
+
+        if editor_open {
+            let release = settings.release_ms * 0.001 * self.sample_rate;
+            // the lookahead and envelope views only have room for a pair
+            for channel in 0..channels.min(2) {
+                let window = self.processor.lookahead_window(&settings, channel);
+                let limiter = self.processor.limiter(channel);
+                self.gui_lookahead
+                    .store(channel, limiter.buffer, window, &limiter.current_peak);
+                self.gui_progress
+                    .store(channel, &limiter.current_peak, limiter.state, release);
             }
         }
-        ProcessStatus::Normal
+
+        #[cfg(feature = "diagnostics")]
+        if self
+            .diagnostics_capture
+            .block(buffer_samples, self.sample_rate, &self.diagnostics)
+        {
+            context.execute_background(Task::LogDiagnostics);
+        }
+
+        // hosts that suspend plugins on silent input would cut off what's
+        // still in the lookahead otherwise
+        let tail =
+            self.processor.tail_samples(&settings) + self.catcher.tail_samples(&catcher_settings);
+        match tail {
+            0 => ProcessStatus::Normal,
+            tail => ProcessStatus::Tail(tail),
+        }
     }
 }
 
-fn lerp(a: f32, b: f32, t: f32) -> f32 {
-    a + (b - a) * t
+// the params the audio thread goes by that aren't limiter settings, read
+// through the same overrides
+struct Controls {
+    // whether the limiter follows the gliding lookahead's smoother per
+    // sample, an override stays at the override instead. Everything else
+    // goes through the `SettingsSmoother`.
+    lookahead: bool,
+    smoothing_steps: u32,
+    reset_on_play: bool,
+    gain_match: bool,
+    compensate_loudness: bool,
+    auto_release: bool,
+    // the signal, its frequency and its amplitude, while it's on
+    tone: Option<(ToneSignal, f32, f32)>,
+    // the CC the reduction goes out on, while it does
+    gr_midi_cc: Option<u8>,
 }
 
-fn calc_atk_reduction(db: f32, t: f32) -> f32 {
-    lerp(0.0, -1.0 * db, t)
-}
+impl Limit2zero {
+    // the limiter's and the catcher's together
+    fn latency_samples(&self) -> u32 {
+        self.processor.latency_samples() + self.catcher.latency_samples()
+    }
+
+    // the params as the audio thread sees them, with the MIDI and OSC
+    // overrides and what the macros drive
+    fn settings(&mut self) -> (Settings, Controls) {
+        let midi = self.midi.overrides();
+        let overrides = self.macro_control.overrides(&self.params, midi);
+        let free = |ptr: ParamPtr| !overrides.iter().any(|(overridden, _)| *overridden == ptr);
+        let (input, output) = (&self.params.input, &self.params.output);
+        let tone = &self.params.test_tone;
+        let controls = Controls {
+            lookahead: free(input.lookahead.as_ptr()),
+            smoothing_steps: (plain(&input.automation_smoothing, overrides)
+                * 0.001
+                * self.sample_rate)
+                .round() as u32,
+            reset_on_play: plain(&input.reset_on_play, overrides),
+            gain_match: plain(&output.gain_match, overrides),
+            compensate_loudness: plain(&output.compensate_loudness, overrides),
+            auto_release: plain(&self.params.hold_release.auto_release, overrides),
+            tone: plain(&tone.enabled, overrides).then(|| {
+                (
+                    plain(&tone.signal, overrides),
+                    plain(&tone.frequency, overrides),
+                    util::db_to_gain(plain(&tone.level, overrides)),
+                )
+            }),
+            gr_midi_cc: plain(&output.gr_midi_out, overrides)
+                .then(|| plain(&output.gr_midi_cc, overrides) as u8),
+        };
 
-impl ClapPlugin for Limit2zero {
-    const CLAP_ID: &'static str = "com.your-domain.limit2zero";
-    const CLAP_DESCRIPTION: Option<&'static str> = Some("basic limiter");
-    const CLAP_MANUAL_URL: Option<&'static str> = Some(Self::URL);
-    const CLAP_SUPPORT_URL: Option<&'static str> = None;
+        let mut settings = Settings::with_overrides(&self.params, overrides);
+        if controls.compensate_loudness {
+            settings.measured_compensation_db = Some(self.compensation.db.load(Ordering::Relaxed));
+        }
+        // the catcher follows trim, so it still only touches what the
+        // limiter let through
+        if controls.gain_match {
+            settings.trim = self.compensation.matched_db.load(Ordering::Relaxed);
+            settings.compensate = false;
+            settings.output_gain = 1.0;
+        }
+        // over the params and the macros alike, once there's a guess
+        if controls.auto_release {
+            if let Some(material) = self.material.material() {
+                (settings.hold_ms, settings.release_ms) = material.timing();
+            }
+        }
+        (settings, controls)
+    }
+}
 
-    // Don't forget to change these features
-    const CLAP_FEATURES: &'static [ClapFeature] = &[ClapFeature::AudioEffect, ClapFeature::Stereo];
+// a param's value with its override in place, if it has one
+fn plain<P: Param>(param: &P, overrides: &[(ParamPtr, f32)]) -> P::Plain {
+    match overrides.iter().find(|(ptr, _)| *ptr == param.as_ptr()) {
+        Some((_, normalized)) => param.preview_plain(*normalized),
+        None => param.modulated_plain_value(),
+    }
 }
 
-impl Vst3Plugin for Limit2zero {
-    const VST3_CLASS_ID: [u8; 16] = *b"Exactly16Chars!!";
+// the engine only sees plain values
+impl Settings {
+    pub(crate) fn from_params(params: &Limit2zeroParams) -> Self {
+        Self::with_overrides(params, &[])
+    }
+
+    // `overrides` are normalized values that replace those params' own, the
+    // MIDI CCs mapped to them
+    pub(crate) fn with_overrides(params: &Limit2zeroParams, overrides: &[(ParamPtr, f32)]) -> Self {
+        let mut settings = Self {
+            drive: plain(&params.input.drive, overrides),
+            trim: plain(&params.output.trim, overrides),
+            output_gain: plain(&params.output.output_gain, overrides),
+            lookahead_ms: plain(&params.input.lookahead, overrides),
+            lookahead_accuracy: plain(&params.input.lookahead_accuracy, overrides),
+            lookahead_glide: plain(&params.input.lookahead_glide, overrides),
+            lookahead_offset_ms: plain(&params.input.lookahead_offset, overrides),
+            live: plain(&params.input.live, overrides),
+            dc_block: plain(&params.input.dc_block, overrides),
+            detector_tilt_db: plain(&params.input.detector_tilt, overrides),
+            detector_weighting: plain(&params.input.detector_weighting, overrides).into(),
+            detector_low_db: plain(&params.input.detector_low, overrides),
+            detector_mid_db: plain(&params.input.detector_mid, overrides),
+            detector_mid_hz: plain(&params.input.detector_mid_freq, overrides),
+            detector_high_db: plain(&params.input.detector_high, overrides),
+            detector_listen: plain(&params.input.detector_listen, overrides),
+            algorithm: plain(&params.input.algorithm, overrides).into(),
+            leveling: plain(&params.input.leveling, overrides),
+            leveling_target_db: plain(&params.input.leveling_target, overrides),
+            leveling_range_db: plain(&params.input.leveling_range, overrides),
+            attack_amt: plain(&params.attack.attack_amt, overrides),
+            attack_overshoot: plain(&params.attack.attack_bite, overrides),
+            hold_ms: plain(&params.hold_release.hold, overrides),
+            release_ms: plain(&params.hold_release.release, overrides),
+            hold_level: plain(&params.hold_release.hold_level, overrides),
+            true_hold: plain(&params.hold_release.true_hold, overrides),
+            release_start: plain(&params.hold_release.release_start, overrides),
+            release_scaling: plain(&params.hold_release.release_scaling, overrides),
+            gr_slew_db_per_ms: Some(plain(&params.hold_release.gr_slew, overrides))
+                .filter(|rate| *rate < GR_SLEW_OFF),
+            freeze: plain(&params.hold_release.freeze, overrides),
+            true_peak: plain(&params.output.true_peak, overrides),
+            true_peak_catcher: plain(&params.output.true_peak_catcher, overrides),
+            inverse: plain(&params.output.inverse, overrides),
+            stereo_link: plain(&params.input.stereo_link, overrides),
+            width: plain(&params.output.width, overrides),
+            balance_guard_db: Some(plain(&params.input.balance_guard, overrides))
+                .filter(|guard| *guard < BALANCE_GUARD_OFF),
+            compensate: plain(&params.output.compensate, overrides),
+            measured_compensation_db: None,
+            bypass: plain(&params.bypass, overrides),
+            attack: EnvelopeShape {
+                linearity: plain(&params.attack.atk_env_linearity, overrides),
+                center: plain(&params.attack.atk_env_center, overrides),
+                smooth_amount: plain(&params.attack_smoothing.atk_smooth_amt, overrides),
+                polarity_in: plain(&params.attack.atk_env_polarity_in, overrides),
+                polarity_out: plain(&params.attack.atk_env_polarity_out, overrides),
+                power_in: plain(&params.attack.atk_env_power_in, overrides),
+                power_out: plain(&params.attack.atk_env_power_out, overrides),
+                sm_polarity_in: plain(&params.attack_smoothing.atk_env_sm_polarity_in, overrides),
+                sm_polarity_out: plain(&params.attack_smoothing.atk_env_sm_polarity_out, overrides),
+                sm_power_in: plain(&params.attack_smoothing.atk_env_sm_power_in, overrides),
+                sm_power_out: plain(&params.attack_smoothing.atk_env_sm_power_out, overrides),
+            },
+            release: EnvelopeShape {
+                linearity: plain(&params.release.rel_env_linearity, overrides),
+                center: plain(&params.release.rel_env_center, overrides),
+                smooth_amount: plain(&params.release.rel_smooth_amt, overrides),
+                polarity_in: plain(&params.release.rel_env_polarity_in, overrides),
+                polarity_out: plain(&params.release.rel_env_polarity_out, overrides),
+                power_in: plain(&params.release.rel_env_power_in, overrides),
+                power_out: plain(&params.release.rel_env_power_out, overrides),
+                sm_polarity_in: plain(&params.release.rel_env_sm_polarity_in, overrides),
+                sm_polarity_out: plain(&params.release.rel_env_sm_polarity_out, overrides),
+                sm_power_in: plain(&params.release.rel_env_sm_power_in, overrides),
+                sm_power_out: plain(&params.release.rel_env_sm_power_out, overrides),
+            },
+        };
+
+        if plain(&params.attack.atk_morph_on, overrides) {
+            let position = plain(&params.attack.atk_morph, overrides);
+            settings.attack = EnvelopeShape::morph(&ATTACK_MORPHS, position);
+        }
+        if plain(&params.release.rel_morph_on, overrides) {
+            let position = plain(&params.release.rel_morph, overrides);
+            settings.release = EnvelopeShape::morph(&RELEASE_MORPHS, position);
+        }
 
-    // And also don't forget to change these categories
-    const VST3_SUBCATEGORIES: &'static [Vst3SubCategory] =
-        &[Vst3SubCategory::Fx, Vst3SubCategory::Dynamics];
+        if !plain(&params.simple.advanced, overrides) {
+            Macros {
+                attack_shape: plain(&params.simple.attack_shape, overrides),
+                release_shape: plain(&params.simple.release_shape, overrides),
+                speed: plain(&params.simple.speed, overrides),
+                amount: plain(&params.simple.amount, overrides),
+            }
+            .apply(&mut settings);
+        }
+        settings
+    }
 }
 
-nih_export_clap!(Limit2zero);
-nih_export_vst3!(Limit2zero);
+metadata::impl_formats!(Limit2zero, metadata::LIMIT2ZERO);
+
+nih_export_clap!(Limit2zero, clip2zero::Clip2zero, gate2zero::Gate2zero);
+nih_export_vst3!(Limit2zero, clip2zero::Clip2zero, gate2zero::Gate2zero);
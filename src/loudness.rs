@@ -0,0 +1,246 @@
+use atomic_float::AtomicF32;
+use std::collections::VecDeque;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+
+use crate::engine::k_weighting;
+
+/// Length of the blocks the audio thread hands over, and the step between
+/// the BS.1770 gating blocks.
+pub(crate) const BLOCK_MS: f32 = 100.0;
+// gating blocks are 400 ms, four of the handed over blocks
+const GATING_BLOCKS: usize = 4;
+// how much of the recent program material goes into the measurement
+const WINDOW_BLOCKS: usize = 300;
+// 3 s, the window of BS.1770's short-term loudness
+const SHORT_TERM_BLOCKS: usize = 30;
+/// Integrated loudness the suggested drive aims for, what most streaming
+/// services normalize to.
+pub(crate) const TARGET_LUFS: f32 = -14.0;
+const ABSOLUTE_GATE: f64 = -70.0;
+const RELATIVE_GATE: f64 = -10.0;
+
+/// Results of the background loudness analysis for the editor, and the
+/// block of input the audio thread hands over to it.
+pub(crate) struct LoudnessSnapshot {
+    // interleaved input, swapped in whole so neither side allocates
+    inbox: Mutex<Vec<f32>>,
+    /// Integrated loudness of the last 30 seconds of input, negative infinity
+    /// while it's all below the absolute gate.
+    pub lufs: AtomicF32,
+    /// Drive in dB that brings the measured loudness up to [`TARGET_LUFS`],
+    /// without accounting for what the limiter takes off again.
+    pub suggested_drive: AtomicF32,
+}
+
+impl Default for LoudnessSnapshot {
+    fn default() -> Self {
+        Self {
+            inbox: Mutex::new(Vec::new()),
+            lufs: AtomicF32::new(f32::NEG_INFINITY),
+            suggested_drive: AtomicF32::new(0.0),
+        }
+    }
+}
+
+/// Collects the plugin's input on the audio thread, one block at a time.
+pub(crate) struct LoudnessCapture {
+    block: Vec<f32>,
+    filled: usize,
+    channels: usize,
+}
+
+impl LoudnessCapture {
+    /// Also sizes `snapshot`'s inbox to match, so call this off the audio thread.
+    pub fn new(channels: usize, sample_rate: f32, snapshot: &LoudnessSnapshot) -> Self {
+        let block_len = (sample_rate * BLOCK_MS / 1000.0).round() as usize;
+        *snapshot.inbox.lock().unwrap() = vec![0.0; block_len * channels];
+        Self {
+            block: vec![0.0; block_len * channels],
+            filled: 0,
+            channels,
+        }
+    }
+
+    /// Adds a frame of input, true once a block is complete and handed over
+    /// to `snapshot`, which is when the analysis should run.
+    pub fn push(&mut self, frame: &[f32], snapshot: &LoudnessSnapshot) -> bool {
+        let start = self.filled * self.channels;
+        self.block[start..start + self.channels].copy_from_slice(&frame[..self.channels]);
+        self.filled += 1;
+        if self.filled * self.channels < self.block.len() {
+            return false;
+        }
+
+        self.filled = 0;
+        // the analysis still has the last block, this one's skipped
+        let Ok(mut inbox) = snapshot.inbox.try_lock() else {
+            return false;
+        };
+        std::mem::swap(&mut *inbox, &mut self.block);
+        true
+    }
+
+    pub fn reset(&mut self) {
+        self.filled = 0;
+    }
+}
+
+impl LoudnessSnapshot {
+    /// The last block the audio thread handed over, interleaved.
+    pub fn read_block<R>(&self, read: impl FnOnce(&[f32]) -> R) -> R {
+        read(&self.inbox.lock().unwrap())
+    }
+}
+
+/// BS.1770 integrated loudness of the recent input, run as a background task.
+pub(crate) struct LoudnessAnalyzer {
+    filters: Vec<KWeighting>,
+    // mean square of each block, summed over the channels
+    blocks: VecDeque<f64>,
+    window: usize,
+    // only integrated loudness leaves out the quiet parts
+    relative_gate: bool,
+}
+
+impl Default for LoudnessAnalyzer {
+    fn default() -> Self {
+        Self {
+            filters: Vec::new(),
+            blocks: VecDeque::new(),
+            window: WINDOW_BLOCKS,
+            relative_gate: true,
+        }
+    }
+}
+
+impl LoudnessAnalyzer {
+    /// Short-term loudness instead, over the last 3 seconds without the
+    /// relative gate.
+    pub fn short_term() -> Self {
+        Self {
+            window: SHORT_TERM_BLOCKS,
+            relative_gate: false,
+            ..Self::default()
+        }
+    }
+
+    /// Starts over, from a new sample rate or channel count.
+    pub fn reset(&mut self, channels: usize, sample_rate: f32, snapshot: &LoudnessSnapshot) {
+        self.filters = (0..channels)
+            .map(|_| KWeighting::new(sample_rate))
+            .collect();
+        self.blocks.clear();
+        self.blocks.reserve(self.window);
+        snapshot.lufs.store(f32::NEG_INFINITY, Ordering::Relaxed);
+        snapshot.suggested_drive.store(0.0, Ordering::Relaxed);
+    }
+
+    /// Measures the block in `snapshot`'s inbox and updates the results.
+    pub fn analyze(&mut self, snapshot: &LoudnessSnapshot) {
+        let channels = self.filters.len();
+        if channels == 0 {
+            return;
+        }
+
+        let mut sum = 0.0;
+        let frames = {
+            let inbox = snapshot.inbox.lock().unwrap();
+            for frame in inbox.chunks_exact(channels) {
+                for (filter, sample) in self.filters.iter_mut().zip(frame) {
+                    let weighted = filter.process(*sample as f64);
+                    sum += weighted * weighted;
+                }
+            }
+            inbox.len() / channels
+        };
+        if frames == 0 {
+            return;
+        }
+
+        if self.blocks.len() >= self.window {
+            self.blocks.pop_front();
+        }
+        self.blocks.push_back(sum / frames as f64);
+
+        let lufs = self.integrated();
+        snapshot.lufs.store(
+            lufs.map_or(f32::NEG_INFINITY, |lufs| lufs as f32),
+            Ordering::Relaxed,
+        );
+        let drive = lufs.map_or(0.0, |lufs| (TARGET_LUFS - lufs as f32).clamp(0.0, 60.0));
+        snapshot.suggested_drive.store(drive, Ordering::Relaxed);
+    }
+
+    fn integrated(&self) -> Option<f64> {
+        let gating: Vec<f64> = (GATING_BLOCKS..=self.blocks.len())
+            .map(|end| {
+                let window = self.blocks.range(end - GATING_BLOCKS..end);
+                window.sum::<f64>() / GATING_BLOCKS as f64
+            })
+            .filter(|power| loudness(*power) > ABSOLUTE_GATE)
+            .collect();
+        if gating.is_empty() {
+            return None;
+        }
+        if !self.relative_gate {
+            return Some(loudness(mean(&gating)));
+        }
+
+        let relative_gate = loudness(mean(&gating)) + RELATIVE_GATE;
+        let gated: Vec<f64> = gating
+            .into_iter()
+            .filter(|power| loudness(*power) > relative_gate)
+            .collect();
+        Some(loudness(mean(&gated)))
+    }
+}
+
+fn mean(powers: &[f64]) -> f64 {
+    powers.iter().sum::<f64>() / powers.len().max(1) as f64
+}
+
+fn loudness(power: f64) -> f64 {
+    -0.691 + 10.0 * power.max(1e-12).log10()
+}
+
+// the two stage pre-filter from BS.1770, the same one the detector can
+// listen through
+struct KWeighting {
+    shelf: Biquad,
+    high_pass: Biquad,
+}
+
+impl KWeighting {
+    fn new(sample_rate: f32) -> Self {
+        let [(shelf_b, shelf_a), (high_pass_b, high_pass_a)] = k_weighting(sample_rate as f64);
+        Self {
+            shelf: Biquad::new(shelf_b, shelf_a),
+            high_pass: Biquad::new(high_pass_b, high_pass_a),
+        }
+    }
+
+    fn process(&mut self, sample: f64) -> f64 {
+        self.high_pass.process(self.shelf.process(sample))
+    }
+}
+
+// direct form II transposed
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+    z: [f64; 2],
+}
+
+impl Biquad {
+    fn new(b: [f64; 3], a: [f64; 2]) -> Self {
+        Self { b, a, z: [0.0; 2] }
+    }
+
+    fn process(&mut self, x: f64) -> f64 {
+        let y = self.b[0] * x + self.z[0];
+        self.z[0] = self.b[1] * x - self.a[0] * y + self.z[1];
+        self.z[1] = self.b[2] * x - self.a[1] * y;
+        y
+    }
+}
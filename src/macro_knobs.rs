@@ -0,0 +1,231 @@
+use nih_plug::prelude::{Param, ParamPtr, Params};
+use serde::{Deserialize, Serialize};
+
+use crate::Limit2zeroParams;
+
+pub(crate) const MACRO_KNOBS: usize = 4;
+
+/// How a macro's travel maps onto a param's range.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) enum MacroCurve {
+    #[default]
+    Linear,
+    // most of the movement near the top of the knob
+    SlowStart,
+    // most of the movement near the bottom
+    FastStart,
+}
+
+impl MacroCurve {
+    pub fn next(&self) -> Self {
+        match self {
+            MacroCurve::Linear => MacroCurve::SlowStart,
+            MacroCurve::SlowStart => MacroCurve::FastStart,
+            MacroCurve::FastStart => MacroCurve::Linear,
+        }
+    }
+
+    /// Key of the curve's label in the editor's string table.
+    pub fn name(&self) -> &'static str {
+        match self {
+            MacroCurve::Linear => "macro.curve_linear",
+            MacroCurve::SlowStart => "macro.curve_slow_start",
+            MacroCurve::FastStart => "macro.curve_fast_start",
+        }
+    }
+
+    fn apply(&self, position: f32) -> f32 {
+        match self {
+            MacroCurve::Linear => position,
+            MacroCurve::SlowStart => position * position,
+            MacroCurve::FastStart => 1.0 - (1.0 - position) * (1.0 - position),
+        }
+    }
+}
+
+/// A param a macro drives, by param ID, going from `from` to `to` as the
+/// macro turns up. Both are normalized values, so `to` may sit below `from`
+/// to turn the param down instead.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct MacroTarget {
+    pub id: String,
+    pub from: f32,
+    pub to: f32,
+    pub curve: MacroCurve,
+}
+
+/// Which params each macro knob drives. Stored in the plugin state next to
+/// the MIDI mapping, so a setup survives reloading the session.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct MacroMap([Vec<MacroTarget>; MACRO_KNOBS]);
+
+impl MacroMap {
+    /// The macro driving the param, and how.
+    pub fn target(&self, id: &str) -> Option<(usize, &MacroTarget)> {
+        self.0.iter().enumerate().find_map(|(knob, targets)| {
+            targets
+                .iter()
+                .find(|target| target.id == id)
+                .map(|target| (knob, target))
+        })
+    }
+
+    // one macro per param, assigning again moves it over its full range
+    pub fn assign(&mut self, knob: usize, id: String) {
+        self.remove(&id);
+        if let Some(targets) = self.0.get_mut(knob) {
+            targets.push(MacroTarget {
+                id,
+                from: 0.0,
+                to: 1.0,
+                curve: MacroCurve::Linear,
+            });
+        }
+    }
+
+    pub fn remove(&mut self, id: &str) {
+        for targets in self.0.iter_mut() {
+            targets.retain(|target| target.id != id);
+        }
+    }
+
+    pub fn target_mut(&mut self, id: &str) -> Option<&mut MacroTarget> {
+        self.0
+            .iter_mut()
+            .flat_map(|targets| targets.iter_mut())
+            .find(|target| target.id == id)
+    }
+}
+
+/// A target worked out to its param, for the audio thread.
+#[derive(Debug, Clone, Copy)]
+struct MappedTarget {
+    knob: usize,
+    param: ParamPtr,
+    from: f32,
+    to: f32,
+    curve: MacroCurve,
+}
+
+impl MappedTarget {
+    fn normalized(&self, position: f32) -> f32 {
+        let position = self.curve.apply(position.clamp(0.0, 1.0));
+        self.from + (self.to - self.from) * position
+    }
+}
+
+/// The mapping as the audio thread reads it. Counts up on every change, so
+/// the audio thread only copies the targets over when they're new.
+#[derive(Debug, Default)]
+pub(crate) struct MacroTable {
+    version: u64,
+    targets: Vec<MappedTarget>,
+}
+
+// the pointers only point into the plugin's own params
+unsafe impl Send for MacroTable {}
+unsafe impl Sync for MacroTable {}
+
+impl MacroTable {
+    /// Works the table out again from the mapping. Goes through the param
+    /// map, so only ever from the editor or `initialize`.
+    pub fn publish(params: &Limit2zeroParams) {
+        let param_map = params.param_map();
+        // the macros can't drive each other, the menu doesn't offer it but
+        // a saved state could still have it
+        let knobs = params.macros.knobs().map(|knob| knob.as_ptr());
+        let targets = params
+            .macro_map
+            .read()
+            .unwrap()
+            .0
+            .iter()
+            .enumerate()
+            .flat_map(|(knob, targets)| targets.iter().map(move |target| (knob, target)))
+            .filter_map(|(knob, target)| {
+                param_map
+                    .iter()
+                    .find(|(id, ptr, _)| *id == target.id && !knobs.contains(ptr))
+                    .map(|(_, ptr, _)| MappedTarget {
+                        knob,
+                        param: *ptr,
+                        from: target.from,
+                        to: target.to,
+                        curve: target.curve,
+                    })
+            })
+            .collect();
+
+        let mut table = params.macro_table.write().unwrap();
+        table.version += 1;
+        table.targets = targets;
+    }
+}
+
+/// Audio thread side of the macros. Like the MIDI mapping, they override the
+/// values the limiter reads rather than automating the params themselves,
+/// so a driven param's own knob stays where it is.
+#[derive(Default)]
+pub(crate) struct MacroControl {
+    version: u64,
+    mapped: Vec<MappedTarget>,
+    values: Vec<(ParamPtr, f32)>,
+}
+
+// the pointers only point into the plugin's own params
+unsafe impl Send for MacroControl {}
+
+impl MacroControl {
+    /// Sizes the targets and overrides up front, so neither turning a macro
+    /// nor picking up a new mapping allocates on the audio thread. There's
+    /// at most one target per param.
+    pub fn reserve(&mut self, params: &Limit2zeroParams) {
+        let len = params.param_map().len();
+        self.mapped.reserve(len);
+        self.values.reserve(len);
+    }
+
+    /// `midi` with the values of the params the macros drive added, for
+    /// everything the audio thread reads. A param moved over MIDI or OSC
+    /// goes by that instead, and so does a macro knob itself.
+    pub fn overrides(
+        &mut self,
+        params: &Limit2zeroParams,
+        midi: &[(ParamPtr, f32)],
+    ) -> &[(ParamPtr, f32)] {
+        self.sync(params);
+
+        let overridden = |ptr: ParamPtr| midi.iter().find(|(param, _)| *param == ptr);
+        let knobs = params
+            .macros
+            .knobs()
+            .map(|knob| match overridden(knob.as_ptr()) {
+                Some((_, value)) => knob.preview_plain(*value),
+                None => knob.value(),
+            });
+
+        self.values.clear();
+        self.values.extend(
+            self.mapped
+                .iter()
+                .filter(|target| overridden(target.param).is_none())
+                .map(|target| (target.param, target.normalized(knobs[target.knob]))),
+        );
+        self.values.extend_from_slice(midi);
+        &self.values
+    }
+
+    // picks up assignments made in the editor, the table holds no more
+    // targets than there are params so this stays within what was reserved
+    fn sync(&mut self, params: &Limit2zeroParams) {
+        let Ok(table) = params.macro_table.try_read() else {
+            return;
+        };
+        if table.version == self.version {
+            return;
+        }
+        self.version = table.version;
+        self.mapped.clear();
+        self.mapped.extend_from_slice(&table.targets);
+    }
+}
@@ -0,0 +1,209 @@
+use std::collections::VecDeque;
+use std::f32::consts::TAU;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::loudness::{LoudnessCapture, LoudnessSnapshot, BLOCK_MS};
+
+// how much recent input the classification looks at, in 100 ms blocks
+const WINDOW_BLOCKS: usize = 20;
+// classified again every 300 ms
+const CLASSIFY_BLOCKS: usize = 3;
+// onsets are looked for in steps this long
+const ONSET_MS: f32 = 5.0;
+// a step this much louder than the one before is an onset
+const ONSET_RISE: f64 = 4.0;
+// quieter than this says nothing about the material, the last guess stays
+const SILENCE_DB: f32 = -60.0;
+
+/// What the input sounds like, as far as the release is concerned.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Material {
+    Drums,
+    Voice,
+    FullMix,
+}
+
+impl Material {
+    const ALL: [Material; 3] = [Material::Drums, Material::Voice, Material::FullMix];
+
+    /// Hold and release in ms: short for drums so they keep their punch,
+    /// long for voice so it doesn't pump between syllables.
+    pub fn timing(&self) -> (f32, f32) {
+        match self {
+            Material::Drums => (5.0, 60.0),
+            Material::Voice => (20.0, 250.0),
+            Material::FullMix => (10.0, 150.0),
+        }
+    }
+}
+
+/// The auto release's guess, and the input the audio thread hands over for it.
+pub(crate) struct MaterialSnapshot {
+    input: LoudnessSnapshot,
+    // 0 until the first guess, the material's place in `Material::ALL` after it
+    material: AtomicU8,
+}
+
+impl Default for MaterialSnapshot {
+    fn default() -> Self {
+        Self {
+            input: LoudnessSnapshot::default(),
+            material: AtomicU8::new(0),
+        }
+    }
+}
+
+impl MaterialSnapshot {
+    pub fn material(&self) -> Option<Material> {
+        let index = self.material.load(Ordering::Relaxed) as usize;
+        index.checked_sub(1).map(|index| Material::ALL[index])
+    }
+
+    /// Collects the input on the audio thread, see [`LoudnessCapture`].
+    pub fn capture(&self, channels: usize, sample_rate: f32) -> LoudnessCapture {
+        LoudnessCapture::new(channels, sample_rate, &self.input)
+    }
+
+    pub fn push(&self, capture: &mut LoudnessCapture, frame: &[f32]) -> bool {
+        capture.push(frame, &self.input)
+    }
+}
+
+// what's measured on every block, on the channels summed to mono
+#[derive(Debug, Default, Clone, Copy)]
+struct BlockFeatures {
+    mean_square: f64,
+    // mean square of the difference between samples, which grows with
+    // frequency
+    diff_square: f64,
+    peak: f32,
+    onsets: usize,
+}
+
+/// Sorts the input into [`Material`]s from its crest factor, spectral
+/// centroid and how many transients it has, run as a background task.
+pub(crate) struct MaterialAnalyzer {
+    channels: usize,
+    sample_rate: f32,
+    blocks: VecDeque<BlockFeatures>,
+    since_classified: usize,
+    last_sample: f32,
+    last_step: f64,
+}
+
+impl Default for MaterialAnalyzer {
+    fn default() -> Self {
+        Self {
+            channels: 0,
+            sample_rate: 44100.0,
+            blocks: VecDeque::with_capacity(WINDOW_BLOCKS),
+            since_classified: 0,
+            last_sample: 0.0,
+            last_step: 0.0,
+        }
+    }
+}
+
+impl MaterialAnalyzer {
+    pub fn reset(&mut self, channels: usize, sample_rate: f32, snapshot: &MaterialSnapshot) {
+        self.channels = channels;
+        self.sample_rate = sample_rate;
+        self.blocks.clear();
+        self.since_classified = 0;
+        self.last_sample = 0.0;
+        self.last_step = 0.0;
+        snapshot.material.store(0, Ordering::Relaxed);
+    }
+
+    pub fn analyze(&mut self, snapshot: &MaterialSnapshot) {
+        if self.channels == 0 {
+            return;
+        }
+
+        let features = snapshot.input.read_block(|block| self.measure(block));
+        if self.blocks.len() >= WINDOW_BLOCKS {
+            self.blocks.pop_front();
+        }
+        self.blocks.push_back(features);
+
+        self.since_classified += 1;
+        if self.since_classified < CLASSIFY_BLOCKS {
+            return;
+        }
+        self.since_classified = 0;
+        if let Some(material) = self.classify() {
+            let index = Material::ALL
+                .iter()
+                .position(|m| *m == material)
+                .unwrap_or(0);
+            snapshot.material.store(index as u8 + 1, Ordering::Relaxed);
+        }
+    }
+
+    fn measure(&mut self, block: &[f32]) -> BlockFeatures {
+        let step_len = ((self.sample_rate * ONSET_MS / 1000.0) as usize).max(1);
+        let mut features = BlockFeatures::default();
+        let mut step = 0.0;
+        let mut step_filled = 0;
+        let mut frames = 0;
+
+        for frame in block.chunks_exact(self.channels) {
+            let sample = frame.iter().sum::<f32>() / self.channels as f32;
+            let diff = sample - self.last_sample;
+            self.last_sample = sample;
+
+            features.mean_square += (sample * sample) as f64;
+            features.diff_square += (diff * diff) as f64;
+            features.peak = features.peak.max(sample.abs());
+            frames += 1;
+
+            step += (sample * sample) as f64;
+            step_filled += 1;
+            if step_filled == step_len {
+                let loud_enough = step / step_len as f64 > db_to_power(SILENCE_DB);
+                if loud_enough && step > self.last_step * ONSET_RISE {
+                    features.onsets += 1;
+                }
+                self.last_step = step;
+                step = 0.0;
+                step_filled = 0;
+            }
+        }
+
+        if frames > 0 {
+            features.mean_square /= frames as f64;
+            features.diff_square /= frames as f64;
+        }
+        features
+    }
+
+    fn classify(&self) -> Option<Material> {
+        let blocks = self.blocks.len() as f64;
+        let mean_square = self.blocks.iter().map(|b| b.mean_square).sum::<f64>() / blocks;
+        if mean_square < db_to_power(SILENCE_DB) {
+            return None;
+        }
+        let diff_square = self.blocks.iter().map(|b| b.diff_square).sum::<f64>() / blocks;
+        let peak = self.blocks.iter().map(|b| b.peak).fold(0.0, f32::max);
+        let onsets: usize = self.blocks.iter().map(|b| b.onsets).sum();
+
+        let crest_db = 20.0 * peak.log10() - 10.0 * mean_square.log10() as f32;
+        // for a sine the difference's power over the signal's is
+        // (2 sin(pi f / sr))^2, close enough to (2 pi f / sr)^2 to turn
+        // around into a frequency
+        let centroid_hz = (diff_square / mean_square).sqrt() as f32 * self.sample_rate / TAU;
+        let onsets_per_second = onsets as f32 / (blocks as f32 * BLOCK_MS / 1000.0);
+
+        Some(if onsets_per_second >= 3.0 && crest_db >= 12.0 {
+            Material::Drums
+        } else if centroid_hz < 3000.0 && crest_db >= 9.0 {
+            Material::Voice
+        } else {
+            Material::FullMix
+        })
+    }
+}
+
+fn db_to_power(db: f32) -> f64 {
+    10f64.powf(db as f64 / 10.0)
+}
@@ -0,0 +1,81 @@
+use nih_plug::prelude::{ClapFeature, Vst3SubCategory};
+
+/// Shared by every plugin in the bundle.
+pub(crate) const VENDOR: &str = "Adamina Barx";
+pub(crate) const URL: &str = env!("CARGO_PKG_HOMEPAGE");
+pub(crate) const EMAIL: &str = "adaminabarx@gmail.com";
+pub(crate) const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// Reverse domain the CLAP IDs hang off. Still the template's placeholder, but
+// hosts key saved sessions on the full ID, so changing it orphans them.
+macro_rules! clap_id {
+    ($name:literal) => {
+        concat!("com.your-domain.", $name)
+    };
+}
+
+/// Everything a host uses to tell one plugin of the bundle from another.
+pub(crate) struct PluginInfo {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub clap_id: &'static str,
+    pub clap_features: &'static [ClapFeature],
+    /// Like the CLAP ID, this has to stay the same once released.
+    pub vst3_class_id: [u8; 16],
+    pub vst3_subcategories: &'static [Vst3SubCategory],
+}
+
+pub(crate) const LIMIT2ZERO: PluginInfo = PluginInfo {
+    name: "limit2zero",
+    description: "basic limiter",
+    clap_id: clap_id!("limit2zero"),
+    clap_features: &[ClapFeature::AudioEffect, ClapFeature::Stereo],
+    vst3_class_id: *b"Exactly16Chars!!",
+    vst3_subcategories: &[Vst3SubCategory::Fx, Vst3SubCategory::Dynamics],
+};
+
+pub(crate) const CLIP2ZERO: PluginInfo = PluginInfo {
+    name: "clip2zero",
+    description: "basic clipper",
+    clap_id: clap_id!("clip2zero"),
+    clap_features: &[
+        ClapFeature::AudioEffect,
+        ClapFeature::Stereo,
+        ClapFeature::Distortion,
+    ],
+    vst3_class_id: *b"Clip2zeroClipper",
+    vst3_subcategories: &[Vst3SubCategory::Fx, Vst3SubCategory::Distortion],
+};
+
+pub(crate) const GATE2ZERO: PluginInfo = PluginInfo {
+    name: "gate2zero",
+    description: "gate with shaped attack and release",
+    clap_id: clap_id!("gate2zero"),
+    clap_features: &[
+        ClapFeature::AudioEffect,
+        ClapFeature::Stereo,
+        ClapFeature::Gate,
+    ],
+    vst3_class_id: *b"Gate2zeroGateFx!",
+    vst3_subcategories: &[Vst3SubCategory::Fx, Vst3SubCategory::Dynamics],
+};
+
+/// Implements `ClapPlugin` and `Vst3Plugin` for a plugin from its
+/// [`PluginInfo`], which is all the export macros need beyond `Plugin`.
+macro_rules! impl_formats {
+    ($plugin:ty, $info:expr) => {
+        impl ClapPlugin for $plugin {
+            const CLAP_ID: &'static str = $info.clap_id;
+            const CLAP_DESCRIPTION: Option<&'static str> = Some($info.description);
+            const CLAP_MANUAL_URL: Option<&'static str> = Some(Self::URL);
+            const CLAP_SUPPORT_URL: Option<&'static str> = None;
+            const CLAP_FEATURES: &'static [ClapFeature] = $info.clap_features;
+        }
+
+        impl Vst3Plugin for $plugin {
+            const VST3_CLASS_ID: [u8; 16] = $info.vst3_class_id;
+            const VST3_SUBCATEGORIES: &'static [Vst3SubCategory] = $info.vst3_subcategories;
+        }
+    };
+}
+pub(crate) use impl_formats;
@@ -0,0 +1,199 @@
+use nih_plug::prelude::{ParamPtr, Params};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::Ordering;
+
+use crate::Limit2zeroParams;
+
+const CCS: usize = 128;
+/// What [`Limit2zeroParams::midi_learned`] holds while no CC came in to learn.
+pub(crate) const NO_CC: u8 = u8::MAX;
+
+/// Which MIDI CC controls which param, by CC number and param ID. Stored in the
+/// plugin state so a controller setup survives reloading the session.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct MidiMap(BTreeMap<u8, String>);
+
+impl MidiMap {
+    pub fn cc_for(&self, id: &str) -> Option<u8> {
+        self.0
+            .iter()
+            .find(|(_, param)| param.as_str() == id)
+            .map(|(cc, _)| *cc)
+    }
+
+    // one CC per param and one param per CC, learning again moves it
+    pub fn learn(&mut self, cc: u8, id: String) {
+        self.forget(&id);
+        self.0.insert(cc, id);
+    }
+
+    pub fn forget(&mut self, id: &str) {
+        self.0.retain(|_, param| param != id);
+    }
+}
+
+/// The mapping worked out to the params themselves, by CC number, for the
+/// audio thread. A fixed size so handing it over never allocates.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CcTable([Option<ParamPtr>; CCS]);
+
+// the pointers only point into the plugin's own params
+unsafe impl Send for CcTable {}
+unsafe impl Sync for CcTable {}
+
+impl Default for CcTable {
+    fn default() -> Self {
+        Self([None; CCS])
+    }
+}
+
+impl CcTable {
+    /// Works the table out again from the mapping. Goes through the param
+    /// map, so only ever from the editor or `initialize`.
+    pub fn publish(params: &Limit2zeroParams) {
+        let param_map = params.param_map();
+        let mut table = Self::default();
+        for (cc, id) in params.midi_map.read().unwrap().0.iter() {
+            let param = param_map.iter().find(|(param_id, _, _)| param_id == id);
+            if let (Some(slot), Some((_, ptr, _))) = (table.0.get_mut(*cc as usize), param) {
+                *slot = Some(*ptr);
+            }
+        }
+        *params.midi_table.write().unwrap() = table;
+    }
+
+    /// Maps the CC that came in while a param was waiting for one, if any
+    /// did. Called from the editor, the audio thread only notes the CC.
+    pub fn finish_learning(params: &Limit2zeroParams) {
+        let cc = params.midi_learned.swap(NO_CC, Ordering::Relaxed);
+        if cc == NO_CC {
+            return;
+        }
+        let Some(id) = params.midi_learn.write().unwrap().take() else {
+            return;
+        };
+        params.midi_map.write().unwrap().learn(cc, id);
+        Self::publish(params);
+    }
+}
+
+// a CC (or an OSC message) that moved a param, it wins over the param's own
+// value until that changes again from the host or the editor
+struct Override {
+    param: ParamPtr,
+    value: f32,
+    base: f32,
+}
+
+/// Audio thread side of the MIDI mapping, and of OSC with the `osc` feature.
+/// Hosts don't let plugins automate their own params from the audio thread,
+/// so mapped CCs override the param values the limiter reads instead.
+#[derive(Default)]
+pub(crate) struct MidiControl {
+    table: CcTable,
+    overrides: Vec<Override>,
+    values: Vec<(ParamPtr, f32)>,
+}
+
+// the pointers only point into the plugin's own params
+unsafe impl Send for MidiControl {}
+
+impl MidiControl {
+    /// Sizes everything that holds params up front, so neither a CC nor an
+    /// OSC message allocates on the audio thread.
+    pub fn reserve(&mut self, params: &Limit2zeroParams) {
+        let param_map = params.param_map();
+        // there's at most one override per param
+        self.overrides.reserve(param_map.len());
+        self.values.reserve(param_map.len());
+    }
+
+    pub fn receive(&mut self, params: &Limit2zeroParams, cc: u8, value: f32) {
+        // the locks are only ever held for a moment by the editor, rather
+        // than waiting on them this goes by the table it had
+        if let Ok(learning) = params.midi_learn.try_read() {
+            if learning.is_some() {
+                params.midi_learned.store(cc, Ordering::Relaxed);
+            }
+        }
+        if let Ok(table) = params.midi_table.try_read() {
+            self.table = *table;
+        }
+
+        if let Some(param) = self.table.0.get(cc as usize).copied().flatten() {
+            self.set(param, value);
+        }
+    }
+
+    /// Overrides a param with a normalized value, also for remote control
+    /// over OSC.
+    pub fn set(&mut self, param: ParamPtr, value: f32) {
+        let base = unsafe { param.unmodulated_normalized_value() };
+        match self.overrides.iter_mut().find(|o| o.param == param) {
+            Some(o) => {
+                o.value = value;
+                o.base = base;
+            }
+            None => self.overrides.push(Override { param, value, base }),
+        }
+    }
+
+    /// The params currently overridden and their normalized values, for
+    /// [`crate::Settings::with_overrides`].
+    pub fn overrides(&mut self) -> &[(ParamPtr, f32)] {
+        self.overrides
+            .retain(|o| unsafe { o.param.unmodulated_normalized_value() == o.base });
+
+        self.values.clear();
+        self.values
+            .extend(self.overrides.iter().map(|o| (o.param, o.value)));
+        &self.values
+    }
+}
+
+// about 100 updates a second, as fast as a CC is any use for metering
+const GR_OUT_HZ: f32 = 100.0;
+// the reduction a CC value of 1 stands for, in dB
+const GR_OUT_RANGE_DB: f32 = 24.0;
+
+/// The gain reduction going out as a CC, the deepest since the last one so a
+/// peak between updates still shows. Only sent when it changed.
+pub(crate) struct GrOut {
+    interval: usize,
+    elapsed: usize,
+    deepest: f32,
+    last: Option<f32>,
+}
+
+impl GrOut {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            interval: (sample_rate / GR_OUT_HZ).round().max(1.0) as usize,
+            elapsed: 0,
+            deepest: 0.0,
+            last: None,
+        }
+    }
+
+    /// Takes one frame's reduction in dB, gives the CC value to send when
+    /// one is due, 0 with no reduction and 1 at `GR_OUT_RANGE_DB` or more.
+    pub fn push(&mut self, reduction_db: f32) -> Option<f32> {
+        self.deepest = self.deepest.min(reduction_db);
+        self.elapsed += 1;
+        if self.elapsed < self.interval {
+            return None;
+        }
+
+        let value = (-self.deepest / GR_OUT_RANGE_DB).clamp(0.0, 1.0);
+        // to the CC's 128 steps, anything finer would go out unchanged
+        let value = (value * 127.0).round() / 127.0;
+        self.elapsed = 0;
+        self.deepest = 0.0;
+        if self.last == Some(value) {
+            return None;
+        }
+        self.last = Some(value);
+        Some(value)
+    }
+}
@@ -0,0 +1,305 @@
+use atomic_float::AtomicF32;
+use nih_plug::prelude::*;
+use rosc::{OscMessage, OscPacket, OscType};
+use std::cell::UnsafeCell;
+use std::net::{IpAddr, SocketAddr, UdpSocket};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::Limit2zeroParams;
+
+const PREFIX: &str = "/limit2zero";
+// how often subscribers get the gain reduction
+const TELEMETRY_INTERVAL: Duration = Duration::from_millis(33);
+// how many ports from the configured one on are tried, it's most likely
+// taken by another instance
+const FALLBACK_PORTS: u16 = 16;
+// values waiting for the audio thread, a flood past that is dropped
+const QUEUE_LEN: usize = 256;
+
+/// Remote control over OSC, on UDP at the `osc_address` and `osc_port` from
+/// the user defaults, or the first free port after it:
+///
+/// - `/limit2zero/param/<id> <f>` sets a param to a normalized value
+/// - `/limit2zero/param/<id>` replies with its normalized value and display
+///   text, the one set over OSC while that still stands
+/// - `/limit2zero/subscribe` and `/limit2zero/unsubscribe` start and stop
+///   `/limit2zero/gr <left> <right>` messages with the gain reduction in dB
+///
+/// Values set this way override the param like a mapped MIDI CC does, hosts
+/// don't let a plugin automate its own params.
+pub(crate) struct OscServer {
+    shared: Arc<Shared>,
+    thread: Option<JoinHandle<()>>,
+}
+
+// a value set over OSC, with the param's own value at the time. Like the
+// audio thread's override, it stands until the param changes from that.
+struct Sent {
+    param: ParamPtr,
+    value: f32,
+    base: f32,
+}
+
+struct Shared {
+    running: AtomicBool,
+    pending: ParamQueue,
+    reduction: [AtomicF32; 2],
+}
+
+/// Params and normalized values from the server thread to the audio thread,
+/// without locking or allocating. One thread pushes and one drains, like
+/// [`crate::telemetry::MeterQueue`].
+struct ParamQueue {
+    slots: Box<[UnsafeCell<Option<(ParamPtr, f32)>>]>,
+    // the next slot to read, only moved by the audio thread
+    head: AtomicUsize,
+    // the next slot to write, only moved by the server thread
+    tail: AtomicUsize,
+}
+
+// a slot is only touched by the side that owns it between head and tail, and
+// the pointers only point into the plugin's own params
+unsafe impl Send for ParamQueue {}
+unsafe impl Sync for ParamQueue {}
+
+impl Default for ParamQueue {
+    fn default() -> Self {
+        Self {
+            // one slot stays empty to tell a full queue from an empty one
+            slots: (0..QUEUE_LEN + 1).map(|_| UnsafeCell::new(None)).collect(),
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl ParamQueue {
+    /// Server thread side. Drops the value when the audio thread has fallen
+    /// behind and the queue is full.
+    fn push(&self, param: ParamPtr, value: f32) {
+        let tail = self.tail.load(Ordering::Relaxed);
+        let next = (tail + 1) % self.slots.len();
+        if next == self.head.load(Ordering::Acquire) {
+            return;
+        }
+        unsafe { *self.slots[tail].get() = Some((param, value)) };
+        self.tail.store(next, Ordering::Release);
+    }
+
+    /// Audio thread side, everything pushed since the last call, oldest first.
+    fn drain(&self, mut set: impl FnMut(ParamPtr, f32)) {
+        let mut head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Acquire);
+        while head != tail {
+            if let Some((param, value)) = unsafe { *self.slots[head].get() } {
+                set(param, value);
+            }
+            head = (head + 1) % self.slots.len();
+        }
+        self.head.store(head, Ordering::Release);
+    }
+}
+
+impl OscServer {
+    /// Listens on `address`, which the user defaults leave on localhost.
+    pub fn start(params: Arc<Limit2zeroParams>, address: IpAddr, port: u16) -> Option<Self> {
+        let socket = match bind(address, port) {
+            Ok(socket) => socket,
+            Err(err) => {
+                nih_error!("couldn't start the OSC server on {address} from port {port}: {err}");
+                return None;
+            }
+        };
+        if let Ok(local) = socket.local_addr() {
+            nih_log!("OSC server listening on {local}");
+        }
+        // wakes the thread up for telemetry and to notice it should stop
+        socket.set_read_timeout(Some(TELEMETRY_INTERVAL)).ok()?;
+
+        let shared = Arc::new(Shared {
+            running: AtomicBool::new(true),
+            pending: ParamQueue::default(),
+            reduction: [AtomicF32::new(0.0), AtomicF32::new(0.0)],
+        });
+        let thread = std::thread::Builder::new()
+            .name(String::from("limit2zero-osc"))
+            .spawn({
+                let shared = shared.clone();
+                move || serve(socket, params, shared)
+            })
+            .ok()?;
+
+        Some(Self {
+            shared,
+            thread: Some(thread),
+        })
+    }
+
+    /// Hands the values received since the last call to `set`, already
+    /// worked out to the params. Called from the audio thread.
+    pub fn drain(&self, set: impl FnMut(ParamPtr, f32)) {
+        self.shared.pending.drain(set);
+    }
+
+    /// The lowest gain reduction of the block, per channel.
+    pub fn store_reduction(&self, channel: usize, reduction: f32) {
+        self.shared.reduction[channel].fetch_min(reduction, Ordering::Relaxed);
+    }
+}
+
+impl Drop for OscServer {
+    fn drop(&mut self) {
+        self.shared.running.store(false, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+// the configured port, or the first free one after it
+fn bind(address: IpAddr, port: u16) -> std::io::Result<UdpSocket> {
+    let mut result = UdpSocket::bind((address, port));
+    for fallback in (1..FALLBACK_PORTS).filter_map(|offset| port.checked_add(offset)) {
+        if result.is_ok() {
+            break;
+        }
+        result = UdpSocket::bind((address, fallback));
+    }
+    result
+}
+
+fn serve(socket: UdpSocket, params: Arc<Limit2zeroParams>, shared: Arc<Shared>) {
+    let param_map = params.param_map();
+    let mut subscribers: Vec<SocketAddr> = Vec::new();
+    let mut sent: Vec<Sent> = Vec::new();
+    let mut last_telemetry = Instant::now();
+    let mut buf = [0; rosc::decoder::MTU];
+
+    while shared.running.load(Ordering::Relaxed) {
+        if let Ok((len, from)) = socket.recv_from(&mut buf) {
+            match rosc::decoder::decode_udp(&buf[..len]) {
+                Ok((_, packet)) => {
+                    for msg in messages(packet) {
+                        handle(
+                            &socket,
+                            from,
+                            msg,
+                            &param_map,
+                            &shared,
+                            &mut subscribers,
+                            &mut sent,
+                        );
+                    }
+                }
+                Err(err) => nih_error!("bad OSC packet from {from}: {err:?}"),
+            }
+        }
+
+        if last_telemetry.elapsed() >= TELEMETRY_INTERVAL {
+            last_telemetry = Instant::now();
+            let reduction = [
+                shared.reduction[0].swap(0.0, Ordering::Relaxed),
+                shared.reduction[1].swap(0.0, Ordering::Relaxed),
+            ];
+            if !subscribers.is_empty() {
+                let msg = OscMessage {
+                    addr: format!("{PREFIX}/gr"),
+                    args: reduction.into_iter().map(OscType::Float).collect(),
+                };
+                for subscriber in &subscribers {
+                    send(&socket, *subscriber, msg.clone());
+                }
+            }
+        }
+    }
+}
+
+// bundles are flattened, their time tags are ignored
+fn messages(packet: OscPacket) -> Vec<OscMessage> {
+    match packet {
+        OscPacket::Message(msg) => vec![msg],
+        OscPacket::Bundle(bundle) => bundle.content.into_iter().flat_map(messages).collect(),
+    }
+}
+
+fn handle(
+    socket: &UdpSocket,
+    from: SocketAddr,
+    msg: OscMessage,
+    param_map: &[(String, ParamPtr, String)],
+    shared: &Shared,
+    subscribers: &mut Vec<SocketAddr>,
+    sent: &mut Vec<Sent>,
+) {
+    let Some(path) = msg.addr.strip_prefix(PREFIX) else {
+        return;
+    };
+
+    match path {
+        "/subscribe" => {
+            if !subscribers.contains(&from) {
+                subscribers.push(from);
+            }
+        }
+        "/unsubscribe" => subscribers.retain(|subscriber| *subscriber != from),
+        _ => {
+            let Some(id) = path.strip_prefix("/param/") else {
+                return;
+            };
+            let Some((_, param, _)) = param_map.iter().find(|(param_id, _, _)| param_id == id)
+            else {
+                return;
+            };
+
+            match msg.args.first() {
+                Some(arg) => {
+                    let value = match arg {
+                        OscType::Float(value) => *value,
+                        OscType::Double(value) => *value as f32,
+                        OscType::Int(value) => *value as f32,
+                        _ => return,
+                    };
+                    let value = value.clamp(0.0, 1.0);
+                    shared.pending.push(*param, value);
+                    let base = unsafe { param.unmodulated_normalized_value() };
+                    sent.retain(|sent| sent.param != *param);
+                    sent.push(Sent {
+                        param: *param,
+                        value,
+                        base,
+                    });
+                }
+                None => {
+                    // what the limiter goes by, which is still what was
+                    // set here until the host or the editor move the param
+                    let own = unsafe { param.unmodulated_normalized_value() };
+                    let set = sent
+                        .iter()
+                        .find(|sent| sent.param == *param && sent.base == own)
+                        .map(|sent| sent.value);
+                    let (value, text) = unsafe {
+                        let value = set.unwrap_or_else(|| param.modulated_normalized_value());
+                        (value, param.normalized_value_to_string(value, true))
+                    };
+                    let reply = OscMessage {
+                        addr: msg.addr,
+                        args: vec![OscType::Float(value), OscType::String(text)],
+                    };
+                    send(socket, from, reply);
+                }
+            }
+        }
+    }
+}
+
+fn send(socket: &UdpSocket, to: SocketAddr, msg: OscMessage) {
+    match rosc::encoder::encode(&OscPacket::Message(msg)) {
+        Ok(bytes) => {
+            let _ = socket.send_to(&bytes, to);
+        }
+        Err(err) => nih_error!("couldn't encode an OSC message: {err:?}"),
+    }
+}
@@ -0,0 +1,94 @@
+use std::cell::UnsafeCell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// ~0.5 s of frames at the rate the audio thread sends them
+const METER_QUEUE_LEN: usize = 64;
+
+/// The meters over a stretch of samples, per channel: the loudest level in
+/// dB before and after the limiter, and the deepest reduction after and
+/// before the stereo link. Starts out at 0 dB, like the meters always have.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub(crate) struct MeterFrame {
+    pub pre: [f32; 2],
+    pub post: [f32; 2],
+    pub env: [f32; 2],
+    pub channel_env: [f32; 2],
+}
+
+impl MeterFrame {
+    pub fn add(&mut self, channel: usize, pre: f32, post: f32, env: f32, channel_env: f32) {
+        self.pre[channel] = self.pre[channel].max(pre);
+        self.post[channel] = self.post[channel].max(post);
+        self.env[channel] = self.env[channel].min(env);
+        self.channel_env[channel] = self.channel_env[channel].min(channel_env);
+    }
+
+    pub fn merge(&mut self, other: &MeterFrame) {
+        for channel in 0..2 {
+            self.add(
+                channel,
+                other.pre[channel],
+                other.post[channel],
+                other.env[channel],
+                other.channel_env[channel],
+            );
+        }
+    }
+}
+
+/// Hands whole [`MeterFrame`]s from the audio thread to the editor without
+/// locking, so the editor never sees a level from one frame next to a
+/// reduction from another. One thread pushes and one drains, nothing else.
+pub(crate) struct MeterQueue {
+    slots: Box<[UnsafeCell<MeterFrame>]>,
+    // the next slot to read, only moved by the editor
+    head: AtomicUsize,
+    // the next slot to write, only moved by the audio thread
+    tail: AtomicUsize,
+}
+
+// a slot is only touched by the side that owns it between head and tail
+unsafe impl Sync for MeterQueue {}
+
+impl Default for MeterQueue {
+    fn default() -> Self {
+        Self {
+            // one slot stays empty to tell a full queue from an empty one
+            slots: (0..METER_QUEUE_LEN + 1)
+                .map(|_| UnsafeCell::new(MeterFrame::default()))
+                .collect(),
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl MeterQueue {
+    /// Audio thread side. False when the editor has fallen behind and the
+    /// queue is full, then the frame should be merged into the next one.
+    pub fn push(&self, frame: MeterFrame) -> bool {
+        let tail = self.tail.load(Ordering::Relaxed);
+        let next = (tail + 1) % self.slots.len();
+        if next == self.head.load(Ordering::Acquire) {
+            return false;
+        }
+        unsafe { *self.slots[tail].get() = frame };
+        self.tail.store(next, Ordering::Release);
+        true
+    }
+
+    /// Editor side, everything sent since the last call merged into one
+    /// frame. A default frame when nothing came in.
+    pub fn drain(&self) -> MeterFrame {
+        let mut head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Acquire);
+
+        let mut merged = MeterFrame::default();
+        while head != tail {
+            merged.merge(unsafe { &*self.slots[head].get() });
+            head = (head + 1) % self.slots.len();
+        }
+        self.head.store(head, Ordering::Release);
+        merged
+    }
+}
@@ -0,0 +1,126 @@
+//! Renders a few canonical signals through the limiter and compares them with
+//! the traces in `tests/golden`. After a change that's meant to alter the
+//! sound, check the difference and write new traces with
+//! `UPDATE_GOLDEN=1 cargo test --test golden`.
+
+use limit2zero::engine::signal::{impulse_train, sine_burst, square};
+use limit2zero::engine::{db_to_gain, render, Render, Settings};
+use std::path::PathBuf;
+
+const SAMPLE_RATE: f32 = 48000.0;
+const LEN: usize = 1200;
+// odd on purpose, so the block boundaries move around the lookahead accuracy
+const BLOCK_SIZE: usize = 100;
+// leaves room for different float math across platforms, not for DSP changes
+const TOLERANCE: f32 = 1e-5;
+
+fn settings() -> Settings {
+    Settings {
+        lookahead_ms: 1.0,
+        lookahead_accuracy: 3,
+        attack_amt: 1.0,
+        hold_ms: 1.0,
+        release_ms: 10.0,
+        stereo_link: 0.5,
+        ..Settings::default()
+    }
+}
+
+// a single sample 6 dB over the ceiling on the left, silence on the right
+fn impulse() -> [Vec<f32>; 2] {
+    [impulse_train(100, LEN, db_to_gain(6.0), LEN), vec![0.0; LEN]]
+}
+
+// 10 ms of 1 kHz at +6 dB after 100 samples of silence, the right channel 6 dB lower
+fn tone_burst() -> [Vec<f32>; 2] {
+    let left = sine_burst(SAMPLE_RATE, 1000.0, db_to_gain(6.0), 100, 480, LEN);
+    let right = left.iter().map(|sample| sample * db_to_gain(-6.0)).collect();
+    [left, right]
+}
+
+// 200 Hz at +3 dB, with the right channel inverted
+fn square_wave() -> [Vec<f32>; 2] {
+    let left = square(SAMPLE_RATE, 200.0, db_to_gain(3.0), LEN);
+    let right = left.iter().map(|sample| -sample).collect();
+    [left, right]
+}
+
+fn golden_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("golden")
+        .join(format!("{name}.txt"))
+}
+
+// one line per sample: the output and then the reduction of every channel
+fn to_text(render: &Render) -> String {
+    let mut text = String::new();
+    for i in 0..render.output[0].len() {
+        let line: Vec<String> = render
+            .output
+            .iter()
+            .chain(&render.reduction)
+            .map(|trace| trace[i].to_string())
+            .collect();
+        text.push_str(&line.join(" "));
+        text.push('\n');
+    }
+    text
+}
+
+fn check(name: &str, input: [Vec<f32>; 2]) {
+    let input: Vec<&[f32]> = input.iter().map(Vec::as_slice).collect();
+    let render = render(&input, SAMPLE_RATE, &settings(), BLOCK_SIZE);
+    let path = golden_path(name);
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, to_text(&render)).unwrap();
+        return;
+    }
+
+    let golden = std::fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("couldn't read {}: {err}", path.display()));
+    let golden: Vec<Vec<f32>> = golden
+        .lines()
+        .map(|line| line.split(' ').map(|value| value.parse().unwrap()).collect())
+        .collect();
+    assert_eq!(golden.len(), LEN, "{name}: wrong number of samples");
+
+    let traces: Vec<&Vec<f32>> = render.output.iter().chain(&render.reduction).collect();
+    for (i, line) in golden.iter().enumerate() {
+        for (trace, expected) in traces.iter().zip(line) {
+            let actual = trace[i];
+            assert!(
+                (actual - expected).abs() <= TOLERANCE,
+                "{name}: sample {i} is {actual}, expected {expected}"
+            );
+        }
+    }
+}
+
+#[test]
+fn golden_impulse() {
+    check("impulse", impulse());
+}
+
+#[test]
+fn golden_tone_burst() {
+    check("tone_burst", tone_burst());
+}
+
+#[test]
+fn golden_square() {
+    check("square", square_wave());
+}
+
+// whatever the traces say, nothing gets out over the ceiling
+#[test]
+fn renders_stay_under_the_ceiling() {
+    for input in [impulse(), tone_burst(), square_wave()] {
+        let input: Vec<&[f32]> = input.iter().map(Vec::as_slice).collect();
+        let render = render(&input, SAMPLE_RATE, &settings(), BLOCK_SIZE);
+        for sample in render.output.iter().flatten() {
+            assert!(sample.abs() <= 1.0 + TOLERANCE, "{sample} is over the ceiling");
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! Settings and sample rates that once broke the limiter's filters. Whatever
+//! goes in, what comes out has to be finite and under the ceiling.
+
+use limit2zero::engine::signal::{pink_noise, sweep};
+use limit2zero::engine::{db_to_gain, render, Settings, Weighting};
+
+// where the A curve's top pole is past Nyquist
+const LOW_SAMPLE_RATES: [f32; 2] = [22050.0, 24000.0];
+const LEN: usize = 4800;
+const BLOCK_SIZE: usize = 100;
+// the float maths of the gain stages, not a real overshoot
+const CEILING: f32 = 1.0 + 1e-5;
+
+fn settings() -> Settings {
+    Settings {
+        lookahead_ms: 1.0,
+        attack_amt: 1.0,
+        release_ms: 10.0,
+        ..Settings::default()
+    }
+}
+
+// up to Nyquist on the left and noise on the right, both well over the ceiling
+fn input(sample_rate: f32) -> [Vec<f32>; 2] {
+    [
+        sweep(sample_rate, 20.0, sample_rate * 0.5, db_to_gain(12.0), LEN),
+        pink_noise(1, db_to_gain(12.0), LEN),
+    ]
+}
+
+fn assert_holds(input: &[Vec<f32>], sample_rate: f32, settings: &Settings) {
+    let input: Vec<&[f32]> = input.iter().map(Vec::as_slice).collect();
+    let render = render(&input, sample_rate, settings, BLOCK_SIZE);
+    for sample in render.output.iter().flatten() {
+        assert!(
+            sample.is_finite() && sample.abs() <= CEILING,
+            "{sample} out at {sample_rate} Hz with {settings:?}"
+        );
+    }
+}
+
+#[test]
+fn every_weighting_holds_at_low_sample_rates() {
+    for sample_rate in LOW_SAMPLE_RATES {
+        for detector_weighting in [Weighting::Flat, Weighting::A, Weighting::K] {
+            for detector_high_db in [-12.0, 0.0, 12.0] {
+                let settings = Settings {
+                    detector_weighting,
+                    detector_high_db,
+                    ..settings()
+                };
+                assert_holds(&input(sample_rate), sample_rate, &settings);
+            }
+        }
+    }
+}
+
+// finite, but the detector's filters overflow on them
+fn near_the_float_limit() -> [Vec<f32>; 2] {
+    let spikes = |sign: f32| {
+        (0..LEN)
+            .map(|i| {
+                if i % 50 < 3 {
+                    sign * f32::MAX * 0.9
+                } else {
+                    0.5
+                }
+            })
+            .collect()
+    };
+    [spikes(1.0), spikes(-1.0)]
+}
+
+#[test]
+fn huge_samples_dont_get_stuck_in_the_detector() {
+    for detector_weighting in [Weighting::Flat, Weighting::A, Weighting::K] {
+        let settings = Settings {
+            detector_weighting,
+            detector_tilt_db: 6.0,
+            detector_high_db: 12.0,
+            ..settings()
+        };
+        assert_holds(&near_the_float_limit(), 48000.0, &settings);
+    }
+}
+
+#[test]
+fn listening_to_huge_samples_stays_finite() {
+    let settings = Settings {
+        detector_listen: true,
+        width: 2.0,
+        ..settings()
+    };
+    let input = near_the_float_limit();
+    let input: Vec<&[f32]> = input.iter().map(Vec::as_slice).collect();
+    let render = render(&input, 48000.0, &settings, BLOCK_SIZE);
+    for sample in render.output.iter().flatten() {
+        assert!(sample.is_finite(), "{sample} out while listening");
+    }
+}
+
+#[test]
+fn widening_stays_under_the_ceiling() {
+    let settings = Settings {
+        stereo_link: 1.0,
+        width: 2.0,
+        ..settings()
+    };
+    // the sides apart, so the linked limiter lets the quieter one through
+    let input = [
+        sweep(48000.0, 20.0, 20000.0, db_to_gain(12.0), LEN),
+        pink_noise(1, db_to_gain(-6.0), LEN),
+    ];
+    assert_holds(&input, 48000.0, &settings);
+}
@@ -0,0 +1,65 @@
+use anyhow::{bail, Context};
+use std::path::Path;
+use std::process::Command;
+
+const PLUGIN: &str = "limit2zero";
+
+/// Builds an AUv2 around the CLAP export with clap-wrapper, for Logic and the
+/// other hosts that only load AUs. Needs macOS, Xcode's command line tools
+/// and CMake, clap-wrapper itself is fetched by CMake. Other arguments, like
+/// `--release`, are passed on to the CLAP bundle.
+pub fn bundle(args: Vec<String>) -> anyhow::Result<()> {
+    if !cfg!(target_os = "macos") {
+        bail!("Audio Units can only be built on macOS");
+    }
+
+    let release = args.iter().any(|arg| arg == "--release");
+    nih_plug_xtask::main_with_args(
+        "cargo xtask",
+        ["bundle", PLUGIN].into_iter().map(String::from).chain(args),
+    )?;
+
+    let bundled = Path::new("target").join("bundled");
+    let clap = bundled.join(format!("{PLUGIN}.clap"));
+    let build_dir = Path::new("target").join("au");
+    let config = if release { "Release" } else { "Debug" };
+
+    run(Command::new("cmake")
+        .arg("-S")
+        .arg(Path::new("xtask").join("au"))
+        .arg("-B")
+        .arg(&build_dir)
+        .arg(format!("-DCMAKE_BUILD_TYPE={config}"))
+        .arg(format!("-DCLAP_PLUGIN={}", clap.canonicalize()?.display())))?;
+    run(Command::new("cmake")
+        .arg("--build")
+        .arg(&build_dir)
+        .args(["--config", config]))?;
+
+    let component = format!("{PLUGIN}.component");
+    let built = build_dir.join(config).join(&component);
+    let built = if built.exists() {
+        built
+    } else {
+        // single config generators don't add the config directory
+        build_dir.join(&component)
+    };
+    let target = bundled.join(&component);
+    if target.exists() {
+        std::fs::remove_dir_all(&target)?;
+    }
+    run(Command::new("cp").arg("-R").arg(&built).arg(&target))?;
+
+    eprintln!("Created an AUv2 bundle at '{}'", target.display());
+    Ok(())
+}
+
+fn run(command: &mut Command) -> anyhow::Result<()> {
+    let status = command
+        .status()
+        .with_context(|| format!("Could not run {command:?}"))?;
+    if !status.success() {
+        bail!("{command:?} failed with {status}");
+    }
+    Ok(())
+}
@@ -1,3 +1,11 @@
+mod au;
+
 fn main() -> nih_plug_xtask::Result<()> {
+    // everything but the AU goes through nih-plug's bundler
+    let mut args = std::env::args().skip(1);
+    if args.next().as_deref() == Some("bundle-au") {
+        return au::bundle(args.collect());
+    }
+
     nih_plug_xtask::main()
 }